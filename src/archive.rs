@@ -0,0 +1,467 @@
+//! メッセージアーカイブモジュール
+//!
+//! このモジュールは、ツールが既読にしたメッセージの本文・送信者・送信日時を
+//! ローカルのSQLiteデータベースに保存し、後から参照するための機能を提供します。
+//! メッセージはルームIDとメッセージIDの組で一意に保存されるため、
+//! 同じメッセージを何度保存しても重複しません。
+//!
+//! 本文はtrigramトークナイザーによる全文検索インデックスにも登録されるため、
+//! 単語の区切りがない日本語の本文も部分一致で検索できます。
+
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::ToSql;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use crate::error::Error;
+use crate::models::{Account, Message, Room};
+
+/// trigramトークナイザーで全文検索できる語の最小文字数です。
+///
+/// これより短い語は本文の部分一致（`LIKE`）で検索します。
+const MIN_FTS_TERM_CHARS: usize = 3;
+
+/// アーカイブを検索する条件です。
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// 本文に含まれる語（空白区切りの全ての語を含むメッセージが対象）
+    pub text: String,
+    /// ルームID
+    pub room_id: Option<i32>,
+    /// 送信者のアカウントID、または名前の一部
+    pub sender: Option<String>,
+    /// 送信日時の下限（この日時を含む）
+    pub since: Option<DateTime<Utc>>,
+    /// 送信日時の上限（この日時を含まない）
+    pub until: Option<DateTime<Utc>>,
+    /// 返す件数の上限（`0`の場合は制限なし）
+    pub limit: usize,
+}
+
+/// 検索に一致したメッセージです。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// ルームID
+    pub room_id: i32,
+    /// ルーム名
+    pub room_name: String,
+    /// メッセージ
+    pub message: Message,
+}
+
+impl SearchHit {
+    /// ChatworkのWebアプリでメッセージを開くURLを返します。
+    pub fn permalink(&self) -> String {
+        format!(
+            "https://www.chatwork.com/#!rid{}-{}",
+            self.room_id, self.message.message_id
+        )
+    }
+}
+
+/// SQLiteによるメッセージアーカイブです。
+pub struct Archive {
+    conn: Mutex<Connection>,
+}
+
+impl Archive {
+    /// 指定されたパスのアーカイブを開きます。
+    ///
+    /// データベースファイルやテーブルが存在しない場合は作成されます。
+    ///
+    /// # エラー
+    ///
+    /// ディレクトリの作成やデータベースの初期化に失敗した場合、`Error`を返します。
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS rooms (
+                room_id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS messages (
+                room_id INTEGER NOT NULL,
+                message_id TEXT NOT NULL,
+                account_id INTEGER NOT NULL,
+                account_name TEXT NOT NULL,
+                body TEXT NOT NULL,
+                send_time INTEGER NOT NULL,
+                archived_at INTEGER NOT NULL,
+                PRIMARY KEY (room_id, message_id)
+            );
+            CREATE INDEX IF NOT EXISTS messages_send_time ON messages (send_time);",
+        )?;
+
+        // 全文検索インデックスがなければ作成し、既存のメッセージを登録する
+        let has_index = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'messages_fts'",
+                [],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !has_index {
+            conn.execute_batch(
+                "CREATE VIRTUAL TABLE messages_fts USING fts5(
+                    body, room_id UNINDEXED, message_id UNINDEXED, tokenize = 'trigram'
+                );
+                INSERT INTO messages_fts (body, room_id, message_id)
+                    SELECT body, room_id, message_id FROM messages;",
+            )?;
+        }
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// ルームのメッセージを保存します。
+    ///
+    /// 既に保存されているメッセージは無視されます。ルーム名は最新のものに更新されます。
+    ///
+    /// # 戻り値
+    ///
+    /// 新たに保存したメッセージの数を返します。
+    ///
+    /// # エラー
+    ///
+    /// データベースへの書き込みに失敗した場合、`Error`を返します。
+    pub fn store(&self, room: &Room, messages: &[Message]) -> Result<usize, Error> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO rooms (room_id, name) VALUES (?1, ?2)
+             ON CONFLICT (room_id) DO UPDATE SET name = excluded.name",
+            params![room.room_id, room.name],
+        )?;
+
+        let archived_at = Utc::now().timestamp();
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO messages
+                 (room_id, message_id, account_id, account_name, body, send_time, archived_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            let mut index = tx.prepare(
+                "INSERT INTO messages_fts (body, room_id, message_id) VALUES (?1, ?2, ?3)",
+            )?;
+            for message in messages {
+                let count = stmt.execute(params![
+                    room.room_id,
+                    message.message_id,
+                    message.account.account_id,
+                    message.account.name,
+                    message.body,
+                    message.send_time,
+                    archived_at
+                ])?;
+                if count > 0 {
+                    index.execute(params![message.body, room.room_id, message.message_id])?;
+                }
+                inserted += count;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// 指定されたルームの保存済みメッセージを送信日時の古い順に返します。
+    ///
+    /// # エラー
+    ///
+    /// データベースの読み込みに失敗した場合、`Error`を返します。
+    pub fn room_messages(&self, room_id: i32) -> Result<Vec<Message>, Error> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(
+            "SELECT message_id, account_id, account_name, body, send_time
+             FROM messages WHERE room_id = ?1
+             ORDER BY send_time, message_id",
+        )?;
+        let messages = stmt
+            .query_map(params![room_id], |row| {
+                Ok(Message {
+                    message_id: row.get(0)?,
+                    account: Account {
+                        account_id: row.get(1)?,
+                        name: row.get(2)?,
+                    },
+                    body: row.get(3)?,
+                    send_time: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(messages)
+    }
+
+    /// 条件に一致するメッセージを送信日時の新しい順に返します。
+    ///
+    /// # エラー
+    ///
+    /// データベースの読み込みに失敗した場合、`Error`を返します。
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>, Error> {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+        let (fts_terms, short_terms): (Vec<&str>, Vec<&str>) = query
+            .text
+            .split_whitespace()
+            .partition(|term| term.chars().count() >= MIN_FTS_TERM_CHARS);
+        if !fts_terms.is_empty() {
+            conditions.push(
+                "(m.room_id, m.message_id) IN (SELECT room_id, message_id FROM messages_fts WHERE messages_fts MATCH ?)"
+                    .to_string(),
+            );
+            values.push(Box::new(fts_match_expression(&fts_terms)));
+        }
+        for term in short_terms {
+            conditions.push("m.body LIKE ? ESCAPE '\\'".to_string());
+            values.push(Box::new(format!("%{}%", escape_like(term))));
+        }
+        if let Some(room_id) = query.room_id {
+            conditions.push("m.room_id = ?".to_string());
+            values.push(Box::new(room_id));
+        }
+        if let Some(sender) = &query.sender {
+            conditions.push(
+                "(CAST(m.account_id AS TEXT) = ? OR m.account_name LIKE ? ESCAPE '\\')".to_string(),
+            );
+            values.push(Box::new(sender.clone()));
+            values.push(Box::new(format!("%{}%", escape_like(sender))));
+        }
+        if let Some(since) = query.since {
+            conditions.push("m.send_time >= ?".to_string());
+            values.push(Box::new(since.timestamp()));
+        }
+        if let Some(until) = query.until {
+            conditions.push("m.send_time < ?".to_string());
+            values.push(Box::new(until.timestamp()));
+        }
+
+        let mut sql = "SELECT m.room_id, COALESCE(r.name, ''), m.message_id, m.account_id,
+                    m.account_name, m.body, m.send_time
+             FROM messages m LEFT JOIN rooms r ON r.room_id = m.room_id"
+            .to_string();
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY m.send_time DESC, m.message_id DESC");
+        if query.limit > 0 {
+            sql.push_str(&format!(" LIMIT {}", query.limit));
+        }
+
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(&sql)?;
+        let hits = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                Ok(SearchHit {
+                    room_id: row.get(0)?,
+                    room_name: row.get(1)?,
+                    message: Message {
+                        message_id: row.get(2)?,
+                        account: Account {
+                            account_id: row.get(3)?,
+                            name: row.get(4)?,
+                        },
+                        body: row.get(5)?,
+                        send_time: row.get(6)?,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(hits)
+    }
+}
+
+/// 全文検索の語を、全ての語をフレーズとして含む`MATCH`式に変換します。
+fn fts_match_expression(terms: &[&str]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// `LIKE`のワイルドカード文字をエスケープします。
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn message(id: &str, body: &str, send_time: i64) -> Message {
+        Message {
+            message_id: id.to_string(),
+            account: Account {
+                account_id: 1,
+                name: "送信者".to_string(),
+            },
+            body: body.to_string(),
+            send_time,
+        }
+    }
+
+    #[test]
+    fn test_archive_store_deduplicates() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let archive = Archive::open(temp_dir.path().join("data/archive.sqlite3")).unwrap();
+        let room = Room {
+            room_id: 10,
+            name: "開発".to_string(),
+            ..Default::default()
+        };
+
+        let first = [message("1", "おはよう", 100), message("2", "よろしく", 200)];
+        assert_eq!(archive.store(&room, &first).unwrap(), 2);
+
+        let second = [message("2", "よろしく", 200), message("3", "了解", 300)];
+        assert_eq!(archive.store(&room, &second).unwrap(), 1);
+
+        let stored = archive.room_messages(10).unwrap();
+        assert_eq!(
+            stored
+                .iter()
+                .map(|m| m.message_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3"]
+        );
+        assert_eq!(stored[0], first[0]);
+        assert!(archive.room_messages(20).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_search() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let archive = Archive::open(temp_dir.path().join("archive.sqlite3")).unwrap();
+        let dev = Room {
+            room_id: 10,
+            name: "開発".to_string(),
+            ..Default::default()
+        };
+        let chat = Room {
+            room_id: 20,
+            name: "雑談".to_string(),
+            ..Default::default()
+        };
+        archive
+            .store(
+                &dev,
+                &[
+                    message("1", "明日の定例会議は15時からです", 100),
+                    message("2", "リリース手順を更新しました", 200),
+                ],
+            )
+            .unwrap();
+        let mut other = message("3", "会議室の予約をお願いします 100%", 300);
+        other.account = Account {
+            account_id: 2,
+            name: "山田".to_string(),
+        };
+        archive.store(&chat, &[other]).unwrap();
+
+        let search = |query: SearchQuery| {
+            archive
+                .search(&query)
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.message.message_id)
+                .collect::<Vec<_>>()
+        };
+
+        // 3文字以上は全文検索、2文字以下は部分一致で検索する
+        let text = |text: &str| SearchQuery {
+            text: text.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(search(text("定例会議")), vec!["1"]);
+        assert_eq!(search(text("会議")), vec!["3", "1"]);
+        assert_eq!(search(text("会議 予約")), vec!["3"]);
+        assert_eq!(search(text("0%")), vec!["3"]);
+        assert!(search(text("存在しない語")).is_empty());
+
+        assert_eq!(
+            search(SearchQuery {
+                room_id: Some(10),
+                ..text("会議")
+            }),
+            vec!["1"]
+        );
+        assert_eq!(
+            search(SearchQuery {
+                sender: Some("山田".to_string()),
+                ..Default::default()
+            }),
+            vec!["3"]
+        );
+        assert_eq!(
+            search(SearchQuery {
+                sender: Some("1".to_string()),
+                ..Default::default()
+            }),
+            vec!["2", "1"]
+        );
+        assert_eq!(
+            search(SearchQuery {
+                since: DateTime::from_timestamp(150, 0),
+                until: DateTime::from_timestamp(300, 0),
+                ..Default::default()
+            }),
+            vec!["2"]
+        );
+        assert_eq!(
+            search(SearchQuery {
+                limit: 1,
+                ..Default::default()
+            }),
+            vec!["3"]
+        );
+
+        let hits = archive.search(&text("リリース")).unwrap();
+        assert_eq!(hits[0].room_name, "開発");
+        assert_eq!(hits[0].permalink(), "https://www.chatwork.com/#!rid10-2");
+    }
+
+    #[test]
+    fn test_archive_indexes_existing_messages() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let path = temp_dir.path().join("archive.sqlite3");
+        let room = Room {
+            room_id: 10,
+            ..Default::default()
+        };
+        Archive::open(&path)
+            .unwrap()
+            .store(&room, &[message("1", "議事録を共有します", 100)])
+            .unwrap();
+
+        // インデックスのない古いアーカイブを開き直すと、既存のメッセージが登録される
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("DROP TABLE messages_fts")
+            .unwrap();
+        let archive = Archive::open(&path).unwrap();
+        let hits = archive
+            .search(&SearchQuery {
+                text: "議事録".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+}
@@ -0,0 +1,349 @@
+//! コマンドライン引数の定義モジュール
+//!
+//! このモジュールは、`clap`を使用してサブコマンドとその引数を定義します。
+//! サブコマンドを省略した場合は、従来通り全ルームの自動既読を実行します。
+//! ヘルプの説明はメッセージカタログから取得するため、解析の前に設定した言語で表示されます。
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{ArgAction, Args, Parser, Subcommand};
+
+use crate::t;
+
+/// Chatworkのメッセージを自動で既読にするツールです。
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = t!("cli.about"),
+    disable_help_flag = true,
+    disable_version_flag = true
+)]
+pub struct Cli {
+    /// 実行するサブコマンド（省略時は`run`）
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// ヘルプを表示します
+    #[arg(short, long, action = ArgAction::Help, global = true, help = t!("cli.help_help"))]
+    help: Option<bool>,
+    /// バージョンを表示します
+    #[arg(short = 'V', long, action = ArgAction::Version, help = t!("cli.help_version"))]
+    version: Option<bool>,
+}
+
+/// サブコマンドの一覧です。
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 全ルームのメッセージを自動で既読にします
+    #[command(about = t!("cli.about_run"))]
+    Run,
+    /// 設定された間隔で既読処理を繰り返し実行します
+    #[command(about = t!("cli.about_daemon"))]
+    Daemon,
+    /// ChatworkのWebhookを受信し、メッセージが届いたルームを既読にします
+    #[command(about = t!("cli.about_serve"))]
+    Serve,
+    /// ツールが既読にしたメッセージを未読に戻します
+    #[command(about = t!("cli.about_undo"))]
+    Undo(UndoArgs),
+    /// 既読操作のジャーナルを表示します
+    #[command(about = t!("cli.about_journal"))]
+    Journal(JournalArgs),
+    /// アーカイブしたメッセージを検索します
+    #[command(about = t!("cli.about_search"))]
+    Search(SearchArgs),
+    /// 標準入力から読み込んだAPIトークンをOSのキーリングに保存します
+    #[command(about = t!("cli.about_store_token"))]
+    StoreToken(StoreTokenArgs),
+    /// ブラウザでChatworkへのアクセスを許可し、OAuthのトークンを取得して保存します
+    #[command(about = t!("cli.about_authorize"))]
+    Authorize(AuthorizeArgs),
+    /// 設定を検証し、APIでトークンと参照しているルーム・アカウントを確認します
+    #[command(about = t!("cli.about_check_config"))]
+    CheckConfig(CheckConfigArgs),
+    /// 設定ファイルのJSON Schemaを出力します（エディタの補完に使用します）
+    #[command(about = t!("cli.about_config_schema"))]
+    ConfigSchema,
+}
+
+/// `undo`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct UndoArgs {
+    /// この日時以降の既読を未読に戻します（例: "2024-01-31 09:00"）
+    #[arg(long, value_parser = parse_datetime, help = t!("cli.help_undo_since"))]
+    pub since: Option<DateTime<Utc>>,
+    /// この日時より前の既読を未読に戻します（例: "2024-01-31 18:00"）
+    #[arg(long, value_parser = parse_datetime, help = t!("cli.help_undo_until"))]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 日付または期間で絞り込むための引数です。
+#[derive(Debug, Args)]
+pub struct PeriodArgs {
+    /// この日（ローカルタイムゾーン）に絞り込みます（例: "2024-01-31"）
+    #[arg(long, conflicts_with_all = ["since", "until"], help = t!("cli.help_date"))]
+    pub date: Option<NaiveDate>,
+    /// この日時以降に絞り込みます
+    #[arg(long, value_parser = parse_datetime, help = t!("cli.help_since"))]
+    pub since: Option<DateTime<Utc>>,
+    /// この日時より前に絞り込みます
+    #[arg(long, value_parser = parse_datetime, help = t!("cli.help_until"))]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// `journal`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct JournalArgs {
+    /// 表示する記録の期間
+    #[command(flatten)]
+    pub period: PeriodArgs,
+    /// 指定したルームの記録のみを表示します
+    #[arg(long, help = t!("cli.help_journal_room"))]
+    pub room: Option<i32>,
+    /// 記録をJSONL形式で出力します
+    #[arg(long, help = t!("cli.help_journal_json"))]
+    pub json: bool,
+}
+
+/// `search`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct SearchArgs {
+    /// 本文に含まれる語（複数指定した場合は全てを含むメッセージ）
+    #[arg(required = true, help = t!("cli.help_search_terms"))]
+    pub terms: Vec<String>,
+    /// 検索するメッセージの送信日時の期間
+    #[command(flatten)]
+    pub period: PeriodArgs,
+    /// 指定したルームのメッセージのみを検索します
+    #[arg(long, help = t!("cli.help_search_room"))]
+    pub room: Option<i32>,
+    /// 指定した送信者（アカウントIDまたは名前の一部）のメッセージのみを検索します
+    #[arg(long, help = t!("cli.help_search_sender"))]
+    pub sender: Option<String>,
+    /// 表示する件数の上限（0で無制限）
+    #[arg(long, default_value_t = 50, help = t!("cli.help_search_limit"))]
+    pub limit: usize,
+}
+
+/// `store-token`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct StoreTokenArgs {
+    /// キーリングのサービス名
+    #[arg(long, default_value = env!("CARGO_PKG_NAME"), help = t!("cli.help_store_token_service"))]
+    pub service: String,
+    /// キーリングのユーザー名
+    #[arg(long, default_value = "api_token", help = t!("cli.help_store_token_user"))]
+    pub user: String,
+}
+
+/// `authorize`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct AuthorizeArgs {
+    /// 認可するアカウントの名前（`[[accounts]]`を使用している場合。省略時はOAuthを設定した全てのアカウント）
+    #[arg(long, help = t!("cli.help_authorize_account"))]
+    pub account: Option<String>,
+}
+
+/// `check-config`サブコマンドの引数です。
+#[derive(Debug, Args)]
+pub struct CheckConfigArgs {
+    /// APIによる確認を行わず、設定ファイルの内容のみを検証します
+    #[arg(long, help = t!("cli.help_check_config_offline"))]
+    pub offline: bool,
+}
+
+impl PeriodArgs {
+    /// 表示する期間を返します。
+    ///
+    /// `--date`が指定された場合は、その日の0時から翌日の0時までになります。
+    pub fn range(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        match self.date {
+            Some(date) => (
+                local_midnight(date),
+                date.succ_opt().and_then(local_midnight),
+            ),
+            None => (self.since, self.until),
+        }
+    }
+}
+
+/// ローカルタイムゾーンでの指定日の0時を返します。
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+/// コマンドラインで指定された日時を解析します。
+///
+/// RFC 3339形式のほか、ローカルタイムゾーンの"YYYY-MM-DD HH:MM[:SS]"形式と
+/// "YYYY-MM-DD"形式（その日の0時）を受け付けます。
+pub fn parse_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| t!("cli.invalid_datetime", value = value))?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| t!("cli.nonexistent_datetime", value = value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime() {
+        assert_eq!(
+            parse_datetime("2024-01-31T09:00:00+09:00").unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap()
+        );
+
+        let local = Local
+            .with_ymd_and_hms(2024, 1, 31, 9, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_datetime("2024-01-31 09:30").unwrap(), local);
+        assert!(parse_datetime("2024-01-31").is_ok());
+        assert!(parse_datetime("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_cli_help_is_localized() {
+        use clap::CommandFactory;
+
+        // ヘルプの説明はメッセージカタログの現在の言語（デフォルトは日本語）で表示する
+        let help = Cli::command().render_help().to_string();
+        assert!(
+            help.contains("全ルームのメッセージを自動で既読にします"),
+            "{}",
+            help
+        );
+        assert!(help.contains("ヘルプを表示します"), "{}", help);
+
+        let mut command = Cli::command();
+        command.build();
+        let search = command.find_subcommand_mut("search").unwrap();
+        let help = search.render_help().to_string();
+        assert!(help.contains("表示する件数の上限"), "{}", help);
+        assert!(help.contains("ヘルプを表示します"), "{}", help);
+    }
+
+    #[test]
+    fn test_cli_undo_args() {
+        let cli =
+            Cli::try_parse_from(["chatwork_auto_read", "undo", "--since", "2024-01-31"]).unwrap();
+        match cli.command {
+            Some(Command::Undo(args)) => {
+                assert!(args.since.is_some());
+                assert!(args.until.is_none());
+            }
+            _ => panic!("undoサブコマンドとして解析されませんでした"),
+        }
+
+        let cli = Cli::try_parse_from(["chatwork_auto_read"]).unwrap();
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_cli_store_token_args() {
+        let cli = Cli::try_parse_from(["chatwork_auto_read", "store-token"]).unwrap();
+        let Some(Command::StoreToken(args)) = cli.command else {
+            panic!("store-tokenサブコマンドとして解析されませんでした");
+        };
+        assert_eq!(args.service, "chatwork_auto_read");
+        assert_eq!(args.user, "api_token");
+    }
+
+    #[test]
+    fn test_cli_authorize_args() {
+        let cli = Cli::try_parse_from(["chatwork_auto_read", "authorize", "--account", "company"])
+            .unwrap();
+        let Some(Command::Authorize(args)) = cli.command else {
+            panic!("authorizeサブコマンドとして解析されませんでした");
+        };
+        assert_eq!(args.account.as_deref(), Some("company"));
+    }
+
+    #[test]
+    fn test_cli_check_config_args() {
+        let cli = Cli::try_parse_from(["chatwork_auto_read", "check-config", "--offline"]).unwrap();
+        let Some(Command::CheckConfig(args)) = cli.command else {
+            panic!("check-configサブコマンドとして解析されませんでした");
+        };
+        assert!(args.offline);
+
+        let cli = Cli::try_parse_from(["chatwork_auto_read", "config-schema"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::ConfigSchema)));
+    }
+
+    #[test]
+    fn test_cli_journal_args() {
+        let cli = Cli::try_parse_from([
+            "chatwork_auto_read",
+            "journal",
+            "--date",
+            "2024-01-31",
+            "--room",
+            "10",
+        ])
+        .unwrap();
+        let Some(Command::Journal(args)) = cli.command else {
+            panic!("journalサブコマンドとして解析されませんでした");
+        };
+        assert_eq!(args.room, Some(10));
+        let (since, until) = args.period.range();
+        assert_eq!(
+            since.unwrap(),
+            Local
+                .with_ymd_and_hms(2024, 1, 31, 0, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        );
+        assert_eq!(until.unwrap() - since.unwrap(), chrono::Duration::days(1));
+
+        assert!(Cli::try_parse_from([
+            "chatwork_auto_read",
+            "journal",
+            "--date",
+            "2024-01-31",
+            "--since",
+            "2024-01-30",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_search_args() {
+        let cli = Cli::try_parse_from([
+            "chatwork_auto_read",
+            "search",
+            "定例",
+            "会議",
+            "--sender",
+            "山田",
+            "--since",
+            "2024-01-01",
+        ])
+        .unwrap();
+        let Some(Command::Search(args)) = cli.command else {
+            panic!("searchサブコマンドとして解析されませんでした");
+        };
+        assert_eq!(args.terms, vec!["定例", "会議"]);
+        assert_eq!(args.sender.as_deref(), Some("山田"));
+        assert_eq!(args.limit, 50);
+        assert!(args.period.range().0.is_some());
+
+        assert!(Cli::try_parse_from(["chatwork_auto_read", "search"]).is_err());
+    }
+}
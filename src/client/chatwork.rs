@@ -1,12 +1,21 @@
 use crate::error::Error;
-use crate::models::{Message, ReadStatus, Room};
+use crate::health::health;
+use crate::metrics::metrics;
+use crate::models::{Account, File, Message, ReadStatus, Room, Task};
+use crate::oauth::OAuthSession;
+use crate::secret::{scrub, Secret, REDACTED};
+use crate::settings::RedactionSettings;
+use crate::t;
 use anyhow::Context;
 use async_trait::async_trait;
-use log::{error, info, warn};
-use reqwest::Client;
+use log::{debug, error, info, warn};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
 use serde_json::Value;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use tracing::{field, info_span, Instrument};
 
 use mockall::automock;
 
@@ -14,6 +23,8 @@ use mockall::automock;
 const MAX_RETRY_ATTEMPTS: usize = 5;
 /// リトライ間の初期遅延時間。
 const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Chatwork APIのベースURL。
+const API_BASE_URL: &str = "https://api.chatwork.com/v2";
 
 /// Chatwork APIとの対話のためのインターフェースを定義します。
 ///
@@ -29,6 +40,17 @@ pub trait ChatworkClientTrait {
     /// 成功した場合は`Room`オブジェクトのベクターを含む`Result`、操作が失敗した場合は`Error`を返します。
     async fn fetch_rooms(&self) -> Result<Vec<Room>, Error>;
 
+    /// 特定のルームの情報を取得します。
+    ///
+    /// # 引数
+    ///
+    /// * `room_id` - 情報を取得するルームのID。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`Room`オブジェクトを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_room(&self, room_id: i32) -> Result<Room, Error>;
+
     /// 特定のルームからメッセージを取得します。
     ///
     /// # 引数
@@ -65,8 +87,7 @@ pub trait ChatworkClientTrait {
     /// # 例
     ///
     /// ```
-    /// # use your_crate::ChatworkClient;
-    /// # use your_crate::ChatworkClientTrait;
+    /// # use chatwork_auto_read::client::ChatworkClientTrait;
     /// # async fn example(client: &impl ChatworkClientTrait) -> Result<(), Box<dyn std::error::Error>> {
     /// let room_id = 123;
     /// let message_id = "456";
@@ -79,6 +100,94 @@ pub trait ChatworkClientTrait {
         room_id: i32,
         message_id: &str,
     ) -> Result<ReadStatus, Error>;
+
+    /// 指定されたルーム内の特定のメッセージ以降を未読としてマークします。
+    ///
+    /// # 引数
+    ///
+    /// * `room_id` - メッセージを含むルームのID。
+    /// * `message_id` - 未読にする最初のメッセージのID。このメッセージ以降が未読になります。
+    ///
+    /// # 戻り値
+    ///
+    /// 操作が成功した場合は更新後の`ReadStatus`を返します。
+    /// エラーが発生した場合（APIエラー、ネットワークエラーなど）は`Error`を返します。
+    async fn mark_message_as_unread(
+        &self,
+        room_id: i32,
+        message_id: &str,
+    ) -> Result<ReadStatus, Error>;
+
+    /// 自分に割り当てられた未完了のタスクを取得します。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`Task`オブジェクトのベクターを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_my_tasks(&self) -> Result<Vec<Task>, Error>;
+
+    /// 特定のルームの未完了のタスクを取得します。
+    ///
+    /// # 引数
+    ///
+    /// * `room_id` - タスクを取得するルームのID。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`Task`オブジェクトのベクターを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_room_tasks(&self, room_id: i32) -> Result<Vec<Task>, Error>;
+
+    /// 特定のルームにアップロードされたファイルの一覧を取得します。
+    ///
+    /// # 引数
+    ///
+    /// * `room_id` - ファイルを取得するルームのID。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`File`オブジェクトのベクターを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_files(&self, room_id: i32) -> Result<Vec<File>, Error>;
+
+    /// 特定のルームにメッセージを投稿します。
+    ///
+    /// 投稿したメッセージは自分にとって既読の状態になります。
+    ///
+    /// # 引数
+    ///
+    /// * `room_id` - 投稿先のルームのID。
+    /// * `body` - メッセージ本文。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は投稿したメッセージのIDを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn post_message(&self, room_id: i32, body: &str) -> Result<String, Error>;
+
+    /// 認証されたユーザー自身のアカウント情報を取得します。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`Account`オブジェクトを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_me(&self) -> Result<Account, Error>;
+
+    /// 認証されたユーザーのコンタクトの一覧を取得します。
+    ///
+    /// # 戻り値
+    ///
+    /// 成功した場合は`Account`オブジェクトのベクターを含む`Result`、操作が失敗した場合は`Error`を返します。
+    async fn fetch_contacts(&self) -> Result<Vec<Account>, Error>;
+}
+
+/// メッセージ投稿APIのレスポンスです。
+#[derive(Deserialize)]
+struct PostMessageResponse {
+    message_id: String,
+}
+
+/// APIリクエストの認証方法です。
+enum Credentials {
+    /// `X-ChatWorkToken`ヘッダーで送るAPIトークン
+    ApiToken(Secret),
+    /// `Authorization: Bearer`ヘッダーで送るOAuthのアクセストークン
+    OAuth(Arc<OAuthSession>),
 }
 
 /// Chatwork APIとの対話を管理するクライアント。
@@ -87,7 +196,10 @@ pub trait ChatworkClientTrait {
 /// およびレート制限の処理を担当します。
 pub struct ChatworkClient {
     client: Client,
-    api_token: String,
+    credentials: Credentials,
+    redaction: RedactionSettings,
+    account: Option<String>,
+    base_url: String,
 }
 
 impl ChatworkClient {
@@ -97,26 +209,75 @@ impl ChatworkClient {
     ///
     /// * `api_token` - Chatwork APIでの認証に使用するAPIトークン。
     pub fn new(api_token: &str) -> Self {
+        Self::with_credentials(Credentials::ApiToken(api_token.into()))
+    }
+
+    /// OAuthのアクセストークンで認証する新しいChatworkClientインスタンスを作成します。
+    ///
+    /// アクセストークンは有効期限が近づくと、リクエストの前に自動的に更新されます。
+    ///
+    /// # 引数
+    ///
+    /// * `session` - 保存したトークンを使用するOAuthのセッション。
+    pub fn from_oauth(session: Arc<OAuthSession>) -> Self {
+        Self::with_credentials(Credentials::OAuth(session))
+    }
+
+    fn with_credentials(credentials: Credentials) -> Self {
         Self {
             client: Client::new(),
-            api_token: api_token.to_string(),
+            credentials,
+            redaction: RedactionSettings::default(),
+            account: None,
+            base_url: API_BASE_URL.to_string(),
         }
     }
 
+    /// エラーレスポンスの本文をログに記録するときの秘匿の設定を指定します。
+    ///
+    /// 指定しない場合は、本文を記録しません。
+    ///
+    /// # 引数
+    ///
+    /// * `redaction` - ログに記録する内容の秘匿に関する設定
+    pub fn with_redaction(mut self, redaction: RedactionSettings) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// リクエストを送るAPIのベースURLを指定します（テスト用）。
+    #[cfg(test)]
+    fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// メトリクスと稼働状態を記録するときのアカウントの名前を指定します。
+    ///
+    /// # 引数
+    ///
+    /// * `account` - `[[accounts]]`に記述されたアカウントの名前
+    pub fn with_account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
     /// リトライロジックを使用してAPI操作を実行します。
     ///
     /// このメソッドは、レート制限が発生した場合、指数関数的バックオフを用いて
     /// 最大`MAX_RETRY_ATTEMPTS`回まで操作を再試行します。
+    /// OAuthのアクセストークンが拒否された場合は、トークンを更新して1回だけ再試行します。
     ///
     /// # 型パラメータ
     ///
     /// * `T` - デシリアライズされたレスポンスの型。
-    /// * `F` - APIリクエストを実行するクロージャの型。
-    /// * `Fut` - クロージャが返す`Future`の型。
+    /// * `F` - APIリクエストを作成するクロージャの型。
     ///
     /// # 引数
     ///
-    /// * `operation` - APIリクエストを実行するクロージャ。
+    /// * `endpoint` - メトリクスやスパンに記録するエンドポイント（例: `GET /rooms/{room_id}/messages`）。
+    /// * `room_id` - スパンに記録する、リクエストの対象のルームのID。
+    /// * `operation` - 認証ヘッダーを除くAPIリクエストを作成するクロージャ。
     ///
     /// # 戻り値
     ///
@@ -128,39 +289,86 @@ impl ChatworkClient {
     /// 以下の場合にエラーを返します：
     /// - 全てのリトライ試行が失敗した場合
     /// - APIがエラーレスポンスを返した場合
+    /// - OAuthのアクセストークンを更新できない場合
     /// - レスポンスのデシリアライズに失敗した場合
-    async fn execute_with_retry<T, F, Fut>(&self, operation: F) -> Result<T, Error>
+    async fn execute_with_retry<T, F>(
+        &self,
+        endpoint: &str,
+        room_id: Option<i32>,
+        operation: F,
+    ) -> Result<T, Error>
     where
-        F: Fn() -> Fut + Send + Sync,
-        Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
+        F: Fn() -> RequestBuilder + Send + Sync,
         T: serde::de::DeserializeOwned,
     {
         let mut delay = INITIAL_RETRY_DELAY;
+        let mut token_refreshed = false;
 
         for attempt in 0..MAX_RETRY_ATTEMPTS {
             if attempt > 0 {
-                info!("リトライ試行 {} / {}", attempt + 1, MAX_RETRY_ATTEMPTS);
+                info!(
+                    "{}",
+                    t!(
+                        "client.retrying",
+                        attempt = attempt + 1,
+                        max = MAX_RETRY_ATTEMPTS
+                    )
+                );
+                metrics().record_retry(self.account.as_deref(), endpoint);
             }
 
-            let response = operation().await?;
+            let span = info_span!(
+                "http_request",
+                endpoint,
+                room_id,
+                attempt = attempt + 1,
+                status = field::Empty,
+                latency_ms = field::Empty,
+            );
+            let (request, oauth) = self.authenticate(operation()).await?;
+            let started = Instant::now();
+            let response = request.send().instrument(span.clone()).await;
+            let elapsed = started.elapsed();
+            let status = response.as_ref().ok().map(|response| response.status());
+            span.record("latency_ms", elapsed.as_millis() as u64);
+            match status {
+                Some(status) => span.record("status", status.as_u16()),
+                None => span.record("status", "error"),
+            };
+            span.in_scope(|| debug!("{}", t!("client.request_completed")));
+            metrics().observe_request(self.account.as_deref(), endpoint, status, elapsed);
+            let response = response?;
+            health().record_response(
+                self.account.as_deref(),
+                response.status(),
+                response.headers(),
+            );
 
-            if response.status().is_success() {
+            if response.status() == reqwest::StatusCode::NO_CONTENT {
+                // 本文がないため`null`として扱い、`Option`以外の型ではエラーにします
+                return Ok(serde_json::from_value(Value::Null)?);
+            } else if response.status().is_success() {
                 return Ok(response.json().await?);
             } else if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                metrics().record_rate_limited(self.account.as_deref(), endpoint);
                 if attempt == MAX_RETRY_ATTEMPTS - 1 {
                     return Err(Error::MaxRetriesExceeded);
                 }
 
-                warn!(
-                    "レート制限に達しました。{}秒後に再試行します...",
-                    delay.as_secs()
-                );
+                warn!("{}", t!("client.rate_limited", secs = delay.as_secs()));
                 self.log_rate_limit_headers(response.headers()).await;
                 sleep(delay).await;
                 delay *= 2;
+            } else if let Some((session, access_token)) = oauth.filter(|_| {
+                response.status() == reqwest::StatusCode::UNAUTHORIZED && !token_refreshed
+            }) {
+                // 有効期限内に失効したアクセストークンは、更新して1回だけやり直します
+                warn!("{}", t!("client.access_token_rejected"));
+                session.refresh_rejected(&access_token).await?;
+                token_refreshed = true;
             } else {
                 return Err(self
-                    .handle_error_response(response, "APIリクエストが失敗しました")
+                    .handle_error_response(response, &t!("client.request_failed"))
                     .await?);
             }
         }
@@ -168,6 +376,60 @@ impl ChatworkClient {
         Err(Error::MaxRetriesExceeded)
     }
 
+    /// リトライロジックを使用して、一覧を取得するAPI操作を実行します。
+    ///
+    /// 一覧系のAPIは該当データがない場合に空のボディで204を返すため、空の一覧として扱います。
+    /// それ以外は`execute_with_retry`と同じです。
+    async fn execute_list_with_retry<T, F>(
+        &self,
+        endpoint: &str,
+        room_id: Option<i32>,
+        operation: F,
+    ) -> Result<Vec<T>, Error>
+    where
+        F: Fn() -> RequestBuilder + Send + Sync,
+        T: serde::de::DeserializeOwned,
+    {
+        let list: Option<Vec<T>> = self
+            .execute_with_retry(endpoint, room_id, operation)
+            .await?;
+        Ok(list.unwrap_or_default())
+    }
+
+    /// リクエストに認証ヘッダーを追加します。
+    ///
+    /// APIトークンは`X-ChatWorkToken`ヘッダー、OAuthのアクセストークンは`Authorization: Bearer`ヘッダーで送ります。
+    ///
+    /// # 戻り値
+    ///
+    /// 認証ヘッダーを追加したリクエストと、OAuthの場合はセッションと使用したアクセストークンを返します。
+    ///
+    /// # エラー
+    ///
+    /// OAuthのアクセストークンの更新に失敗した場合に`Error`を返します。
+    async fn authenticate(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<(RequestBuilder, Option<(&OAuthSession, Secret)>), Error> {
+        match &self.credentials {
+            Credentials::ApiToken(token) => {
+                Ok((request.header("X-ChatWorkToken", token.expose()), None))
+            }
+            Credentials::OAuth(session) => {
+                let token = session.access_token().await?;
+                Ok((request.bearer_auth(token.expose()), Some((session, token))))
+            }
+        }
+    }
+
+    /// ログから取り除く秘密情報（APIトークンやOAuthのトークン）を返します。
+    async fn secrets(&self) -> Vec<Secret> {
+        match &self.credentials {
+            Credentials::ApiToken(token) => vec![token.clone()],
+            Credentials::OAuth(session) => session.secrets().await,
+        }
+    }
+
     /// APIレスポンスからレート制限ヘッダーをログに記録します。
     ///
     /// このメソッドは、Chatwork APIのレート制限に関する情報を抽出し、
@@ -197,13 +459,23 @@ impl ChatworkClient {
             .and_then(|v| v.to_str().ok())
             .unwrap_or("N/A");
 
-        warn!("レート制限ヘッダー: x-ratelimit-limit: {}, x-ratelimit-remaining: {}, x-ratelimit-reset: {}", limit, remaining, reset);
+        warn!(
+            "{}",
+            t!(
+                "client.rate_limit_headers",
+                limit = limit,
+                remaining = remaining,
+                reset = reset
+            )
+        );
     }
 
     /// APIからのエラーレスポンスを処理します。
     ///
     /// このメソッドは、APIからのエラーレスポンスを解析し、
     /// 詳細な情報をログに記録し、適切なエラー型を生成します。
+    /// ステータスとエラーの内容はエラーレベルで、レスポンスヘッダーと本文はデバッグレベルで記録します。
+    /// 本文は`with_redaction`の設定に従って省略し、APIトークンや認証に関するヘッダーの値は`***`に置き換えます。
     ///
     /// # 引数
     ///
@@ -228,46 +500,98 @@ impl ChatworkClient {
         let body = response
             .text()
             .await
-            .context("レスポンスボディの読み取りに失敗しました")?;
+            .with_context(|| t!("client.read_body_failed"))?;
         let errors: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
 
-        error!("APIエラー: {}. ステータス: {}", error_msg, status);
-        error!("レスポンスヘッダー: {:?}", headers);
-        error!("レスポンスボディ: {}", body);
-        error!("パースされたエラー: {:?}", errors["errors"]);
+        let secrets = self.secrets().await;
+        let secrets: Vec<&Secret> = secrets.iter().collect();
+        error!(
+            "{}",
+            t!("client.api_error", message = error_msg, status = status)
+        );
+        error!(
+            "{}",
+            t!(
+                "client.parsed_errors",
+                errors = scrub(&format!("{:?}", errors["errors"]), &secrets)
+            )
+        );
+        debug!(
+            "{}",
+            t!(
+                "client.response_headers",
+                headers = scrub(&format!("{:?}", redact_headers(&headers)), &secrets)
+            )
+        );
+        debug!(
+            "{}",
+            t!(
+                "client.response_body",
+                body = scrub(&self.redaction.body(&body), &secrets)
+            )
+        );
 
         self.log_rate_limit_headers(&headers).await;
 
-        Ok(Error::ApiError(status, format!("{}: {:?}", error_msg, errors["errors"])).into())
+        Ok(Error::ApiError(
+            status,
+            scrub(&format!("{}: {:?}", error_msg, errors["errors"]), &secrets),
+        ))
     }
 }
 
+/// 認証に関するヘッダー（値が秘密情報になるもの）の名前です。
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-chatworktoken",
+];
+
+/// ログに記録するために、認証に関するヘッダーの値を`***`に置き換えたヘッダーの一覧を返します。
+fn redact_headers(headers: &reqwest::header::HeaderMap) -> Vec<(&str, &str)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or(REDACTED)
+            };
+            (name.as_str(), value)
+        })
+        .collect()
+}
+
 #[async_trait]
 impl ChatworkClientTrait for ChatworkClient {
     async fn fetch_rooms(&self) -> Result<Vec<Room>, Error> {
-        info!("ルームの取得を開始します");
-        let url = "https://api.chatwork.com/v2/rooms";
+        info!("{}", t!("client.fetching_rooms"));
+        let url = format!("{}/rooms", self.base_url);
+
+        self.execute_list_with_retry("GET /rooms", None, || self.client.get(&url))
+            .await
+    }
+
+    async fn fetch_room(&self, room_id: i32) -> Result<Room, Error> {
+        info!("{}", t!("client.fetching_room", room_id = room_id));
+        let url = format!("{}/rooms/{}", self.base_url, room_id);
 
-        self.execute_with_retry(|| async {
-            self.client
-                .get(url)
-                .header("X-ChatWorkToken", &self.api_token)
-                .send()
-                .await
+        self.execute_with_retry("GET /rooms/{room_id}", Some(room_id), || {
+            self.client.get(&url)
         })
         .await
     }
 
     async fn fetch_messages(&self, room_id: i32) -> Result<Vec<Message>, Error> {
-        info!("ルーム: {}のメッセージ取得を開始します", room_id);
-        let url = format!("https://api.chatwork.com/v2/rooms/{}/messages", room_id);
-
-        self.execute_with_retry(|| async {
-            self.client
-                .get(&url)
-                .header("X-ChatWorkToken", &self.api_token)
-                .send()
-                .await
+        info!("{}", t!("client.fetching_messages", room_id = room_id));
+        let url = format!("{}/rooms/{}/messages", self.base_url, room_id);
+
+        // `force=1`を指定しない場合、前回の取得以降のメッセージのみが返され、
+        // 前回の実行で既読を止めたメッセージが取得できなくなります
+        self.execute_list_with_retry("GET /rooms/{room_id}/messages", Some(room_id), || {
+            self.client.get(&url).query(&[("force", "1")])
         })
         .await
     }
@@ -278,28 +602,109 @@ impl ChatworkClientTrait for ChatworkClient {
         message_id: &str,
     ) -> Result<ReadStatus, Error> {
         info!(
-            "メッセージを既読としてマークします。ルーム: {}, メッセージ: {}",
-            room_id, message_id
+            "{}",
+            t!(
+                "client.marking_read",
+                room_id = room_id,
+                message_id = message_id
+            )
         );
 
-        let url = format!(
-            "https://api.chatwork.com/v2/rooms/{}/messages/read",
-            room_id
-        );
+        let url = format!("{}/rooms/{}/messages/read", self.base_url, room_id);
 
         let status: ReadStatus = self
-            .execute_with_retry(|| async {
-                self.client
-                    .put(&url)
-                    .header("X-ChatWorkToken", &self.api_token)
-                    .form(&[("message_id", message_id)])
-                    .send()
-                    .await
+            .execute_with_retry("PUT /rooms/{room_id}/messages/read", Some(room_id), || {
+                self.client.put(&url).form(&[("message_id", message_id)])
             })
             .await?;
 
         Ok(status)
     }
+
+    async fn mark_message_as_unread(
+        &self,
+        room_id: i32,
+        message_id: &str,
+    ) -> Result<ReadStatus, Error> {
+        info!(
+            "{}",
+            t!(
+                "client.marking_unread",
+                room_id = room_id,
+                message_id = message_id
+            )
+        );
+
+        let url = format!("{}/rooms/{}/messages/unread", self.base_url, room_id);
+
+        self.execute_with_retry(
+            "PUT /rooms/{room_id}/messages/unread",
+            Some(room_id),
+            || self.client.put(&url).form(&[("message_id", message_id)]),
+        )
+        .await
+    }
+
+    async fn fetch_my_tasks(&self) -> Result<Vec<Task>, Error> {
+        info!("{}", t!("client.fetching_my_tasks"));
+        let url = format!("{}/my/tasks", self.base_url);
+
+        self.execute_list_with_retry("GET /my/tasks", None, || {
+            self.client.get(&url).query(&[("status", "open")])
+        })
+        .await
+    }
+
+    async fn fetch_room_tasks(&self, room_id: i32) -> Result<Vec<Task>, Error> {
+        info!("{}", t!("client.fetching_room_tasks", room_id = room_id));
+        let url = format!("{}/rooms/{}/tasks", self.base_url, room_id);
+
+        self.execute_list_with_retry("GET /rooms/{room_id}/tasks", Some(room_id), || {
+            self.client.get(&url).query(&[("status", "open")])
+        })
+        .await
+    }
+
+    async fn fetch_files(&self, room_id: i32) -> Result<Vec<File>, Error> {
+        info!("{}", t!("client.fetching_files", room_id = room_id));
+        let url = format!("{}/rooms/{}/files", self.base_url, room_id);
+
+        self.execute_list_with_retry("GET /rooms/{room_id}/files", Some(room_id), || {
+            self.client.get(&url)
+        })
+        .await
+    }
+
+    async fn post_message(&self, room_id: i32, body: &str) -> Result<String, Error> {
+        info!("{}", t!("client.posting_message", room_id = room_id));
+        let url = format!("{}/rooms/{}/messages", self.base_url, room_id);
+
+        let response: PostMessageResponse = self
+            .execute_with_retry("POST /rooms/{room_id}/messages", Some(room_id), || {
+                self.client
+                    .post(&url)
+                    .form(&[("body", body), ("self_unread", "0")])
+            })
+            .await?;
+
+        Ok(response.message_id)
+    }
+
+    async fn fetch_me(&self) -> Result<Account, Error> {
+        info!("{}", t!("client.fetching_me"));
+        let url = format!("{}/me", self.base_url);
+
+        self.execute_with_retry("GET /me", None, || self.client.get(&url))
+            .await
+    }
+
+    async fn fetch_contacts(&self) -> Result<Vec<Account>, Error> {
+        info!("{}", t!("client.fetching_contacts"));
+        let url = format!("{}/contacts", self.base_url);
+
+        self.execute_list_with_retry("GET /contacts", None, || self.client.get(&url))
+            .await
+    }
 }
 
 /// `ChatworkClient`と`ChatworkClientTrait`の単体テスト。
@@ -309,8 +714,12 @@ impl ChatworkClientTrait for ChatworkClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::oauth::OAuthToken;
+    use crate::settings::{BodyRedaction, OAuthSettings};
     use mockall::predicate::*;
     use tokio;
+    use wiremock::matchers::{any, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     // MockChatworkClientTraitを使用可能にする
     use super::MockChatworkClientTrait;
@@ -323,6 +732,7 @@ mod tests {
                 room_id: 1,
                 unread_num: 1,
                 mention_num: 0,
+                ..Default::default()
             }])
         });
 
@@ -331,6 +741,26 @@ mod tests {
         assert_eq!(rooms[0].room_id, 1);
     }
 
+    #[tokio::test]
+    async fn test_fetch_room() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client
+            .expect_fetch_room()
+            .with(eq(1))
+            .times(1)
+            .returning(|room_id| {
+                Ok(Room {
+                    room_id,
+                    unread_num: 3,
+                    ..Default::default()
+                })
+            });
+
+        let room = mock_client.fetch_room(1).await.unwrap();
+        assert_eq!(room.room_id, 1);
+        assert_eq!(room.unread_num, 3);
+    }
+
     #[tokio::test]
     async fn test_fetch_messages() {
         let mut mock_client = MockChatworkClientTrait::new();
@@ -342,6 +772,7 @@ mod tests {
                 Ok(vec![Message {
                     message_id: "1".to_string(),
                     body: "テストメッセージ".to_string(),
+                    ..Default::default()
                 }])
             });
 
@@ -373,6 +804,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_post_message() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client
+            .expect_post_message()
+            .with(eq(123), eq("[info]ダイジェスト[/info]"))
+            .times(1)
+            .returning(|_, _| Ok("789".to_string()));
+
+        let message_id = mock_client
+            .post_message(123, "[info]ダイジェスト[/info]")
+            .await
+            .unwrap();
+        assert_eq!(message_id, "789");
+    }
+
     #[tokio::test]
     async fn test_fetch_rooms_error() {
         let mut mock_client = MockChatworkClientTrait::new();
@@ -399,4 +846,229 @@ mod tests {
         let messages = mock_client.fetch_messages(123).await.unwrap();
         assert!(messages.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_no_content_response() {
+        let server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+        let client = ChatworkClient::new("api_token");
+        let url = server.uri();
+
+        // 一覧を取得するAPIでは、204を空の一覧として扱う
+        let messages: Vec<Message> = client
+            .execute_list_with_retry("GET /rooms/{room_id}/messages", Some(1), || {
+                client.client.get(&url)
+            })
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+
+        // 一覧以外のAPIでは、204を空の一覧として扱わずエラーにする
+        let result: Result<ReadStatus, Error> = client
+            .execute_with_retry("PUT /rooms/{room_id}/messages/read", Some(1), || {
+                client.client.put(&url)
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_room_tasks() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rooms/1/tasks"))
+            .and(query_param("status", "open"))
+            .and(header("x-chatworktoken", "api_token"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "task_id": 3,
+                    "message_id": "100",
+                    "body": "資料を確認する",
+                    "status": "open"
+                }])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = ChatworkClient::new("api_token").with_base_url(server.uri());
+
+        let tasks = client.fetch_room_tasks(1).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].task_id, 3);
+        assert_eq!(tasks[0].message_id, "100");
+        assert!(tasks[0].room.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_my_tasks() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_my_tasks().times(1).returning(|| {
+            Ok(vec![Task {
+                task_id: 1,
+                message_id: "100".to_string(),
+                status: "open".to_string(),
+                ..Default::default()
+            }])
+        });
+
+        let tasks = mock_client.fetch_my_tasks().await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].message_id, "100");
+    }
+
+    #[test]
+    fn test_deserialize_my_tasks() {
+        let json = r#"[{
+            "task_id": 3,
+            "room": {"room_id": 5, "name": "Group Chat Name", "icon_path": "https://example.com/ico_group.png"},
+            "assigned_by_account": {"account_id": 456, "name": "Anna"},
+            "message_id": "13",
+            "body": "buy milk",
+            "limit_time": 1384354799,
+            "status": "open",
+            "limit_type": "date"
+        }]"#;
+
+        let tasks: Vec<Task> = serde_json::from_str(json).unwrap();
+        assert_eq!(tasks[0].room.as_ref().unwrap().room_id, 5);
+        assert_eq!(tasks[0].message_id, "13");
+    }
+
+    #[test]
+    fn test_deserialize_files() {
+        let json = r#"[{
+            "file_id": 3,
+            "account": {"account_id": 123, "name": "Bob", "avatar_image_url": "https://example.com/ico_avatar.png"},
+            "message_id": "22",
+            "filename": "README.md",
+            "filesize": 2232,
+            "upload_date": 1384414750
+        }]"#;
+
+        let files: Vec<File> = serde_json::from_str(json).unwrap();
+        assert_eq!(files[0].file_id, 3);
+        assert_eq!(files[0].account.account_id, 123);
+        assert_eq!(files[0].filesize, 2232);
+    }
+
+    /// テスト中に出力されたログを記録するライターです。
+    #[derive(Clone, Default)]
+    struct LogBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogBuffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    const TOKEN: &str = "secret_token_123";
+
+    /// APIトークンと本文をそのまま返すエラーレスポンスを処理し、出力されたログを返します。
+    async fn error_response_logs(message_bodies: BodyRedaction) -> (Error, String) {
+        // `log`クレートのマクロによる出力も、テスト用のサブスクライバーに渡す
+        let _ = tracing_log::LogTracer::init();
+        let buffer = LogBuffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(buffer.clone())
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let response: reqwest::Response = hyper::Response::builder()
+            .status(401)
+            .header("set-cookie", format!("session={}", TOKEN))
+            .header("x-echo", TOKEN)
+            .body(format!(
+                r#"{{"errors":["Invalid API Token: {}"],"body":"社外秘の本文"}}"#,
+                TOKEN
+            ))
+            .unwrap()
+            .into();
+        let client = ChatworkClient::new(TOKEN).with_redaction(RedactionSettings {
+            message_bodies,
+            ..Default::default()
+        });
+        let error = client
+            .handle_error_response(response, "リクエストに失敗しました")
+            .await
+            .unwrap();
+
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        (error, logs)
+    }
+
+    #[tokio::test]
+    async fn test_error_response_logs_never_contain_token() {
+        for message_bodies in [
+            BodyRedaction::Hide,
+            BodyRedaction::Excerpt,
+            BodyRedaction::Full,
+        ] {
+            let (error, logs) = error_response_logs(message_bodies).await;
+            assert!(logs.contains("401"), "{}", logs);
+            assert!(!logs.contains(TOKEN), "{}", logs);
+            assert!(!error.to_string().contains(TOKEN), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_response_body_redaction() {
+        let (_, logs) = error_response_logs(BodyRedaction::Hide).await;
+        assert!(!logs.contains("社外秘の本文"), "{}", logs);
+
+        let (_, logs) = error_response_logs(BodyRedaction::Full).await;
+        assert!(logs.contains("社外秘の本文"), "{}", logs);
+        assert!(logs.contains("***"), "{}", logs);
+    }
+
+    #[tokio::test]
+    async fn test_authentication_headers() {
+        let url = "https://api.chatwork.com/v2/rooms";
+        let client = ChatworkClient::new("api_token");
+        let (request, oauth) = client.authenticate(client.client.get(url)).await.unwrap();
+        let request = request.build().unwrap();
+        assert_eq!(request.headers()["x-chatworktoken"], "api_token");
+        assert!(request.headers().get("authorization").is_none());
+        assert!(oauth.is_none());
+
+        // OAuthの場合はアクセストークンをBearerトークンとして送る
+        let session = OAuthSession::new(
+            OAuthSettings {
+                client_id: "client".to_string(),
+                client_secret: None,
+                redirect_port: 8765,
+                scope: "rooms.all:read_write".to_string(),
+                token_path: "oauth_token.json".into(),
+            },
+            OAuthToken {
+                access_token: "access_token".into(),
+                refresh_token: "refresh_token".into(),
+                expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+            },
+        );
+        let client = ChatworkClient::from_oauth(Arc::new(session));
+        let (request, oauth) = client.authenticate(client.client.get(url)).await.unwrap();
+        let request = request.build().unwrap();
+        assert_eq!(request.headers()["authorization"], "Bearer access_token");
+        assert!(request.headers().get("x-chatworktoken").is_none());
+        assert_eq!(oauth.unwrap().1.expose(), "access_token");
+        assert_eq!(client.secrets().await.len(), 2);
+    }
 }
@@ -0,0 +1,693 @@
+//! 既読ダイジェストモジュール
+//!
+//! このモジュールは、ツールが既読にしたメッセージを実行結果のレポートから集計し、
+//! ルームごとの件数・発言の多い送信者・メッセージの抜粋をまとめたダイジェストを
+//! Markdown・HTML・プレーンテキストで出力するための機能を提供します。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use log::{debug, info, warn};
+
+use crate::client::ChatworkClientTrait;
+use crate::error::Error;
+use crate::i18n::language;
+use crate::models::Message;
+use crate::report::RunReport;
+use crate::settings::{DigestFormat, DigestSettings};
+use crate::t;
+use crate::utils::{escape_html, excerpt};
+
+/// 1つ以上の実行結果をまとめたダイジェストです。
+#[derive(Debug, Clone)]
+pub struct Digest {
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    runs: usize,
+    report: RunReport,
+}
+
+/// ダイジェストに含める1つのルームの集計です。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomDigest {
+    /// ルームID
+    pub room_id: i32,
+    /// ルーム名
+    pub room_name: String,
+    /// 既読にしたメッセージ（古い順）
+    pub messages: Vec<Message>,
+}
+
+impl RoomDigest {
+    /// 発言の多い送信者を、名前と件数の組で多い順に返します。
+    pub fn top_senders(&self, limit: usize) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for message in &self.messages {
+            *counts.entry(message.account.name.as_str()).or_insert(0) += 1;
+        }
+        let mut senders: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        senders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        senders.truncate(limit);
+        senders
+    }
+}
+
+impl Digest {
+    /// 指定された日時から始まる空のダイジェストを作成します。
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            finished_at: started_at,
+            runs: 0,
+            report: RunReport::default(),
+        }
+    }
+
+    /// 1回の実行結果をダイジェストに追加します。
+    pub fn add(&mut self, report: &RunReport, finished_at: DateTime<Utc>) {
+        self.runs += 1;
+        self.finished_at = finished_at;
+        self.report.rooms.extend(report.rooms.iter().cloned());
+    }
+
+    /// ダイジェストの開始日時を返します。
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// 追加された実行の回数を返します。
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// 既読にしたメッセージをルームごとにまとめて、件数の多い順に返します。
+    pub fn rooms(&self) -> Vec<RoomDigest> {
+        let mut rooms: BTreeMap<i32, RoomDigest> = BTreeMap::new();
+        for room in self.report.rooms.iter().filter(|r| !r.messages.is_empty()) {
+            let digest = rooms.entry(room.room_id).or_insert_with(|| RoomDigest {
+                room_id: room.room_id,
+                room_name: room.room_name.clone(),
+                messages: Vec::new(),
+            });
+            digest.messages.extend(room.messages.iter().cloned());
+        }
+        let mut rooms: Vec<RoomDigest> = rooms.into_values().collect();
+        rooms.sort_by_key(|room| std::cmp::Reverse(room.messages.len()));
+        rooms
+    }
+
+    /// ダイジェストを指定された形式で出力します。
+    pub fn render(&self, settings: &DigestSettings) -> String {
+        match settings.format {
+            DigestFormat::Markdown => self.render_markdown(settings),
+            DigestFormat::Html => self.render_html(settings),
+            DigestFormat::Text => self.render_text(settings),
+        }
+    }
+
+    /// ダイジェストを設定された出力先のディレクトリにファイルとして書き込みます。
+    ///
+    /// # 戻り値
+    ///
+    /// 書き込んだファイルのパスを返します。
+    ///
+    /// # エラー
+    ///
+    /// ディレクトリの作成やファイルの書き込みに失敗した場合、`Error`を返します。
+    pub fn write(&self, settings: &DigestSettings, daily: bool) -> Result<PathBuf, Error> {
+        let started_at = self.started_at.with_timezone(&Local);
+        let stem = if daily {
+            started_at.format("digest-%Y-%m-%d")
+        } else {
+            started_at.format("digest-%Y%m%d-%H%M%S")
+        };
+        let extension = match settings.format {
+            DigestFormat::Markdown => "md",
+            DigestFormat::Html => "html",
+            DigestFormat::Text => "txt",
+        };
+
+        fs::create_dir_all(&settings.output_dir)?;
+        let path = settings.output_dir.join(format!("{}.{}", stem, extension));
+        fs::write(&path, self.render(settings))?;
+        Ok(path)
+    }
+
+    /// タイトルに使用する期間の表記を返します。
+    pub fn title(&self) -> String {
+        t!(
+            "digest.title",
+            from = format_time(self.started_at, "%Y-%m-%d %H:%M"),
+            to = format_time(self.finished_at, "%Y-%m-%d %H:%M")
+        )
+    }
+
+    /// 全体の集計を1行で返します。
+    pub fn summary(&self) -> String {
+        if self.runs > 1 {
+            t!(
+                "digest.summary_runs",
+                runs = self.runs,
+                report = self.report
+            )
+        } else {
+            self.report.to_string()
+        }
+    }
+
+    /// ダイジェストをChatworkの`[info][title]`記法で出力します。
+    ///
+    /// 抜粋に含まれる角括弧は全角に置き換え、元のメッセージのタグが解釈されないようにします。
+    pub fn render_chatwork(&self, settings: &DigestSettings) -> String {
+        let mut out = format!(
+            "[info][title]{}[/title]{}",
+            self.title(),
+            escape_chatwork(&self.summary())
+        );
+        for room in self.rooms() {
+            out.push_str(&format!(
+                "[hr]■ {}\n{}",
+                room_heading(&escape_chatwork(&room.room_name), &room),
+                escape_chatwork(&top_senders(&room, settings))
+            ));
+            for line in excerpt_lines(&room, settings) {
+                out.push_str(&format!("\n・{}", escape_chatwork(&line)));
+            }
+            out.push('\n');
+        }
+        out.push_str("[/info]");
+        out
+    }
+
+    fn render_markdown(&self, settings: &DigestSettings) -> String {
+        let mut out = format!("# {}\n\n{}\n", self.title(), self.summary());
+        for room in self.rooms() {
+            out.push_str(&format!(
+                "\n## {}\n\n{}\n\n",
+                room_heading(&room.room_name, &room),
+                top_senders(&room, settings)
+            ));
+            for line in excerpt_lines(&room, settings) {
+                out.push_str(&format!("- {}\n", line));
+            }
+        }
+        out
+    }
+
+    fn render_html(&self, settings: &DigestSettings) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n<p>{}</p>\n",
+            language().code(),
+            title,
+            title,
+            escape_html(&self.summary())
+        );
+        for room in self.rooms() {
+            out.push_str(&format!(
+                "<h2>{}</h2>\n<p>{}</p>\n<ul>\n",
+                room_heading(&escape_html(&room.room_name), &room),
+                escape_html(&top_senders(&room, settings))
+            ));
+            for line in excerpt_lines(&room, settings) {
+                out.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn render_text(&self, settings: &DigestSettings) -> String {
+        let mut out = format!("{}\n{}\n", self.title(), self.summary());
+        for room in self.rooms() {
+            out.push_str(&format!(
+                "\n■ {}\n  {}\n",
+                room_heading(&room.room_name, &room),
+                top_senders(&room, settings)
+            ));
+            for line in excerpt_lines(&room, settings) {
+                out.push_str(&format!("  {}\n", line));
+            }
+        }
+        out
+    }
+}
+
+/// ダイジェストの出力先を表すトレイトです。
+#[async_trait]
+pub trait DigestSink: Send + Sync {
+    /// ダイジェストを出力します。
+    ///
+    /// # 引数
+    ///
+    /// * `digest` - 出力するダイジェスト
+    /// * `daily` - 1日分をまとめたダイジェストかどうか
+    ///
+    /// # エラー
+    ///
+    /// 出力に失敗した場合、`Error`を返します。
+    async fn deliver(&self, digest: &Digest, daily: bool) -> Result<(), Error>;
+}
+
+/// ダイジェストをファイルに書き込む出力先です。
+pub struct FileSink {
+    settings: DigestSettings,
+}
+
+impl FileSink {
+    /// 新しい`FileSink`を作成します。
+    pub fn new(settings: DigestSettings) -> Self {
+        Self { settings }
+    }
+}
+
+#[async_trait]
+impl DigestSink for FileSink {
+    async fn deliver(&self, digest: &Digest, daily: bool) -> Result<(), Error> {
+        let path = digest.write(&self.settings, daily)?;
+        info!("{}", t!("digest.written", path = path.display()));
+        Ok(())
+    }
+}
+
+/// ダイジェストをChatworkのルーム（デフォルトはマイチャット）に投稿する出力先です。
+pub struct ChatworkSink<T: ChatworkClientTrait> {
+    client: T,
+    settings: DigestSettings,
+    room_id: Mutex<Option<i32>>,
+}
+
+impl<T: ChatworkClientTrait> ChatworkSink<T> {
+    /// 新しい`ChatworkSink`を作成します。
+    ///
+    /// 投稿先は`settings.post_room_id`、指定がなければマイチャットになります。
+    pub fn new(client: T, settings: DigestSettings) -> Self {
+        let room_id = Mutex::new(settings.post_room_id);
+        Self {
+            client,
+            settings,
+            room_id,
+        }
+    }
+
+    /// 投稿先のルームIDを返します。
+    ///
+    /// 指定がない場合はルーム一覧からマイチャットを探し、結果を保持します。
+    async fn room_id(&self) -> Result<i32, Error> {
+        if let Some(room_id) = *self.room_id.lock().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(room_id);
+        }
+
+        let room_id = self
+            .client
+            .fetch_rooms()
+            .await?
+            .into_iter()
+            .find(|room| room.room_type == "my")
+            .map(|room| room.room_id)
+            .ok_or_else(|| Error::Other(anyhow::anyhow!(t!("digest.my_chat_not_found"))))?;
+        *self.room_id.lock().unwrap_or_else(|e| e.into_inner()) = Some(room_id);
+        Ok(room_id)
+    }
+}
+
+#[async_trait]
+impl<T: ChatworkClientTrait + Send + Sync> DigestSink for ChatworkSink<T> {
+    async fn deliver(&self, digest: &Digest, _daily: bool) -> Result<(), Error> {
+        let room_id = self.room_id().await?;
+        let message_id = self
+            .client
+            .post_message(room_id, &digest.render_chatwork(&self.settings))
+            .await?;
+        info!(
+            "{}",
+            t!("digest.posted", room_id = room_id, message_id = message_id)
+        );
+        Ok(())
+    }
+}
+
+/// 実行結果を集め、設定されたタイミングでダイジェストを出力する構造体です。
+pub struct DigestCollector {
+    daily: bool,
+    current: Option<Digest>,
+    sinks: Vec<Box<dyn DigestSink>>,
+}
+
+impl DigestCollector {
+    /// 新しい`DigestCollector`を作成します。
+    ///
+    /// # 引数
+    ///
+    /// * `daily` - `true`の場合は1日ごと、`false`の場合は実行ごとにダイジェストを出力します
+    pub fn new(daily: bool) -> Self {
+        Self {
+            daily,
+            current: None,
+            sinks: Vec::new(),
+        }
+    }
+
+    /// ダイジェストの出力先を追加します。
+    pub fn with_sink(mut self, sink: impl DigestSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// 1回の実行結果を記録し、必要であればダイジェストを出力します。
+    ///
+    /// 1日ごとの場合は、日付（ローカルタイムゾーン）が変わったときに前日分を出力します。
+    ///
+    /// # 戻り値
+    ///
+    /// ダイジェストを出力した場合は`true`を返します。
+    ///
+    /// # エラー
+    ///
+    /// いずれかの出力先への出力に失敗した場合、`Error`を返します。
+    pub async fn record(
+        &mut self,
+        report: &RunReport,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+    ) -> Result<bool, Error> {
+        let mut delivered = false;
+        let same_day = self.current.as_ref().is_some_and(|digest| {
+            digest.started_at().with_timezone(&Local).date_naive()
+                == started_at.with_timezone(&Local).date_naive()
+        });
+        if !self.daily || !same_day {
+            delivered = self.flush().await?;
+        }
+
+        self.current
+            .get_or_insert_with(|| Digest::new(started_at))
+            .add(report, finished_at);
+
+        if !self.daily {
+            delivered = self.flush().await?;
+        }
+        Ok(delivered)
+    }
+
+    /// 記録中のダイジェストを全ての出力先に出力します。
+    ///
+    /// 既読にしたメッセージがない場合は出力しません。
+    /// 一部の出力先で失敗した場合も、残りの出力先への出力を続けます。
+    ///
+    /// # エラー
+    ///
+    /// いずれかの出力先への出力に失敗した場合、最後に発生した`Error`を返します。
+    pub async fn flush(&mut self) -> Result<bool, Error> {
+        let Some(digest) = self.current.take() else {
+            return Ok(false);
+        };
+        if digest.rooms().is_empty() {
+            debug!("{}", t!("digest.nothing_read"));
+            return Ok(false);
+        }
+
+        let mut result = Ok(true);
+        for sink in &self.sinks {
+            if let Err(e) = sink.deliver(&digest, self.daily).await {
+                warn!("{}", t!("digest.deliver_failed", error = e));
+                result = Err(e);
+            }
+        }
+        result
+    }
+}
+
+/// ルームのメッセージの抜粋を、送信日時と送信者を添えた行にして返します。
+///
+/// 抜粋の数が上限を超える場合は、最後に省略した件数を示す行を加えます。
+fn excerpt_lines(room: &RoomDigest, settings: &DigestSettings) -> Vec<String> {
+    let mut lines: Vec<String> = room
+        .messages
+        .iter()
+        .take(settings.max_excerpts)
+        .map(|message| {
+            format!(
+                "{} {}: {}",
+                DateTime::from_timestamp(message.send_time, 0)
+                    .map(|time| format_time(time, "%m/%d %H:%M"))
+                    .unwrap_or_default(),
+                message.account.name,
+                excerpt(&message.body, settings.excerpt_chars)
+            )
+        })
+        .collect();
+    let omitted = room.messages.len().saturating_sub(settings.max_excerpts);
+    if omitted > 0 {
+        lines.push(t!("digest.omitted", count = omitted));
+    }
+    lines
+}
+
+/// ルームの見出し（ルーム名と件数）を返します。ルーム名は出力形式に合わせてエスケープしたものを渡します。
+fn room_heading(room_name: &str, room: &RoomDigest) -> String {
+    t!("digest.room", room = room_name, count = room.messages.len())
+}
+
+/// よく発言した人とその件数を1行で返します。
+fn top_senders(room: &RoomDigest, settings: &DigestSettings) -> String {
+    let senders = room
+        .top_senders(settings.top_senders)
+        .iter()
+        .map(|(name, count)| t!("digest.sender", name = name, count = count))
+        .collect::<Vec<_>>()
+        .join(", ");
+    t!("digest.top_senders", senders = senders)
+}
+
+fn format_time(time: DateTime<Utc>, format: &str) -> String {
+    time.with_timezone(&Local).format(format).to_string()
+}
+
+fn escape_chatwork(value: &str) -> String {
+    value.replace('[', "［").replace(']', "］")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MockChatworkClientTrait;
+    use crate::models::{Account, Room};
+    use crate::report::{RoomOutcome, SkipReason};
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn message(id: &str, sender: &str, body: &str) -> Message {
+        Message {
+            message_id: id.to_string(),
+            account: Account {
+                account_id: 1,
+                name: sender.to_string(),
+            },
+            body: body.to_string(),
+            send_time: 1_700_000_000,
+        }
+    }
+
+    fn read(message_id: &str, messages: &[Message]) -> RoomOutcome {
+        RoomOutcome::Read {
+            message_id: message_id.to_string(),
+            consumed: messages.len(),
+        }
+    }
+
+    fn sample_digest() -> Digest {
+        let dev = vec![
+            message("1", "山田", "おはようございます\n本日の予定です"),
+            message("2", "佐藤", "<b>了解</b>です"),
+            message("3", "山田", "リリースしました"),
+        ];
+        let chat = vec![message("4", "鈴木", "ランチ行きましょう")];
+
+        let mut first = RunReport::default();
+        first.push_with_messages(1, "開発", read("3", &dev), dev);
+        first.push(2, "お知らせ", RoomOutcome::Skipped(SkipReason::Mention));
+        let mut second = RunReport::default();
+        second.push_with_messages(3, "雑談", read("4", &chat), chat);
+
+        let now = Utc::now();
+        let mut digest = Digest::new(now);
+        digest.add(&first, now);
+        digest.add(&second, now);
+        digest
+    }
+
+    #[test]
+    fn test_digest_rooms() {
+        let digest = sample_digest();
+        assert_eq!(digest.runs(), 2);
+
+        let rooms = digest.rooms();
+        assert_eq!(
+            rooms
+                .iter()
+                .map(|r| r.room_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["開発", "雑談"]
+        );
+        assert_eq!(rooms[0].top_senders(1), vec![("山田".to_string(), 2)]);
+        assert!(digest
+            .summary()
+            .starts_with("実行: 2回, 既読: 2ルーム（4件）"));
+    }
+
+    #[test]
+    fn test_digest_render() {
+        let digest = sample_digest();
+        let settings = DigestSettings {
+            max_excerpts: 2,
+            ..Default::default()
+        };
+
+        let markdown = digest.render(&settings);
+        assert!(markdown.contains("\n## 開発（3件）\n"));
+        assert!(markdown.contains("よく発言した人: 山田（2件）, 佐藤（1件）"));
+        assert!(markdown.contains("山田: おはようございます\n"));
+        assert!(markdown.contains("- ほか1件\n"));
+
+        let html = digest.render(&DigestSettings {
+            format: DigestFormat::Html,
+            ..settings.clone()
+        });
+        assert!(html.contains("<h2>開発（3件）</h2>"));
+        assert!(html.contains("&lt;b&gt;了解&lt;/b&gt;です"));
+
+        let text = digest.render(&DigestSettings {
+            format: DigestFormat::Text,
+            ..settings
+        });
+        assert!(text.contains("■ 雑談（1件）"));
+    }
+
+    #[test]
+    fn test_digest_write() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let settings = DigestSettings {
+            output_dir: temp_dir.path().join("digests"),
+            ..Default::default()
+        };
+        let digest = sample_digest();
+
+        let path = digest.write(&settings, true).unwrap();
+        let expected = digest
+            .started_at()
+            .with_timezone(&Local)
+            .format("digest-%Y-%m-%d.md")
+            .to_string();
+        assert_eq!(path.file_name().unwrap().to_str().unwrap(), expected);
+        assert_eq!(fs::read_to_string(path).unwrap(), digest.render(&settings));
+    }
+
+    #[tokio::test]
+    async fn test_digest_collector_daily() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let settings = DigestSettings {
+            output_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let messages = vec![message("1", "山田", "おはようございます")];
+        let mut report = RunReport::default();
+        report.push_with_messages(1, "開発", read("1", &messages), messages);
+
+        let day = |day: u32, hour: u32| {
+            Local
+                .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let mut collector = DigestCollector::new(true).with_sink(FileSink::new(settings.clone()));
+        assert!(!collector
+            .record(&report, day(1, 9), day(1, 9))
+            .await
+            .unwrap());
+        assert!(!collector
+            .record(&report, day(1, 18), day(1, 18))
+            .await
+            .unwrap());
+
+        // 日付が変わると前日分を出力する
+        assert!(collector
+            .record(&report, day(2, 9), day(2, 9))
+            .await
+            .unwrap());
+        let first = temp_dir.path().join("digest-2024-01-01.md");
+        assert!(fs::read_to_string(first)
+            .unwrap()
+            .contains("## 開発（2件）"));
+
+        assert!(collector.flush().await.unwrap());
+        assert!(temp_dir.path().join("digest-2024-01-02.md").exists());
+
+        // 実行ごとの場合は、既読にしたメッセージがある実行のみ出力する
+        let mut collector = DigestCollector::new(false).with_sink(FileSink::new(settings));
+        assert!(collector
+            .record(&report, day(3, 9), day(3, 9))
+            .await
+            .unwrap());
+        assert!(!collector
+            .record(&RunReport::default(), day(3, 10), day(3, 10))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_chatwork_sink_posts_to_my_chat() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    room_type: "group".to_string(),
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 99,
+                    room_type: "my".to_string(),
+                    ..Default::default()
+                },
+            ])
+        });
+        mock_client
+            .expect_post_message()
+            .withf(|room_id, body| {
+                *room_id == 99
+                    && body.starts_with("[info][title]既読ダイジェスト（")
+                    && body.contains("■ 開発（3件）")
+                    && body.ends_with("[/info]")
+            })
+            .times(2)
+            .returning(|_, _| Ok("1000".to_string()));
+
+        let sink = ChatworkSink::new(mock_client, DigestSettings::default());
+        let digest = sample_digest();
+        sink.deliver(&digest, false).await.unwrap();
+        // マイチャットのルームIDは保持され、2回目はルーム一覧を取得しない
+        sink.deliver(&digest, false).await.unwrap();
+    }
+
+    #[test]
+    fn test_digest_render_chatwork_escapes_tags() {
+        let messages = vec![message("1", "山田", "[To:123]確認お願いします[info]")];
+        let mut report = RunReport::default();
+        report.push_with_messages(1, "開発", read("1", &messages), messages);
+        let mut digest = Digest::new(Utc::now());
+        digest.add(&report, Utc::now());
+
+        let body = digest.render_chatwork(&DigestSettings::default());
+        assert!(body.contains("山田: ［To:123］確認お願いします［info］"));
+        assert_eq!(body.matches("[info]").count(), 1);
+    }
+}
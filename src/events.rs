@@ -0,0 +1,710 @@
+//! 実行イベントモジュール
+//!
+//! このモジュールは、`MessageProcessor`の実行中に発生するイベント（実行の開始・終了、
+//! ルームの既読・スキップ・失敗）を表す型と、それらを外部に送信するための機能を提供します。
+//! Webhookへの送信はバックグラウンドのタスクで行うため、送信先が停止していても既読処理は待たされません。
+//! Webhookの送信に失敗したイベントは件数に上限のあるファイルに保存され、
+//! 次に送信できたときに古い順に再送されます。
+
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::Error;
+use crate::report::{RoomOutcome, RoomReport, RunReport};
+use crate::settings::EventWebhookSettings;
+use crate::t;
+
+/// 署名を付与するヘッダーの名前です。
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// 1回の送信を待つ時間の上限です。
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 実行中に発生したイベントです。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// 実行を開始した
+    RunStarted {
+        /// 実行の識別子
+        run_id: String,
+        /// 発生日時
+        timestamp: DateTime<Utc>,
+    },
+    /// ルームのメッセージを既読にした
+    RoomRead {
+        /// 実行の識別子
+        run_id: String,
+        /// 発生日時
+        timestamp: DateTime<Utc>,
+        /// ルームID
+        room_id: i32,
+        /// ルーム名
+        room_name: String,
+        /// 既読にした最新のメッセージのID
+        message_id: String,
+        /// 既読にした未読メッセージの数
+        consumed: usize,
+    },
+    /// ルームを既読にしなかった
+    RoomSkipped {
+        /// 実行の識別子
+        run_id: String,
+        /// 発生日時
+        timestamp: DateTime<Utc>,
+        /// ルームID
+        room_id: i32,
+        /// ルーム名
+        room_name: String,
+        /// 理由の識別子（既読にできるメッセージがなかった場合は`nothing_to_read`）
+        reason: String,
+        /// 理由の説明
+        detail: String,
+    },
+    /// ルームの処理に失敗した
+    RoomFailed {
+        /// 実行の識別子
+        run_id: String,
+        /// 発生日時
+        timestamp: DateTime<Utc>,
+        /// ルームID
+        room_id: i32,
+        /// ルーム名
+        room_name: String,
+        /// エラーの内容
+        error: String,
+    },
+    /// 実行を終了した
+    RunFinished {
+        /// 実行の識別子
+        run_id: String,
+        /// 発生日時
+        timestamp: DateTime<Utc>,
+        /// 既読にしたルームの数
+        read_rooms: usize,
+        /// 既読にした未読メッセージの数
+        consumed_messages: usize,
+        /// スキップしたルームの数
+        skipped_rooms: usize,
+        /// 処理に失敗したルームの数
+        failed_rooms: usize,
+        /// 実行全体が失敗した場合のエラーの内容
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl RunEvent {
+    /// 実行の開始イベントを作成します。
+    pub fn run_started(run_id: &str) -> Self {
+        RunEvent::RunStarted {
+            run_id: run_id.to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// ルームの処理結果からイベントを作成します。
+    pub fn room(run_id: &str, room: &RoomReport) -> Self {
+        let run_id = run_id.to_string();
+        let timestamp = Utc::now();
+        let room_id = room.room_id;
+        let room_name = room.room_name.clone();
+        match &room.outcome {
+            RoomOutcome::Read {
+                message_id,
+                consumed,
+            } => RunEvent::RoomRead {
+                run_id,
+                timestamp,
+                room_id,
+                room_name,
+                message_id: message_id.clone(),
+                consumed: *consumed,
+            },
+            RoomOutcome::NothingToRead => RunEvent::RoomSkipped {
+                run_id,
+                timestamp,
+                room_id,
+                room_name,
+                reason: "nothing_to_read".to_string(),
+                detail: t!("events.nothing_to_read"),
+            },
+            RoomOutcome::Skipped(reason) => RunEvent::RoomSkipped {
+                run_id,
+                timestamp,
+                room_id,
+                room_name,
+                reason: reason.key().to_string(),
+                detail: reason.to_string(),
+            },
+            RoomOutcome::Failed(error) => RunEvent::RoomFailed {
+                run_id,
+                timestamp,
+                room_id,
+                room_name,
+                error: error.clone(),
+            },
+        }
+    }
+
+    /// 実行の終了イベントを作成します。
+    ///
+    /// # 引数
+    ///
+    /// * `run_id` - 実行の識別子
+    /// * `report` - 実行結果のレポート
+    /// * `error` - 実行全体が失敗した場合のエラーの内容
+    pub fn run_finished(run_id: &str, report: &RunReport, error: Option<String>) -> Self {
+        RunEvent::RunFinished {
+            run_id: run_id.to_string(),
+            timestamp: Utc::now(),
+            read_rooms: report.read_rooms(),
+            consumed_messages: report.consumed_messages(),
+            skipped_rooms: report.skipped_rooms(),
+            failed_rooms: report.failed_rooms(),
+            error,
+        }
+    }
+}
+
+/// 実行イベントの送信先を表すトレイトです。
+///
+/// 送信の失敗は送信先ごとに処理され、既読処理には影響しません。
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// イベントを送信します。
+    async fn emit(&self, event: &RunEvent);
+
+    /// それまでに受け取ったイベントの送信（または保存）が終わるまで待ちます。
+    ///
+    /// プロセスの終了前に呼び出します。デフォルトでは何もしません。
+    async fn flush(&self) {}
+}
+
+/// 複数の`MessageProcessor`（アカウント）で1つの送信先を共有するための実装です。
+#[async_trait]
+impl<T: EventSink + ?Sized> EventSink for std::sync::Arc<T> {
+    async fn emit(&self, event: &RunEvent) {
+        (**self).emit(event).await;
+    }
+
+    async fn flush(&self) {
+        (**self).flush().await;
+    }
+}
+
+/// 送信できなかったイベントを保存する、件数に上限のあるJSONLファイルです。
+pub struct EventQueue {
+    path: PathBuf,
+    capacity: usize,
+}
+
+impl EventQueue {
+    /// 指定されたパスと上限のキューを作成します。
+    pub fn new(path: impl Into<PathBuf>, capacity: usize) -> Self {
+        Self {
+            path: path.into(),
+            capacity,
+        }
+    }
+
+    /// 保存されているイベントを古い順に読み込みます。
+    ///
+    /// 解析できない行は無視します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み込みに失敗した場合、`Error`を返します。
+    pub fn load(&self) -> Result<VecDeque<RunEvent>, Error> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut events = VecDeque::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            match serde_json::from_str(&line) {
+                Ok(event) => events.push_back(event),
+                Err(e) if !line.trim().is_empty() => {
+                    warn!("{}", t!("events.queue_parse_failed", error = e))
+                }
+                Err(_) => {}
+            }
+        }
+        Ok(events)
+    }
+
+    /// キューの末尾にイベントを追加します。
+    ///
+    /// 上限を超える場合は古いイベントから破棄します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み書きに失敗した場合、`Error`を返します。
+    pub fn push(&self, event: &RunEvent) -> Result<(), Error> {
+        let mut events = self.load()?;
+        events.push_back(event.clone());
+        self.store(&mut events)
+    }
+
+    /// キューの内容を指定されたイベントで置き換えます。
+    ///
+    /// 上限を超える場合は古いイベントから破棄します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの書き込みに失敗した場合、`Error`を返します。
+    pub fn store(&self, events: &mut VecDeque<RunEvent>) -> Result<(), Error> {
+        let overflow = events.len().saturating_sub(self.capacity);
+        if overflow > 0 {
+            warn!(
+                "{}",
+                t!(
+                    "events.queue_overflow",
+                    capacity = self.capacity,
+                    count = overflow
+                )
+            );
+            events.drain(..overflow);
+        }
+
+        if events.is_empty() {
+            match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => return Ok(()),
+            }
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        for event in events.iter() {
+            let mut line = serde_json::to_string(event)?;
+            line.push('\n');
+            file.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// 送信の結果です。
+enum Delivery {
+    /// 送信できた
+    Delivered,
+    /// 送信先が受け付けなかった（再送しない）
+    Rejected,
+    /// 送信先に到達できなかった（後で再送する）
+    Unavailable,
+}
+
+/// 送信タスクへの指示です。
+enum Command {
+    /// イベントを送信する
+    Emit(RunEvent),
+    /// それまでの指示を処理し終えたことを知らせる
+    Flush(oneshot::Sender<()>),
+}
+
+/// 実行イベントのJSONをWebhookにPOSTする送信先です。
+///
+/// `secret`が設定されている場合、本文のHMAC-SHA256署名を`X-Signature-256`ヘッダーに
+/// `sha256=<16進数>`の形式で付与します。
+/// イベントはバックグラウンドのタスクが受け取った順に送信するため、`emit`は送信を待ちません。
+pub struct WebhookEventSink {
+    sender: mpsc::UnboundedSender<Command>,
+}
+
+impl WebhookEventSink {
+    /// 設定から新しい`WebhookEventSink`を作成し、送信タスクを開始します。
+    ///
+    /// Tokioのランタイムの中で呼び出す必要があります。
+    pub fn new(settings: EventWebhookSettings) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut worker = Worker::new(settings);
+        tokio::spawn(async move {
+            while let Some(command) = receiver.recv().await {
+                match command {
+                    Command::Emit(event) => worker.handle(&event).await,
+                    Command::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    /// 本文の署名を`sha256=<16進数>`の形式で返します。
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMACは任意の長さの鍵を受け付けます");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookEventSink {
+    async fn emit(&self, event: &RunEvent) {
+        if self.sender.send(Command::Emit(event.clone())).is_err() {
+            warn!("{}", t!("events.worker_stopped"));
+        }
+    }
+
+    async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(Command::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+}
+
+/// バックグラウンドでイベントを送信するタスクの状態です。
+struct Worker {
+    client: Client,
+    settings: EventWebhookSettings,
+    queue: EventQueue,
+    /// 直前の送信で送信先に到達できたかどうか
+    available: bool,
+}
+
+impl Worker {
+    fn new(settings: EventWebhookSettings) -> Self {
+        let queue = EventQueue::new(&settings.queue_path, settings.queue_capacity);
+        Self {
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            settings,
+            queue,
+            available: true,
+        }
+    }
+
+    /// イベントを1件処理します。
+    ///
+    /// 送信先に到達できなくなった後は、次の実行の開始イベントまで送信を試みず、
+    /// 受け取ったイベントをそのままキューに追加します。
+    async fn handle(&mut self, event: &RunEvent) {
+        let result = if self.available || matches!(event, RunEvent::RunStarted { .. }) {
+            self.deliver(event).await
+        } else {
+            self.queue.push(event)
+        };
+        if let Err(e) = result {
+            warn!("{}", t!("events.queue_io_failed", error = e));
+        }
+    }
+
+    /// キューに残っているイベントを送信し、その後に新しいイベントを送信します。
+    ///
+    /// 送信先に到達できなくなった時点で、残りのイベントをキューに保存します。
+    async fn deliver(&mut self, event: &RunEvent) -> Result<(), Error> {
+        let mut pending = self.queue.load()?;
+        let queued = pending.len();
+        pending.push_back(event.clone());
+
+        while let Some(next) = pending.front() {
+            match self.send_with_retry(next).await {
+                Delivery::Delivered | Delivery::Rejected => {
+                    pending.pop_front();
+                }
+                Delivery::Unavailable => break,
+            }
+        }
+
+        self.available = pending.is_empty();
+        if !pending.is_empty() {
+            warn!("{}", t!("events.queued", count = pending.len()));
+        } else if queued > 0 {
+            info!("{}", t!("events.resent", count = queued));
+        }
+        self.queue.store(&mut pending)
+    }
+
+    /// 再試行しながらイベントを1件送信します。
+    async fn send_with_retry(&self, event: &RunEvent) -> Delivery {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("{}", t!("events.serialize_failed", error = e));
+                return Delivery::Rejected;
+            }
+        };
+
+        let mut delay = Duration::from_millis(self.settings.retry_delay_ms);
+        for attempt in 0..=self.settings.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+
+            let mut request = self
+                .client
+                .post(&self.settings.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            for (name, value) in &self.settings.headers {
+                request = request.header(name, value);
+            }
+            if let Some(secret) = &self.settings.secret {
+                request = request.header(
+                    SIGNATURE_HEADER,
+                    WebhookEventSink::sign(secret.expose(), &body),
+                );
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => return Delivery::Delivered,
+                Ok(response)
+                    if response.status().is_client_error()
+                        && response.status() != StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    warn!("{}", t!("events.rejected", status = response.status()));
+                    return Delivery::Rejected;
+                }
+                Ok(response) => warn!(
+                    "{}",
+                    t!(
+                        "events.send_failed_status",
+                        status = response.status(),
+                        attempt = attempt + 1
+                    )
+                ),
+                Err(e) => warn!(
+                    "{}",
+                    t!("events.send_failed", attempt = attempt + 1, error = e)
+                ),
+            }
+        }
+        Delivery::Unavailable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::SkipReason;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn settings(url: &str, temp_dir: &TempDir) -> EventWebhookSettings {
+        EventWebhookSettings {
+            url: url.to_string(),
+            headers: BTreeMap::from([("x-team".to_string(), "dashboard".to_string())]),
+            secret: Some("secret".into()),
+            max_retries: 2,
+            retry_delay_ms: 1,
+            queue_path: temp_dir.path().join("queue.jsonl"),
+            queue_capacity: 2,
+        }
+    }
+
+    /// `settings`と同じファイルのキューを返します。
+    fn queue(temp_dir: &TempDir) -> EventQueue {
+        EventQueue::new(temp_dir.path().join("queue.jsonl"), 2)
+    }
+
+    #[test]
+    fn test_run_event_from_report() {
+        let mut report = RunReport::default();
+        report.push(
+            1,
+            "開発",
+            RoomOutcome::Read {
+                message_id: "10".to_string(),
+                consumed: 2,
+            },
+        );
+        report.push(2, "雑談", RoomOutcome::Skipped(SkipReason::Mention));
+        report.push(3, "bot", RoomOutcome::NothingToRead);
+
+        let json = serde_json::to_value(RunEvent::room("run", &report.rooms[0])).unwrap();
+        assert_eq!(json["event"], "room_read");
+        assert_eq!(json["consumed"], 2);
+
+        let json = serde_json::to_value(RunEvent::room("run", &report.rooms[1])).unwrap();
+        assert_eq!(json["event"], "room_skipped");
+        assert_eq!(json["reason"], "mention");
+
+        let json = serde_json::to_value(RunEvent::room("run", &report.rooms[2])).unwrap();
+        assert_eq!(json["reason"], "nothing_to_read");
+
+        let json = serde_json::to_value(RunEvent::run_finished("run", &report, None)).unwrap();
+        assert_eq!(json["event"], "run_finished");
+        assert_eq!(json["read_rooms"], 1);
+        assert_eq!(json["skipped_rooms"], 1);
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn test_sign() {
+        // printf '{}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            WebhookEventSink::sign("secret", b"{}"),
+            "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_event_sink_signs_and_retries() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let server = MockServer::start().await;
+        let event = RunEvent::run_started("run");
+        let body = serde_json::to_vec(&event).unwrap();
+
+        // 最初の1回は失敗し、再試行で成功する
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/events"))
+            .and(header("x-team", "dashboard"))
+            .and(header(
+                SIGNATURE_HEADER,
+                WebhookEventSink::sign("secret", &body).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let sink = WebhookEventSink::new(settings(&format!("{}/events", server.uri()), &temp_dir));
+        sink.emit(&event).await;
+        sink.flush().await;
+        assert!(queue(&temp_dir).load().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_event_sink_queues_when_unavailable() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        // 受信側が停止している間のイベントは上限までキューに保存される
+        let uri = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let sink = WebhookEventSink::new(settings(&uri, &temp_dir));
+        for run_id in ["run1", "run2", "run3"] {
+            sink.emit(&RunEvent::run_started(run_id)).await;
+        }
+        sink.flush().await;
+        let queued: Vec<RunEvent> = queue(&temp_dir).load().unwrap().into();
+        assert_eq!(queued.len(), 2);
+        assert!(matches!(&queued[0], RunEvent::RunStarted { run_id, .. } if run_id == "run2"));
+
+        // 受信側が復旧すると、キューのイベントを古い順に送信してから新しいイベントを送信する
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(3)
+            .mount(&server)
+            .await;
+        let sink = WebhookEventSink::new(settings(&server.uri(), &temp_dir));
+        sink.emit(&RunEvent::run_started("run4")).await;
+        sink.flush().await;
+        assert!(queue(&temp_dir).load().unwrap().is_empty());
+
+        let received: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                serde_json::from_slice::<serde_json::Value>(&request.body).unwrap()["run_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(received, vec!["run2", "run3", "run4"]);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_event_sink_drops_rejected_event() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let sink = WebhookEventSink::new(settings(&server.uri(), &temp_dir));
+        sink.emit(&RunEvent::run_started("run")).await;
+        sink.flush().await;
+        assert!(queue(&temp_dir).load().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_event_sink_skips_sending_while_unavailable() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let server = MockServer::start().await;
+        // 最初のイベントの送信（再試行を含めて3回）と、次の実行の開始イベントの送信のみを受け取る
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(6)
+            .mount(&server)
+            .await;
+
+        let sink = WebhookEventSink::new(settings(&server.uri(), &temp_dir));
+        let report = RunReport::default();
+        sink.emit(&RunEvent::run_started("run1")).await;
+        sink.emit(&RunEvent::run_finished("run1", &report, None))
+            .await;
+        sink.flush().await;
+        let queued: Vec<RunEvent> = queue(&temp_dir).load().unwrap().into();
+        assert_eq!(queued.len(), 2);
+        assert!(matches!(&queued[1], RunEvent::RunFinished { .. }));
+
+        // 次の実行の開始イベントで再び送信を試みる
+        sink.emit(&RunEvent::run_started("run2")).await;
+        sink.flush().await;
+        let queued: Vec<RunEvent> = queue(&temp_dir).load().unwrap().into();
+        assert_eq!(queued.len(), 2);
+        assert!(matches!(&queued[1], RunEvent::RunStarted { run_id, .. } if run_id == "run2"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_event_sink_does_not_wait_for_delivery() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let sink = WebhookEventSink::new(settings(&server.uri(), &temp_dir));
+        let started = std::time::Instant::now();
+        sink.emit(&RunEvent::run_started("run")).await;
+        assert!(started.elapsed() < Duration::from_millis(500));
+        sink.flush().await;
+        assert!(started.elapsed() >= Duration::from_millis(500));
+    }
+}
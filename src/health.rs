@@ -0,0 +1,454 @@
+//! ヘルスチェックモジュール
+//!
+//! このモジュールは、最後に成功した実行・最後のエラー・レート制限の状態・APIトークンの有効性を
+//! アカウントごとに記録し、常駐して実行するモードの死活監視のために`/healthz`と`/readyz`として公開する機能を提供します。
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+use crate::settings::HealthSettings;
+use crate::t;
+
+/// Chatwork APIのレート制限の状態です。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimit {
+    /// 制限期間内に実行できるリクエストの数
+    pub limit: Option<u32>,
+    /// 残りのリクエストの数
+    pub remaining: Option<u32>,
+    /// 制限がリセットされる日時
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    /// レスポンスヘッダーからレート制限の状態を読み取ります。
+    ///
+    /// レート制限に関するヘッダーがない場合は`None`を返します。
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<i64>().ok())
+        };
+        let rate_limit = Self {
+            limit: value("x-ratelimit-limit").map(|limit| limit as u32),
+            remaining: value("x-ratelimit-remaining").map(|remaining| remaining as u32),
+            reset_at: value("x-ratelimit-reset")
+                .and_then(|reset| Utc.timestamp_opt(reset, 0).single()),
+        };
+        (rate_limit.limit.is_some() || rate_limit.remaining.is_some()).then_some(rate_limit)
+    }
+
+    /// 指定された日時にレート制限を受けている状態かどうかを返します。
+    pub fn is_limited(&self, now: DateTime<Utc>) -> bool {
+        self.remaining == Some(0) && self.reset_at.is_none_or(|reset_at| reset_at > now)
+    }
+}
+
+/// 記録された稼働状態です。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthStatus {
+    /// 最後に実行が成功した日時
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// 最後に発生したエラー
+    pub last_error: Option<String>,
+    /// 最後にエラーが発生した日時
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// 最後に受け取ったレート制限の状態
+    pub rate_limit: Option<RateLimit>,
+    /// APIトークンが有効かどうか（まだAPIを呼び出していない場合は`None`）
+    pub token_valid: Option<bool>,
+}
+
+/// 1つのアカウントの稼働状態です。
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountHealth {
+    /// `[[accounts]]`に記述されたアカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+    pub account: Option<String>,
+    /// 記録された稼働状態
+    #[serde(flatten)]
+    pub state: HealthStatus,
+    /// 現在レート制限を受けているかどうか
+    pub rate_limited: bool,
+}
+
+/// `/healthz`と`/readyz`が返す判定結果です。
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// 判定結果（`ok` / `failing`）
+    pub status: &'static str,
+    /// 失敗と判定した理由（いずれかのアカウントが失敗した場合は、そのアカウントの理由）
+    pub reasons: Vec<String>,
+    /// 起動した日時
+    pub started_at: DateTime<Utc>,
+    /// アカウントごとの稼働状態
+    pub accounts: Vec<AccountHealth>,
+}
+
+impl HealthReport {
+    /// 判定結果が正常かどうかを返します。
+    pub fn is_ok(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+/// アプリケーションの稼働状態を記録する構造体です。
+///
+/// 稼働状態はアカウントごとに記録し、いずれかのアカウントが失敗した場合に失敗と判定します。
+/// 各メソッドの`account`には`[[accounts]]`に記述されたアカウントの名前
+/// （`[[accounts]]`を使用していない場合は`None`）を指定します。
+pub struct Health {
+    started_at: DateTime<Utc>,
+    statuses: Mutex<BTreeMap<Option<String>, HealthStatus>>,
+}
+
+impl Health {
+    /// 新しい`Health`インスタンスを作成します。
+    fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            statuses: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// アカウントを登録し、まだ実行していないアカウントも判定の対象にします。
+    pub fn register(&self, account: Option<&str>) {
+        self.update(account, |_| {});
+    }
+
+    /// 記録されたアカウントの稼働状態を返します。
+    pub fn status(&self, account: Option<&str>) -> HealthStatus {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&account.map(str::to_string))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 実行が成功したことを記録します。
+    pub fn record_success(&self, account: Option<&str>, at: DateTime<Utc>) {
+        self.update(account, |status| status.last_success_at = Some(at));
+    }
+
+    /// エラーが発生したことを記録します。
+    pub fn record_error(&self, account: Option<&str>, error: &str, at: DateTime<Utc>) {
+        self.update(account, |status| {
+            status.last_error = Some(error.to_string());
+            status.last_error_at = Some(at);
+        });
+    }
+
+    /// APIのレスポンスから、トークンの有効性とレート制限の状態を記録します。
+    pub fn record_response(
+        &self,
+        account: Option<&str>,
+        status_code: reqwest::StatusCode,
+        headers: &HeaderMap,
+    ) {
+        self.update(account, |status| {
+            if status_code == reqwest::StatusCode::UNAUTHORIZED {
+                status.token_valid = Some(false);
+            } else if status_code.is_success() {
+                status.token_valid = Some(true);
+            }
+            if let Some(rate_limit) = RateLimit::from_headers(headers) {
+                status.rate_limit = Some(rate_limit);
+            }
+        });
+    }
+
+    /// アカウントの稼働状態を更新します。
+    fn update(&self, account: Option<&str>, f: impl FnOnce(&mut HealthStatus)) {
+        let mut statuses = self.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        f(statuses.entry(account.map(str::to_string)).or_default());
+    }
+
+    /// プロセスが正常に稼働しているか（`/healthz`）を判定します。
+    ///
+    /// いずれかのアカウントで、APIトークンが無効な場合や、最後に成功した実行
+    /// （まだ成功していない場合は起動）から`unhealthy_after_secs`が経過した場合に失敗と判定します。
+    pub fn liveness(&self, settings: &HealthSettings, now: DateTime<Utc>) -> HealthReport {
+        self.report(now, |state, reasons| {
+            if state.token_valid == Some(false) {
+                reasons.push(t!("health.token_invalid"));
+            }
+            let since = state.last_success_at.unwrap_or(self.started_at);
+            if now - since > Duration::seconds(settings.unhealthy_after_secs as i64) {
+                reasons.push(t!(
+                    "health.no_success_for",
+                    secs = settings.unhealthy_after_secs
+                ));
+            }
+        })
+    }
+
+    /// リクエストを受け付けられる状態か（`/readyz`）を判定します。
+    ///
+    /// いずれかのアカウントで、まだ実行が成功していない場合、最後に成功した実行から
+    /// `stale_after_secs`が経過した場合、APIトークンが無効な場合、レート制限を受けている場合に
+    /// 失敗と判定します。
+    pub fn readiness(&self, settings: &HealthSettings, now: DateTime<Utc>) -> HealthReport {
+        self.report(now, |state, reasons| {
+            if state.token_valid == Some(false) {
+                reasons.push(t!("health.token_invalid"));
+            }
+            match state.last_success_at {
+                None => reasons.push(t!("health.no_success_yet")),
+                Some(at) if now - at > Duration::seconds(settings.stale_after_secs as i64) => {
+                    reasons.push(t!(
+                        "health.no_success_for",
+                        secs = settings.stale_after_secs
+                    ))
+                }
+                Some(_) => {}
+            }
+            if state
+                .rate_limit
+                .as_ref()
+                .is_some_and(|rate_limit| rate_limit.is_limited(now))
+            {
+                reasons.push(t!("health.rate_limited"));
+            }
+        })
+    }
+
+    /// アカウントごとに判定し、判定結果をまとめます。
+    ///
+    /// まだアカウントが記録されていない場合は、`[[accounts]]`を使用していないアカウントとして判定します。
+    ///
+    /// # 引数
+    ///
+    /// * `now` - 現在の日時
+    /// * `check` - アカウントの稼働状態を判定し、失敗と判定した理由を追加するクロージャ
+    fn report(
+        &self,
+        now: DateTime<Utc>,
+        check: impl Fn(&HealthStatus, &mut Vec<String>),
+    ) -> HealthReport {
+        let mut statuses = self
+            .statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if statuses.is_empty() {
+            statuses.insert(None, HealthStatus::default());
+        }
+
+        let mut reasons = Vec::new();
+        let mut accounts = Vec::with_capacity(statuses.len());
+        for (account, state) in statuses {
+            let mut account_reasons = Vec::new();
+            check(&state, &mut account_reasons);
+            reasons.extend(account_reasons.into_iter().map(|reason| match &account {
+                Some(name) => t!("health.account_reason", account = name, reason = reason),
+                None => reason,
+            }));
+            let rate_limited = state
+                .rate_limit
+                .as_ref()
+                .is_some_and(|rate_limit| rate_limit.is_limited(now));
+            accounts.push(AccountHealth {
+                account,
+                state,
+                rate_limited,
+            });
+        }
+        HealthReport {
+            status: if reasons.is_empty() { "ok" } else { "failing" },
+            reasons,
+            started_at: self.started_at,
+            accounts,
+        }
+    }
+}
+
+/// アプリケーション全体で共有する稼働状態を返します。
+pub fn health() -> &'static Health {
+    static HEALTH: OnceLock<Health> = OnceLock::new();
+    HEALTH.get_or_init(|| Health::new(Utc::now()))
+}
+
+/// `/healthz`と`/readyz`で稼働状態を公開するルーターを作成します。
+///
+/// 失敗と判定した場合は`503 Service Unavailable`を返します。
+pub fn router(settings: HealthSettings) -> Router {
+    Router::new()
+        .route(
+            "/healthz",
+            get(|State(settings): State<HealthSettings>| async move {
+                respond(health().liveness(&settings, Utc::now()))
+            }),
+        )
+        .route(
+            "/readyz",
+            get(|State(settings): State<HealthSettings>| async move {
+                respond(health().readiness(&settings, Utc::now()))
+            }),
+        )
+        .with_state(settings)
+}
+
+/// 判定結果をレスポンスに変換します。
+fn respond(report: HealthReport) -> (StatusCode, Json<HealthReport>) {
+    let status = if report.is_ok() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn settings() -> HealthSettings {
+        HealthSettings {
+            stale_after_secs: 600,
+            unhealthy_after_secs: 1800,
+        }
+    }
+
+    fn rate_limit_headers(remaining: &str, reset: i64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", "300".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", remaining.parse().unwrap());
+        headers.insert("x-ratelimit-reset", reset.to_string().parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_rate_limit_from_headers() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+        let rate_limit =
+            RateLimit::from_headers(&rate_limit_headers("0", now.timestamp() + 60)).unwrap();
+        assert_eq!(rate_limit.limit, Some(300));
+        assert_eq!(rate_limit.remaining, Some(0));
+        assert!(rate_limit.is_limited(now));
+        assert!(!rate_limit.is_limited(now + Duration::seconds(61)));
+
+        assert!(RateLimit::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_liveness_becomes_failing_when_stale() {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+        let health = Health::new(started_at);
+
+        // 起動直後はまだ成功していなくても稼働中とみなす
+        assert!(health.liveness(&settings(), started_at).is_ok());
+        let report = health.liveness(&settings(), started_at + Duration::seconds(1801));
+        assert_eq!(report.status, "failing");
+
+        health.record_success(None, started_at + Duration::seconds(1800));
+        assert!(health
+            .liveness(&settings(), started_at + Duration::seconds(1801))
+            .is_ok());
+
+        health.record_response(None, reqwest::StatusCode::UNAUTHORIZED, &HeaderMap::new());
+        let report = health.liveness(&settings(), started_at + Duration::seconds(1801));
+        assert_eq!(report.reasons, vec!["APIトークンが無効です"]);
+    }
+
+    #[test]
+    fn test_readiness() {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+        let health = Health::new(started_at);
+        assert!(!health.readiness(&settings(), started_at).is_ok());
+
+        health.record_success(None, started_at);
+        health.record_response(None, reqwest::StatusCode::OK, &HeaderMap::new());
+        let report = health.readiness(&settings(), started_at + Duration::seconds(60));
+        assert!(report.is_ok());
+        assert_eq!(report.accounts[0].state.token_valid, Some(true));
+
+        // レート制限を受けている間は準備ができていないと判定する
+        health.record_response(
+            None,
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            &rate_limit_headers("0", started_at.timestamp() + 300),
+        );
+        let report = health.readiness(&settings(), started_at + Duration::seconds(60));
+        assert!(report.accounts[0].rate_limited);
+        assert_eq!(report.reasons, vec!["APIのレート制限を受けています"]);
+
+        let report = health.readiness(&settings(), started_at + Duration::seconds(601));
+        assert_eq!(report.reasons, vec!["600秒以上実行が成功していません"]);
+
+        health.record_error(None, "APIエラー", started_at + Duration::seconds(601));
+        assert_eq!(health.status(None).last_error.as_deref(), Some("APIエラー"));
+    }
+
+    #[test]
+    fn test_health_is_kept_per_account() {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+        let health = Health::new(started_at);
+        health.register(Some("work"));
+        health.register(Some("personal"));
+
+        health.record_success(Some("work"), started_at);
+        health.record_response(Some("work"), reqwest::StatusCode::OK, &HeaderMap::new());
+        let report = health.readiness(&settings(), started_at + Duration::seconds(60));
+        assert_eq!(
+            report.reasons,
+            vec!["アカウントpersonal: まだ実行が成功していません"]
+        );
+
+        // いずれかのアカウントが失敗していれば失敗と判定する
+        health.record_success(Some("personal"), started_at);
+        health.record_response(
+            Some("personal"),
+            reqwest::StatusCode::UNAUTHORIZED,
+            &HeaderMap::new(),
+        );
+        let report = health.liveness(&settings(), started_at + Duration::seconds(60));
+        assert_eq!(report.status, "failing");
+        assert_eq!(
+            report.reasons,
+            vec!["アカウントpersonal: APIトークンが無効です"]
+        );
+        assert_eq!(health.status(Some("work")).token_valid, Some(true));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["accounts"][0]["account"], "personal");
+        assert_eq!(json["accounts"][0]["token_valid"], false);
+        assert_eq!(json["accounts"][1]["account"], "work");
+        assert_eq!(json["accounts"][1]["token_valid"], true);
+    }
+
+    #[tokio::test]
+    async fn test_router() {
+        let router = router(settings());
+
+        let response = router
+            .clone()
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert!(json.get("started_at").is_some());
+
+        health().record_success(None, Utc::now());
+        let response = router
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,268 @@
+//! 日本語と英語のメッセージカタログです。
+//!
+//! 各要素は`(キー, 日本語, 英語)`で、メッセージ中の`{名前}`は引数の値に置き換えられます。
+
+/// キーごとの日本語と英語のメッセージです。
+pub(super) const MESSAGES: &[(&str, &str, &str)] = &[
+    ("processor.fetch_room_failed", "ルーム{room_id}の情報の取得に失敗しました: {error}", "Failed to fetch room {room_id}: {error}"),
+    ("processor.rooms_found", "処理対象のルームが{count}個見つかりました", "Found {count} rooms to process"),
+    ("processor.open_tasks_found", "自分宛ての未完了タスクが{count}件見つかりました", "Found {count} open tasks assigned to me"),
+    ("processor.fetch_my_tasks_failed", "自分宛ての未完了タスクの取得に失敗しました。タスクがあるルームは既読にしません: {error}", "Failed to fetch my open tasks. Rooms with my tasks are not read: {error}"),
+    ("processor.processing_room", "ルームを処理中: {index} / {total} (ID: {room_id})", "Processing room {index} / {total} (ID: {room_id})"),
+    ("processor.all_rooms_done", "全てのルームの処理が完了しました: {report}", "Finished processing all rooms: {report}"),
+    ("processor.room_skipped", "ルーム{room_id}をスキップします: {reason}", "Skipping room {room_id}: {reason}"),
+    ("processor.room_succeeded", "ルーム{room_id}の処理が成功しました", "Processed room {room_id} successfully"),
+    ("processor.room_failed", "ルーム{room_id}の処理に失敗しました: {error}", "Failed to process room {room_id}: {error}"),
+    ("processor.blocked_at", "メッセージ{message_id}で既読を止めます: {reason}", "Stopping before message {message_id}: {reason}"),
+    ("processor.archive_failed", "メッセージのアーカイブに失敗しました: {error}", "Failed to archive messages: {error}"),
+    ("processor.notifying_blocked", "既読を止めたことを通知します: {subject}", "Notifying that reading was stopped: {subject}"),
+    ("processor.notify_failed", "通知の送信に失敗しました: {error}", "Failed to send a notification: {error}"),
+    ("processor.searching_target", "{count}個のメッセージから対象のメッセージを検索中", "Searching {count} messages for the target message"),
+    ("processor.target_found", "対象のメッセージが見つかりました: ID {message_id}", "Found the target message: ID {message_id}"),
+    ("processor.target_not_found", "対象のメッセージが見つかりませんでした", "No target message was found"),
+    ("processor.unknown_file", "ファイルID {file_id}", "file ID {file_id}"),
+    ("processor.notified_save_failed", "通知済みのキーの保存に失敗しました: {error}", "Failed to save the notified keys: {error}"),
+
+    ("journal.write_failed", "ジャーナルへの書き込みに失敗しました: {error}", "Failed to write to the journal: {error}"),
+    ("journal.read", "既読", "read"),
+    ("journal.unread", "未読", "unread"),
+    ("journal.entry", "{timestamp} {action} ルーム{room_id}: {from} -> {to}（{consumed}件）", "{timestamp} {action} room {room_id}: {from} -> {to} ({consumed} messages)"),
+    ("journal.rules", " ルール: {rules}", " rules: {rules}"),
+    ("journal.read_status", " 未読: {unread}件, メンション: {mentions}件", " unread: {unread}, mentions: {mentions}"),
+    ("journal.account", " アカウント: {account}", " account: {account}"),
+
+    ("client.retrying", "リトライ試行 {attempt} / {max}", "Retry attempt {attempt} / {max}"),
+    ("client.request_completed", "APIリクエストが完了しました", "API request completed"),
+    ("client.rate_limited", "レート制限に達しました。{secs}秒後に再試行します...", "Rate limit reached. Retrying in {secs} seconds..."),
+    ("client.request_failed", "APIリクエストが失敗しました", "API request failed"),
+    ("client.rate_limit_headers", "レート制限ヘッダー: x-ratelimit-limit: {limit}, x-ratelimit-remaining: {remaining}, x-ratelimit-reset: {reset}", "Rate limit headers: x-ratelimit-limit: {limit}, x-ratelimit-remaining: {remaining}, x-ratelimit-reset: {reset}"),
+    ("client.read_body_failed", "レスポンスボディの読み取りに失敗しました", "Failed to read the response body"),
+    ("client.api_error", "APIエラー: {message}. ステータス: {status}", "API error: {message}. Status: {status}"),
+    ("client.response_headers", "レスポンスヘッダー: {headers}", "Response headers: {headers}"),
+    ("client.response_body", "レスポンスボディ: {body}", "Response body: {body}"),
+    ("client.parsed_errors", "パースされたエラー: {errors}", "Parsed errors: {errors}"),
+    ("client.fetching_rooms", "ルームの取得を開始します", "Fetching rooms"),
+    ("client.fetching_room", "ルーム: {room_id}の情報取得を開始します", "Fetching room {room_id}"),
+    ("client.fetching_messages", "ルーム: {room_id}のメッセージ取得を開始します", "Fetching messages in room {room_id}"),
+    ("client.marking_read", "メッセージを既読としてマークします。ルーム: {room_id}, メッセージ: {message_id}", "Marking messages as read. Room: {room_id}, message: {message_id}"),
+    ("client.marking_unread", "メッセージを未読としてマークします。ルーム: {room_id}, メッセージ: {message_id}", "Marking messages as unread. Room: {room_id}, message: {message_id}"),
+    ("client.fetching_my_tasks", "自分の未完了タスクの取得を開始します", "Fetching my open tasks"),
+    ("client.fetching_room_tasks", "ルーム{room_id}の未完了タスクの取得を開始します", "Fetching open tasks of room {room_id}"),
+    ("client.fetching_files", "ルーム: {room_id}のファイル一覧の取得を開始します", "Fetching files in room {room_id}"),
+    ("client.posting_message", "ルーム: {room_id}にメッセージを投稿します", "Posting a message to room {room_id}"),
+    ("client.access_token_rejected", "アクセストークンが拒否されたため、更新して再試行します", "The access token was rejected; refreshing it and retrying"),
+    ("client.fetching_me", "自分のアカウント情報の取得を開始します", "Fetching my account"),
+    ("client.fetching_contacts", "コンタクトの取得を開始します", "Fetching contacts"),
+
+    ("skip.excluded", "スキップリストに含まれています", "listed in the skip list"),
+    ("skip.not_included", "対象リストに含まれていません", "not listed in the target list"),
+    ("skip.no_unread", "未読メッセージがありません", "no unread messages"),
+    ("skip.below_min_unread", "未読メッセージが{unread}件で、しきい値の{min}件未満です", "{unread} unread messages, below the threshold of {min}"),
+    ("skip.mention", "メンションが含まれています", "contains a mention"),
+    ("skip.open_tasks", "自分宛ての未完了タスクが{count}件あります", "{count} open tasks are assigned to me"),
+    ("skip.mention_not_found", "取得したメッセージ内にメンションが見つかりません", "no mention found in the fetched messages"),
+    ("skip.unread_not_fetched", "未読メッセージが{unread}件ありますが、取得できたメッセージは{fetched}件のため、最初の未読メッセージを確認できません", "{unread} unread messages, but only {fetched} messages could be fetched, so the first unread message cannot be checked"),
+
+    ("block.toall", "[toall]を含むメッセージ", "a message containing [toall]"),
+    ("block.mention", "アカウント{account_id}へのメンション", "a mention of account {account_id}"),
+    ("block.task", "自分宛てのタスク", "a task assigned to me"),
+    ("block.attachment", "添付ファイル「{filename}」", "the attachment \"{filename}\""),
+    ("block.keyword", "キーワード「{pattern}」に一致するメッセージ", "a message matching the keyword \"{pattern}\""),
+    ("block.protected_sender", "保護対象のアカウント{account_id}からのメッセージ", "a message from the protected account {account_id}"),
+
+    ("report.summary", "既読: {read_rooms}ルーム（{consumed}件）, スキップ: {skipped_rooms}ルーム, 失敗: {failed_rooms}ルーム", "Read: {read_rooms} rooms ({consumed} messages), skipped: {skipped_rooms} rooms, failed: {failed_rooms} rooms"),
+
+    ("error.api", "APIエラー ({status}): {message}", "API error ({status}): {message}"),
+    ("error.reqwest", "Reqwestエラー: {error}", "HTTP error: {error}"),
+    ("error.env_var", "環境変数エラー: {error}", "Environment variable error: {error}"),
+    ("error.serde_json", "Serde JSONエラー: {error}", "JSON error: {error}"),
+    ("error.config", "設定エラー: {error}", "Configuration error: {error}"),
+    ("error.max_retries", "最大リトライ回数を超過しました", "Exceeded the maximum number of retries"),
+    ("error.io", "I/Oエラー: {error}", "I/O error: {error}"),
+    ("error.database", "データベースエラー: {error}", "Database error: {error}"),
+    ("error.notification", "通知エラー: {error}", "Notification error: {error}"),
+    ("error.oauth", "OAuth認証エラー: {error}", "OAuth error: {error}"),
+
+    ("digest.title", "既読ダイジェスト（{from} 〜 {to}）", "Read digest ({from} - {to})"),
+    ("digest.summary_runs", "実行: {runs}回, {report}", "Runs: {runs}, {report}"),
+    ("digest.room", "{room}（{count}件）", "{room} ({count} messages)"),
+    ("digest.top_senders", "よく発言した人: {senders}", "Most active: {senders}"),
+    ("digest.sender", "{name}（{count}件）", "{name} ({count})"),
+    ("digest.omitted", "ほか{count}件", "and {count} more"),
+    ("digest.written", "ダイジェストを出力しました: {path}", "Wrote the digest: {path}"),
+    ("digest.my_chat_not_found", "マイチャットが見つかりません", "My Chat room was not found"),
+    ("digest.posted", "ダイジェストをルーム{room_id}に投稿しました（メッセージ: {message_id}）", "Posted the digest to room {room_id} (message: {message_id})"),
+    ("digest.nothing_read", "既読にしたメッセージがないため、ダイジェストを出力しません", "No messages were read, so no digest is written"),
+    ("digest.deliver_failed", "ダイジェストの出力に失敗しました: {error}", "Failed to deliver the digest: {error}"),
+
+    ("events.nothing_to_read", "既読にできるメッセージがありません", "no messages could be read"),
+    ("events.queue_parse_failed", "キューのイベントを解析できないため破棄します: {error}", "Discarding an unparsable event in the queue: {error}"),
+    ("events.queue_overflow", "イベントのキューが上限の{capacity}件を超えたため、古いイベントを{count}件破棄します", "The event queue exceeded its capacity of {capacity}; discarding {count} oldest events"),
+    ("events.queued", "Webhookに送信できなかったイベント{count}件をキューに保存します", "Queueing {count} events that could not be sent to the webhook"),
+    ("events.resent", "キューに保存していたイベント{count}件を送信しました", "Sent {count} queued events"),
+    ("events.serialize_failed", "イベントのシリアライズに失敗しました: {error}", "Failed to serialize an event: {error}"),
+    ("events.rejected", "Webhookがイベントを受け付けませんでした（ステータス{status}）", "The webhook rejected the event (status {status})"),
+    ("events.send_failed_status", "Webhookの送信に失敗しました（ステータス{status}、{attempt}回目）", "Failed to send to the webhook (status {status}, attempt {attempt})"),
+    ("events.send_failed", "Webhookの送信に失敗しました（{attempt}回目）: {error}", "Failed to send to the webhook (attempt {attempt}): {error}"),
+    ("events.queue_io_failed", "イベントキューの読み書きに失敗しました: {error}", "Failed to read or write the event queue: {error}"),
+    ("events.worker_stopped", "実行イベントの送信タスクが停止しているため、イベントを送信できません", "The run event delivery task has stopped, so the event cannot be sent"),
+
+    ("health.token_invalid", "APIトークンが無効です", "The API token is invalid"),
+    ("health.no_success_for", "{secs}秒以上実行が成功していません", "No successful run for more than {secs} seconds"),
+    ("health.no_success_yet", "まだ実行が成功していません", "No run has succeeded yet"),
+    ("health.rate_limited", "APIのレート制限を受けています", "The API is rate-limited"),
+    ("health.account_reason", "アカウント{account}: {reason}", "Account {account}: {reason}"),
+
+    ("notifier.subject", "{room}の既読を止めました（{reason}）", "Stopped reading {room} ({reason})"),
+    ("notifier.describe_room", "ルーム: {room} (ID: {room_id})\nルール: {rule}\n理由: {reason}\n", "Room: {room} (ID: {room_id})\nRule: {rule}\nReason: {reason}\n"),
+    ("notifier.describe_message", "送信者: {sender} (ID: {account_id})\n本文:\n{body}\n", "Sender: {sender} (ID: {account_id})\nBody:\n{body}\n"),
+    ("notifier.webhook_status", "Webhookの送信先がステータス{status}を返しました", "The webhook endpoint returned status {status}"),
+    ("notifier.command_failed", "通知コマンドが異常終了しました: {status}", "The notification command failed: {status}"),
+    ("notifier.rule_filtered", "ルール{rule}は通知の対象外のため、通知しません", "Not notifying because rule {rule} is not enabled for this notifier"),
+
+    ("undo.no_previous_boundary", "ルーム{room_id}は既読前の位置が記録されていないため、未読に戻せません", "Cannot mark room {room_id} as unread because its position before reading was not recorded"),
+    ("undo.no_previous_boundary_error", "既読前の位置が記録されていません", "The position before reading was not recorded"),
+    ("undo.marking_unread", "ルーム{room_id}をメッセージ{message_id}以降から未読に戻します", "Marking room {room_id} as unread from message {message_id}"),
+
+    ("app.daemon_started", "デーモンモードを開始します（{secs}秒間隔）", "Starting daemon mode (every {secs} seconds)"),
+    ("app.daemon_stopped", "デーモンモードを終了します", "Stopping daemon mode"),
+    ("app.webhook_receiver_missing", "`[webhook_receiver]`が設定されていません", "`[webhook_receiver]` is not configured"),
+    ("app.webhook_listening", "Webhookの受信を開始します: http://{listen}{path}", "Receiving webhooks at http://{listen}{path}"),
+    ("app.webhook_server_stopped", "Webhookの受信サーバーが停止しました", "The webhook server stopped"),
+    ("app.webhook_stopped", "Webhookの受信を終了します", "Stopping the webhook receiver"),
+    ("app.invalid_listen", "待ち受けるアドレスが正しくありません: {listen}", "Invalid listen address: {listen}"),
+    ("app.server_started", "HTTPサーバーを起動しました: http://{addr}", "Started the HTTP server at http://{addr}"),
+    ("app.server_failed", "HTTPサーバーが停止しました: {error}", "The HTTP server stopped: {error}"),
+    ("app.room_error", "ルーム{room_id}: {error}", "Room {room_id}: {error}"),
+    ("app.run_failed", "既読処理に失敗しました: {error}", "Failed to mark messages as read: {error}"),
+    ("app.error", "アプリケーションエラー: {error}", "Application error: {error}"),
+    ("app.account_done", "アカウント{account}の処理が完了しました: {report}", "Finished processing account {account}: {report}"),
+    ("app.account_failed", "アカウント{account}の処理に失敗しました: {error}", "Failed to process account {account}: {error}"),
+    ("app.serve_single_account", "serveコマンドで処理できるアカウントは1つのみです。[[accounts]]を1つにしてください", "The serve command supports only one account. Configure a single [[accounts]] entry"),
+
+    ("cli.undo_nothing", "未読に戻す既読の記録がありません", "There are no read records to undo"),
+    ("cli.undo_done", "ルーム{room_id}: メッセージ{message_id}以降を未読に戻しました（未読{unread}件）", "Room {room_id}: marked messages from {message_id} as unread ({unread} unread)"),
+    ("cli.undo_failed", "ルーム{room_id}: 未読に戻せませんでした: {error}", "Room {room_id}: could not mark messages as unread: {error}"),
+    ("cli.journal_empty", "該当する記録がありません", "No matching records"),
+    ("cli.search_empty", "該当するメッセージがありません", "No matching messages"),
+    ("cli.invalid_datetime", "日時の形式が正しくありません: {value}", "Invalid date and time format: {value}"),
+    ("cli.nonexistent_datetime", "存在しない日時です: {value}", "This date and time does not exist: {value}"),
+    ("cli.store_token_prompt", "キーリングに保存するAPIトークンを入力してください:", "Enter the API token to store in the keyring:"),
+    ("cli.store_token_done", "APIトークンをキーリング（サービス: {service}、ユーザー: {user}）に保存しました", "Stored the API token in the keyring (service: {service}, user: {user})"),
+    ("cli.undo_account", "アカウント{account}:", "Account {account}:"),
+    ("cli.authorize_account", "アカウント {account} を認可します", "Authorizing account {account}"),
+    ("cli.authorize_done", "トークンを{path}に保存しました", "Saved the token to {path}"),
+    ("cli.authorize_not_configured", "OAuth（oauth）を設定したアカウントがありません", "No account has OAuth (oauth) configured"),
+    ("cli.check_config_account", "アカウント{account}: {name}（ID: {account_id}）として認証されました", "Account {account}: authenticated as {name} (ID: {account_id})"),
+    ("cli.check_config_error", "エラー: {issue}", "error: {issue}"),
+    ("cli.check_config_warning", "警告: {issue}", "warning: {issue}"),
+    ("cli.check_config_ok", "設定に問題はありません（警告: {warnings}件）", "The settings are valid ({warnings} warnings)"),
+    ("cli.check_config_failed", "設定に{errors}件のエラーがあります（警告: {warnings}件）", "The settings contain {errors} errors ({warnings} warnings)"),
+    ("cli.about", "Chatworkのメッセージを自動で既読にするツールです", "A tool that automatically marks Chatwork messages as read"),
+    ("cli.help_help", "ヘルプを表示します", "Print help"),
+    ("cli.help_version", "バージョンを表示します", "Print version"),
+    ("cli.about_run", "全ルームのメッセージを自動で既読にします", "Mark messages in all rooms as read"),
+    ("cli.about_daemon", "設定された間隔で既読処理を繰り返し実行します", "Repeat marking messages as read at the configured interval"),
+    ("cli.about_serve", "ChatworkのWebhookを受信し、メッセージが届いたルームを既読にします", "Receive Chatwork webhooks and mark the rooms that received messages as read"),
+    ("cli.about_undo", "ツールが既読にしたメッセージを未読に戻します", "Mark messages this tool marked as read as unread again"),
+    ("cli.about_journal", "既読操作のジャーナルを表示します", "Show the journal of read operations"),
+    ("cli.about_search", "アーカイブしたメッセージを検索します", "Search archived messages"),
+    ("cli.about_store_token", "標準入力から読み込んだAPIトークンをOSのキーリングに保存します", "Store an API token read from standard input in the OS keyring"),
+    ("cli.about_authorize", "ブラウザでChatworkへのアクセスを許可し、OAuthのトークンを取得して保存します", "Authorize access to Chatwork in the browser and save the OAuth token"),
+    ("cli.about_check_config", "設定を検証し、APIでトークンと参照しているルーム・アカウントを確認します", "Validate the settings and check the token and the referenced rooms and accounts with the API"),
+    ("cli.about_config_schema", "設定ファイルのJSON Schemaを出力します（エディタの補完に使用します）", "Print the JSON Schema of the settings file (for editor completion)"),
+    ("cli.help_undo_since", "この日時以降の既読を未読に戻します（例: \"2024-01-31 09:00\"）", "Undo reads at or after this date and time (e.g. \"2024-01-31 09:00\")"),
+    ("cli.help_undo_until", "この日時より前の既読を未読に戻します（例: \"2024-01-31 18:00\"）", "Undo reads before this date and time (e.g. \"2024-01-31 18:00\")"),
+    ("cli.help_date", "この日（ローカルタイムゾーン）に絞り込みます（例: \"2024-01-31\"）", "Only include this day in the local time zone (e.g. \"2024-01-31\")"),
+    ("cli.help_since", "この日時以降に絞り込みます", "Only include entries at or after this date and time"),
+    ("cli.help_until", "この日時より前に絞り込みます", "Only include entries before this date and time"),
+    ("cli.help_journal_room", "指定したルームの記録のみを表示します", "Only show records for this room"),
+    ("cli.help_journal_json", "記録をJSONL形式で出力します", "Print records as JSONL"),
+    ("cli.help_search_terms", "本文に含まれる語（複数指定した場合は全てを含むメッセージ）", "Words in the message body (messages must contain all of them)"),
+    ("cli.help_search_room", "指定したルームのメッセージのみを検索します", "Only search messages in this room"),
+    ("cli.help_search_sender", "指定した送信者（アカウントIDまたは名前の一部）のメッセージのみを検索します", "Only search messages from this sender (account ID or part of the name)"),
+    ("cli.help_search_limit", "表示する件数の上限（0で無制限）", "Maximum number of results (0 for no limit)"),
+    ("cli.help_store_token_service", "キーリングのサービス名", "Keyring service name"),
+    ("cli.help_store_token_user", "キーリングのユーザー名", "Keyring user name"),
+    ("cli.help_authorize_account", "認可するアカウントの名前（`[[accounts]]`を使用している場合。省略時はOAuthを設定した全てのアカウント）", "Name of the account to authorize (with `[[accounts]]`; defaults to all accounts with OAuth configured)"),
+    ("cli.help_check_config_offline", "APIによる確認を行わず、設定ファイルの内容のみを検証します", "Only validate the settings file without checking with the API"),
+
+    ("webhook.invalid_token", "Webhookのトークンをデコードできません", "Cannot decode the webhook token"),
+    ("webhook.invalid_signature", "署名が正しくないWebhookを拒否しました", "Rejected a webhook with an invalid signature"),
+    ("webhook.invalid_body", "Webhookの本文を解析できません: {error}", "Cannot parse the webhook body: {error}"),
+    ("webhook.received", "Webhookを受信しました: {event_type} (ルーム: {room_id})", "Received a webhook: {event_type} (room: {room_id})"),
+    ("webhook.ignored", "処理対象外のWebhookを無視します: {event_type}", "Ignoring an unsupported webhook: {event_type}"),
+    ("webhook.processing", "Webhookを受信したルームを処理します: {room_ids}", "Processing rooms that received webhooks: {room_ids}"),
+
+    ("logging.invalid_level", "ログレベルの設定が正しくありません: {error}", "Invalid log level setting: {error}"),
+    ("logging.open_file_failed", "ログファイル{path}を開けません: {error}", "Cannot open the log file {path}: {error}"),
+
+    ("telemetry.exporter_failed", "OTLPエクスポーターの作成に失敗しました", "Failed to create the OTLP exporter"),
+    ("telemetry.setup_failed", "OpenTelemetryの設定に失敗しました: {error}", "Failed to set up OpenTelemetry: {error}"),
+    ("telemetry.shutdown_failed", "OpenTelemetryの停止に失敗しました: {error}", "Failed to shut down OpenTelemetry: {error}"),
+
+    ("token.file_read_failed", "APIトークンのファイル{path}を読み込めません: {error}", "Failed to read the API token file {path}: {error}"),
+    ("token.file_permissions", "APIトークンのファイル{path}のパーミッション({mode})が緩すぎます。`chmod 600 {path}`で所有者のみが読めるようにしてください", "The API token file {path} has overly permissive mode {mode}. Run `chmod 600 {path}` so that only the owner can read it"),
+    ("token.file_empty", "APIトークンのファイル{path}が空です", "The API token file {path} is empty"),
+    ("token.command_start_failed", "APIトークンを取得するコマンド{program}を実行できません: {error}", "Failed to run the API token command {program}: {error}"),
+    ("token.command_failed", "APIトークンを取得するコマンド{program}が失敗しました: {status}", "The API token command {program} failed: {status}"),
+    ("token.command_empty", "APIトークンを取得するコマンド{program}の出力が空です", "The API token command {program} printed nothing"),
+    ("token.keyring_failed", "キーリング（サービス: {service}、ユーザー: {user}）にアクセスできません: {error}", "Failed to access the keyring (service: {service}, user: {user}): {error}"),
+    ("token.keyring_empty", "キーリング（サービス: {service}、ユーザー: {user}）のAPIトークンが空です", "The API token in the keyring (service: {service}, user: {user}) is empty"),
+    ("token.keyring_panicked", "キーリングへのアクセス中に異常終了しました", "Accessing the keyring panicked"),
+    ("token.keyring_disabled", "キーリングを使用するには`keyring`フィーチャーを有効にしてビルドしてください", "Build with the `keyring` feature enabled to use the keyring"),
+    ("token.input_empty", "APIトークンが入力されていません", "No API token was entered"),
+    ("token.multiple_sources", "chatwork.api_token、api_token_file、api_token_command、api_token_keyring、oauthはいずれか1つのみ指定してください", "Specify only one of chatwork.api_token, api_token_file, api_token_command, api_token_keyring and oauth"),
+    ("token.missing", "APIトークンが設定されていません。chatwork.api_token、api_token_file、api_token_command、api_token_keyring、oauthのいずれか、または環境変数APP_CHATWORK_API_TOKENを指定してください", "No API token is configured. Set one of chatwork.api_token, api_token_file, api_token_command, api_token_keyring and oauth, or the APP_CHATWORK_API_TOKEN environment variable"),
+    ("token.config_world_readable", "設定ファイル{path}にAPIトークンが書かれていますが、全てのユーザーが読めるパーミッション({mode})です。`chmod 600 {path}`を実行するか、api_token_fileなどの読み込み元を使用してください", "The config file {path} contains an API token but is readable by all users (mode {mode}). Run `chmod 600 {path}` or use a source such as api_token_file"),
+
+    ("redaction.hidden_body", "（本文は省略しました: {chars}文字）", "(body omitted: {chars} characters)"),
+
+    ("settings.chatwork_and_accounts", "[[accounts]]を使用する場合は、[chatwork]や環境変数APP_CHATWORK_API_TOKENにAPIトークンを指定せず、アカウントごとに指定してください", "When using [[accounts]], specify the API token for each account instead of in [chatwork] or the APP_CHATWORK_API_TOKEN environment variable"),
+    ("settings.account_name_empty", "[[accounts]]のnameが空です", "An [[accounts]] entry has an empty name"),
+    ("settings.account_name_duplicated", "[[accounts]]のname「{account}」が重複しています", "The [[accounts]] name \"{account}\" is used more than once"),
+    ("settings.account_error", "アカウント{account}: {error}", "Account {account}: {error}"),
+    ("settings.oauth_token_path_duplicated", "アカウント{account}のOAuthのトークンのファイル{path}は、他のアカウントと同じです。アカウントごとにoauth.token_pathを指定してください", "The OAuth token file {path} of account {account} is shared with another account. Set oauth.token_path for each account"),
+    ("settings.invalid", "設定に誤りがあります:\n  - {issues}", "The settings contain errors:\n  - {issues}"),
+
+    ("oauth.open_url", "ブラウザで次のURLを開き、Chatworkへのアクセスを許可してください:\n{url}", "Open the following URL in your browser and allow access to Chatwork:\n{url}"),
+    ("oauth.waiting", "{redirect_uri} へのリダイレクトを待っています…", "Waiting for the redirect to {redirect_uri}..."),
+    ("oauth.listen_failed", "認可コードを受け取るポート{port}で待ち受けできません: {error}", "Failed to listen on port {port} for the authorization code: {error}"),
+    ("oauth.authorize_timeout", "{secs}秒以内に認可されませんでした", "Authorization was not completed within {secs} seconds"),
+    ("oauth.denied", "アクセスが許可されませんでした: {error}", "Access was not granted: {error}"),
+    ("oauth.state_mismatch", "リダイレクトのstateが一致しないため、無視しました", "Ignored a redirect whose state does not match"),
+    ("oauth.code_missing", "リダイレクトに認可コードが含まれていません", "The redirect does not contain an authorization code"),
+    ("oauth.callback_succeeded", "認可が完了しました。このページを閉じてください。", "Authorization is complete. You can close this page."),
+    ("oauth.token_request_failed", "トークンの取得に失敗しました（{status}）: {error}", "Failed to obtain a token ({status}): {error}"),
+    ("oauth.token_read_failed", "OAuthのトークンのファイル{path}を読み込めません: {error}", "Failed to read the OAuth token file {path}: {error}"),
+    ("oauth.token_write_failed", "OAuthのトークンのファイル{path}に書き込めません: {error}", "Failed to write the OAuth token file {path}: {error}"),
+    ("oauth.not_authorized", "OAuthのトークンのファイル{path}がありません。先に`chatwork_auto_read authorize`を実行してください", "The OAuth token file {path} does not exist. Run `chatwork_auto_read authorize` first"),
+    ("oauth.token_refreshed", "アクセストークンを更新しました（有効期限: {expires_at}）", "Refreshed the access token (expires at {expires_at})"),
+    ("oauth.token_reloaded", "他のプロセスが更新したトークンを読み込みました", "Loaded the token refreshed by another process"),
+
+    ("validation.digest_no_output", "ダイジェストが有効ですが、write_fileとpost_to_chatworkがどちらもfalseのため出力されません", "The digest is enabled, but neither write_file nor post_to_chatwork is true, so it is never output"),
+    ("validation.interval_zero", "0秒は指定できないため、1秒として扱います", "0 seconds is not allowed and is treated as 1 second"),
+    ("validation.path_not_absolute", "パス「{path}」は/で始まる必要があります", "The path \"{path}\" must start with /"),
+    ("validation.empty_list", "1つ以上指定してください", "Specify at least one entry"),
+    ("validation.token_whitespace", "APIトークンの前後に空白や改行が含まれています", "The API token has leading or trailing whitespace or newlines"),
+    ("validation.port_zero", "ポート番号に0は指定できません", "The port number must not be 0"),
+    ("validation.room_included_and_excluded", "ルーム{room_id}はexclude_room_idsにも含まれているため、処理されません", "Room {room_id} is also in exclude_room_ids and is never processed"),
+    ("validation.rule_matches_nothing", "room_idsとroom_patternsがどちらも空のため、どのルームにも適用されません", "Both room_ids and room_patterns are empty, so the rule applies to no room"),
+    ("validation.senders_empty", "actionがblock_from_sendersですが、sendersが空のため既読を止めません", "action is block_from_senders, but senders is empty, so nothing is blocked"),
+    ("validation.account_id_empty", "アカウントIDが空です", "The account ID is empty"),
+    ("validation.account_id_whitespace", "アカウントID「{account_id}」に空白が含まれています", "The account ID \"{account_id}\" contains whitespace"),
+    ("validation.account_id_not_numeric", "アカウントID「{account_id}」は数字のみで指定してください", "The account ID \"{account_id}\" must consist of digits only"),
+    ("validation.room_id_not_positive", "ルームID{room_id}は正の数ではありません", "The room ID {room_id} is not positive"),
+    ("validation.invalid_listen", "「{listen}」は「IPアドレス:ポート」の形式ではありません", "\"{listen}\" is not in the form \"IP address:port\""),
+    ("validation.invalid_url", "「{url}」はHTTPまたはHTTPSのURLではありません", "\"{url}\" is not an HTTP or HTTPS URL"),
+    ("validation.invalid_mailbox", "「{address}」はメールアドレスではありません", "\"{address}\" is not an email address"),
+    ("validation.blank", "空にはできません", "Must not be empty"),
+    ("validation.unknown_key_similar", "不明なキーのため無視されます（{similar}の誤りではありませんか）", "Unknown key, ignored (did you mean {similar}?)"),
+    ("validation.unknown_key", "不明なキーのため無視されます", "Unknown key, ignored"),
+    ("validation.number_as_string", "数値が文字列「{value}」として書かれています。引用符を外してください", "The number is written as the string \"{value}\". Remove the quotes"),
+    ("validation.token_rejected", "トークンを確認できません: {error}", "Could not verify the token: {error}"),
+    ("validation.room_not_found", "ルーム{room_id}は参加しているルームにありません", "Room {room_id} is not among the joined rooms"),
+    ("validation.rooms_failed", "ルームを取得できません: {error}", "Could not fetch rooms: {error}"),
+    ("validation.account_not_found", "アカウント{account_id}は自分またはコンタクトにありません", "Account {account_id} is neither me nor a contact"),
+    ("validation.contacts_failed", "コンタクトを取得できません: {error}", "Could not fetch contacts: {error}"),
+];
@@ -0,0 +1,256 @@
+//! メッセージカタログモジュール
+//!
+//! このモジュールは、ログ・コマンドラインへの出力・エラー・レポートに使用するメッセージを
+//! 日本語と英語で管理し、設定またはOSのロケールに従って選択した言語で返す機能を提供します。
+//!
+//! メッセージは[`t!`](crate::t)マクロでキーと名前付きの引数を指定して取得します。
+//!
+//! ```
+//! use chatwork_auto_read::i18n::{translate_in, Language};
+//! use chatwork_auto_read::t;
+//!
+//! assert_eq!(t!("processor.rooms_found", count = 3), "処理対象のルームが3個見つかりました");
+//! assert_eq!(
+//!     translate_in(Language::En, "processor.rooms_found", &[("count", &3)]),
+//!     "Found 3 rooms to process"
+//! );
+//! ```
+
+mod catalog;
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// メッセージの言語です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// 日本語
+    #[default]
+    Ja,
+    /// 英語
+    En,
+}
+
+impl Language {
+    /// ロケールの文字列（例: `ja-JP`、`en_US.UTF-8`）から言語を判定します。
+    ///
+    /// 日本語のロケールと、`C`・`POSIX`のように言語を指定しないロケールは日本語、
+    /// それ以外は英語とみなします。
+    pub fn from_locale(locale: &str) -> Self {
+        let locale = locale.to_ascii_lowercase();
+        let unspecified = locale == "c" || locale.starts_with("c.") || locale == "posix";
+        if locale.starts_with("ja") || unspecified {
+            Language::Ja
+        } else {
+            Language::En
+        }
+    }
+
+    /// 言語コード（HTMLの`lang`属性などに使用）を返します。
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Ja => "ja",
+            Language::En => "en",
+        }
+    }
+
+    /// OSのロケールから言語を判定します。ロケールを取得できない場合は日本語を返します。
+    pub fn detect() -> Self {
+        sys_locale::get_locale().map_or(Language::Ja, |locale| Self::from_locale(&locale))
+    }
+}
+
+/// 現在の言語（`Language`を`u8`に変換した値）です。
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::Ja as u8);
+
+/// メッセージの言語を設定します。
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// 現在のメッセージの言語を返します（デフォルトは日本語）。
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        value if value == Language::En as u8 => Language::En,
+        _ => Language::Ja,
+    }
+}
+
+/// キーごとの日本語と英語のメッセージを返します。
+fn messages() -> &'static HashMap<&'static str, (&'static str, &'static str)> {
+    static MESSAGES: OnceLock<HashMap<&'static str, (&'static str, &'static str)>> =
+        OnceLock::new();
+    MESSAGES.get_or_init(|| {
+        catalog::MESSAGES
+            .iter()
+            .map(|(key, ja, en)| (*key, (*ja, *en)))
+            .collect()
+    })
+}
+
+/// 現在の言語で、キーに対応するメッセージを返します。
+///
+/// 通常は[`t!`](crate::t)マクロを使用します。
+pub fn translate(key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    translate_in(language(), key, args)
+}
+
+/// 指定された言語で、キーに対応するメッセージを返します。
+///
+/// メッセージ中の`{名前}`は、同じ名前の引数の値に置き換えます。
+/// キーがカタログにない場合は、キーをそのまま返します。
+pub fn translate_in(language: Language, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let Some((ja, en)) = messages().get(key) else {
+        return key.to_string();
+    };
+    let template = match language {
+        Language::Ja => ja,
+        Language::En => en,
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = *template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let name = &after[..end];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => {
+                let _ = write!(out, "{}", value);
+            }
+            None => out.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 現在の言語で、キーに対応するメッセージを`String`として返します。
+///
+/// 引数は`名前 = 値`の形式で指定し、値は`Display`を実装している必要があります。
+///
+/// ```
+/// use chatwork_auto_read::t;
+///
+/// assert_eq!(t!("processor.room_succeeded", room_id = 10), "ルーム10の処理が成功しました");
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    fn placeholders(template: &str) -> BTreeSet<&str> {
+        Regex::new(r"\{([a-z_]+)\}")
+            .unwrap()
+            .captures_iter(template)
+            .map(|captures| captures.get(1).unwrap().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_translate_in() {
+        let args: [(&str, &dyn fmt::Display); 2] = [("room_id", &10), ("reason", &"mention")];
+        assert_eq!(
+            translate_in(Language::Ja, "processor.room_skipped", &args),
+            "ルーム10をスキップします: mention"
+        );
+        assert_eq!(
+            translate_in(Language::En, "processor.room_skipped", &args),
+            "Skipping room 10: mention"
+        );
+        // 引数が足りない場合は`{名前}`のまま残す
+        assert_eq!(
+            translate_in(Language::En, "processor.room_skipped", &args[..1]),
+            "Skipping room 10: {reason}"
+        );
+        assert_eq!(
+            translate_in(Language::En, "no.such.key", &[]),
+            "no.such.key"
+        );
+    }
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(Language::from_locale("ja-JP"), Language::Ja);
+        assert_eq!(Language::from_locale("ja_JP.UTF-8"), Language::Ja);
+        assert_eq!(Language::from_locale("C"), Language::Ja);
+        assert_eq!(Language::from_locale("POSIX"), Language::Ja);
+        assert_eq!(Language::from_locale("en-US"), Language::En);
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Language::En);
+    }
+
+    #[test]
+    fn test_catalog_is_consistent() {
+        let mut keys = BTreeSet::new();
+        for (key, ja, en) in catalog::MESSAGES {
+            assert!(keys.insert(*key), "キー{}が重複しています", key);
+            assert!(
+                !en.is_empty() && !ja.is_empty(),
+                "キー{}の訳がありません",
+                key
+            );
+            assert_eq!(
+                placeholders(ja),
+                placeholders(en),
+                "キー{}の日本語と英語で引数が異なります",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalog_covers_used_keys() {
+        // ソースコード中の`t!`で使用しているキーが全てカタログにあることを確認する
+        let pattern = Regex::new(r#"t!\(\s*"([a-z0-9_.]+)""#).unwrap();
+        let mut dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("src")];
+        let mut used = 0;
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path).unwrap();
+                for captures in pattern.captures_iter(&source) {
+                    let key = &captures[1];
+                    used += 1;
+                    assert!(
+                        messages().contains_key(key),
+                        "{}で使用しているキー{}がカタログにありません",
+                        path.display(),
+                        key
+                    );
+                }
+            }
+        }
+        assert!(used > 0);
+    }
+}
@@ -0,0 +1,361 @@
+//! 既読操作のジャーナルモジュール
+//!
+//! このモジュールは、ツールが移動させた既読位置を追記専用のJSONLファイルに記録し、
+//! 後から読み出すための機能を提供します。記録された内容は`undo`コマンドで
+//! 既読状態を元に戻すために使用されます。
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::models::ReadStatus;
+use crate::t;
+
+/// ジャーナルに記録された操作の種類です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalAction {
+    /// メッセージを既読にした
+    #[default]
+    Read,
+    /// `undo`によってメッセージを未読に戻した
+    Unread,
+}
+
+/// ジャーナルの1件の記録を表す構造体です。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// 記録を行った実行の識別子
+    pub run_id: String,
+    /// 記録した日時
+    pub timestamp: DateTime<Utc>,
+    /// 操作の種類
+    #[serde(default)]
+    pub action: JournalAction,
+    /// ルームID
+    pub room_id: i32,
+    /// 操作前の既読の境界（最初の未読メッセージのID）
+    ///
+    /// 取得したメッセージより多くの未読があった場合は、取得した中で最も古いメッセージのIDになります。
+    pub previous_boundary: Option<String>,
+    /// 操作後の既読の境界（既読にした最新のメッセージのID）
+    pub new_boundary: String,
+    /// 既読にした未読メッセージの数
+    #[serde(default)]
+    pub consumed: usize,
+    /// 既読の位置を決めたルールの識別子（`toall`、`mention`、`min_message_age`など）
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// APIが返した操作後のルームの状態
+    #[serde(default)]
+    pub read_status: Option<ReadStatus>,
+    /// 操作を行ったアカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            JournalAction::Read => t!("journal.read"),
+            JournalAction::Unread => t!("journal.unread"),
+        };
+        write!(
+            f,
+            "{}",
+            t!(
+                "journal.entry",
+                timestamp = self
+                    .timestamp
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S"),
+                action = action,
+                room_id = self.room_id,
+                from = self.previous_boundary.as_deref().unwrap_or("-"),
+                to = self.new_boundary,
+                consumed = self.consumed
+            )
+        )?;
+        if !self.rules.is_empty() {
+            write!(f, "{}", t!("journal.rules", rules = self.rules.join(",")))?;
+        }
+        if let Some(status) = &self.read_status {
+            write!(
+                f,
+                "{}",
+                t!(
+                    "journal.read_status",
+                    unread = status.unread_num,
+                    mentions = status.mention_num
+                )
+            )?;
+        }
+        if let Some(account) = &self.account {
+            write!(f, "{}", t!("journal.account", account = account))?;
+        }
+        Ok(())
+    }
+}
+
+/// 追記専用のJSONLファイルによるジャーナルです。
+pub struct Journal {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl Journal {
+    /// 指定されたパスのジャーナルを開きます。
+    ///
+    /// ファイルは最初の書き込み時に作成されます。
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// ジャーナルファイルのパスを返します。
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 記録を1件追記します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの作成や書き込みに失敗した場合、`Error`を返します。
+    pub fn append(&self, entry: &JournalEntry) -> Result<(), Error> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// 全ての記録を古い順に読み込みます。
+    ///
+    /// ファイルが存在しない場合は空のリストを返します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み込みや記録の解析に失敗した場合、`Error`を返します。
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, Error> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(entries)
+    }
+
+    /// 指定されたアカウントが最後に既読を行った実行の記録を返します。
+    ///
+    /// # 引数
+    ///
+    /// * `account` - アカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+    ///
+    /// # エラー
+    ///
+    /// ジャーナルの読み込みに失敗した場合、`Error`を返します。
+    pub fn last_run(&self, account: Option<&str>) -> Result<Vec<JournalEntry>, Error> {
+        let entries = self.entries()?;
+        let Some(run_id) = entries
+            .iter()
+            .rev()
+            .find(|entry| {
+                entry.action == JournalAction::Read && entry.account.as_deref() == account
+            })
+            .map(|entry| entry.run_id.clone())
+        else {
+            return Ok(Vec::new());
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.run_id == run_id && entry.action == JournalAction::Read)
+            .collect())
+    }
+
+    /// 指定された期間内に既読を行った記録を返します。
+    ///
+    /// # 引数
+    ///
+    /// * `since` - 期間の開始（この日時を含む）。`None`の場合は制限なし
+    /// * `until` - 期間の終了（この日時を含まない）。`None`の場合は制限なし
+    ///
+    /// # エラー
+    ///
+    /// ジャーナルの読み込みに失敗した場合、`Error`を返します。
+    pub fn read_between(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<JournalEntry>, Error> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.action == JournalAction::Read)
+            .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+            .filter(|entry| until.is_none_or(|until| entry.timestamp < until))
+            .collect())
+    }
+
+    /// 期間とルームで絞り込んだ記録を、操作の種類にかかわらず返します。
+    ///
+    /// # 引数
+    ///
+    /// * `since` - 期間の開始（この日時を含む）。`None`の場合は制限なし
+    /// * `until` - 期間の終了（この日時を含まない）。`None`の場合は制限なし
+    /// * `room_id` - ルームID。`None`の場合は全てのルーム
+    ///
+    /// # エラー
+    ///
+    /// ジャーナルの読み込みに失敗した場合、`Error`を返します。
+    pub fn query(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        room_id: Option<i32>,
+    ) -> Result<Vec<JournalEntry>, Error> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+            .filter(|entry| until.is_none_or(|until| entry.timestamp < until))
+            .filter(|entry| room_id.is_none_or(|room_id| entry.room_id == room_id))
+            .collect())
+    }
+}
+
+/// 新しい実行の識別子を生成します。
+pub fn new_run_id() -> String {
+    Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn entry(run_id: &str, hour: u32, room_id: i32, action: JournalAction) -> JournalEntry {
+        JournalEntry {
+            run_id: run_id.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+            action,
+            room_id,
+            previous_boundary: Some(format!("{}-prev", room_id)),
+            new_boundary: format!("{}-new", room_id),
+            consumed: 1,
+            rules: Vec::new(),
+            read_status: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_journal_append_and_read() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let journal = Journal::new(temp_dir.path().join("data/journal.jsonl"));
+        assert!(journal.entries().unwrap().is_empty());
+
+        journal
+            .append(&entry("run1", 1, 10, JournalAction::Read))
+            .unwrap();
+        journal
+            .append(&entry("run2", 2, 20, JournalAction::Read))
+            .unwrap();
+        journal
+            .append(&entry("run2", 2, 30, JournalAction::Read))
+            .unwrap();
+        journal
+            .append(&entry("undo", 3, 20, JournalAction::Unread))
+            .unwrap();
+
+        assert_eq!(journal.entries().unwrap().len(), 4);
+
+        let last_run = journal.last_run(None).unwrap();
+        assert_eq!(
+            last_run.iter().map(|e| e.room_id).collect::<Vec<_>>(),
+            vec![20, 30]
+        );
+
+        // アカウントごとに最後の実行を探す
+        journal
+            .append(&JournalEntry {
+                account: Some("company".to_string()),
+                ..entry("run3", 4, 40, JournalAction::Read)
+            })
+            .unwrap();
+        let company = journal.last_run(Some("company")).unwrap();
+        assert_eq!(company.len(), 1);
+        assert_eq!(company[0].room_id, 40);
+        assert_eq!(journal.last_run(None).unwrap().len(), 2);
+        assert!(journal.last_run(Some("personal")).unwrap().is_empty());
+
+        let between = journal
+            .read_between(
+                Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+                Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap()),
+            )
+            .unwrap();
+        assert_eq!(between.len(), 1);
+        assert_eq!(between[0].room_id, 10);
+
+        let room_20 = journal.query(None, None, Some(20)).unwrap();
+        assert_eq!(
+            room_20.iter().map(|e| e.action).collect::<Vec<_>>(),
+            vec![JournalAction::Read, JournalAction::Unread]
+        );
+        let since_2 = journal
+            .query(
+                Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap()),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(since_2.len(), 4);
+    }
+
+    #[test]
+    fn test_journal_entry_display() {
+        let mut entry = entry("run1", 1, 10, JournalAction::Read);
+        entry.rules = vec!["mention".to_string(), "min_message_age".to_string()];
+        entry.read_status = Some(ReadStatus {
+            unread_num: 2,
+            mention_num: 1,
+        });
+
+        let line = entry.to_string();
+        assert!(line.ends_with(
+            "既読 ルーム10: 10-prev -> 10-new（1件） ルール: mention,min_message_age 未読: 2件, メンション: 1件"
+        ));
+
+        entry.account = Some("company".to_string());
+        assert!(entry.to_string().ends_with(" アカウント: company"));
+    }
+}
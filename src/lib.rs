@@ -3,25 +3,78 @@
 //! このモジュールは、アプリケーションの主要なコンポーネントをまとめ、
 //! 実行のエントリーポイントとなる`run`関数を提供します。
 
+/// 既読にしたメッセージのアーカイブを含むモジュールです。
+pub mod archive;
+/// コマンドライン引数の定義を含むモジュールです。
+pub mod cli;
 /// Chatwork APIクライアントの実装を含むモジュールです。
 pub mod client;
+/// 既読にしたメッセージのダイジェストを含むモジュールです。
+pub mod digest;
 /// エラー型の定義を含むモジュールです。
 pub mod error;
+/// 実行イベントの送信を含むモジュールです。
+pub mod events;
+/// 稼働状態のヘルスチェックを含むモジュールです。
+pub mod health;
+/// ログやエラーなどのメッセージカタログを含むモジュールです。
+pub mod i18n;
+/// 既読操作のジャーナルを含むモジュールです。
+pub mod journal;
+/// Prometheus形式のメトリクスを含むモジュールです。
+pub mod metrics;
 /// データモデルの定義を含むモジュールです。
 pub mod models;
+/// 既読を止めたときの通知を含むモジュールです。
+pub mod notifier;
+/// OAuth 2.0による認可とトークンの更新を含むモジュールです。
+pub mod oauth;
 /// メッセージ処理ロジックを含むモジュールです。
 pub mod processor;
+/// 実行結果のレポートを含むモジュールです。
+pub mod report;
+/// 秘密情報の保持とログからの秘匿を含むモジュールです。
+pub mod secret;
 /// アプリケーション設定の管理を行うモジュールです。
 pub mod settings;
+/// OpenTelemetryによるトレースの送信を含むモジュールです。
+#[cfg(feature = "otlp")]
+pub mod telemetry;
+/// APIトークンの読み込みを含むモジュールです。
+pub mod token;
+/// 既読の取り消し処理を含むモジュールです。
+pub mod undo;
 /// ユーティリティ関数を含むモジュールです。
 pub mod utils;
+/// 設定の検証を含むモジュールです。
+pub mod validation;
+/// ChatworkのWebhookの受信を含むモジュールです。
+pub mod webhook;
 
 pub use client::ChatworkClient;
 pub use error::Error;
 pub use processor::MessageProcessor;
 pub use settings::Settings;
 
-use anyhow::Result;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use anyhow::{Context, Result};
+use archive::{Archive, SearchQuery};
+use chrono::Utc;
+use cli::{
+    AuthorizeArgs, CheckConfigArgs, Cli, Command, JournalArgs, SearchArgs, StoreTokenArgs, UndoArgs,
+};
+use digest::{ChatworkSink, DigestCollector, FileSink};
+use events::WebhookEventSink;
+use journal::{Journal, JournalEntry};
+use log::{error, info, warn};
+use notifier::NotifiedKeys;
+use report::RunReport;
+use settings::{AccountMode, DigestSchedule};
+use webhook::WebhookReceiver;
 
 /// アプリケーションのメイン実行関数です。
 ///
@@ -39,8 +92,8 @@ use anyhow::Result;
 ///
 /// # 例
 ///
-/// ```
-/// use your_crate_name::run;
+/// ```no_run
+/// use chatwork_auto_read::run;
 ///
 /// #[tokio::main]
 /// async fn main() -> anyhow::Result<()> {
@@ -48,13 +101,717 @@ use anyhow::Result;
 /// }
 /// ```
 pub async fn run() -> Result<()> {
-    utils::setup_logging();
+    let settings = load_settings()?;
+    let mut accounts = build_accounts(&settings, false)?;
+
+    let started_at = Utc::now();
+    let results = process_accounts(&accounts, settings.account_mode).await;
+    // 1つのアカウントの失敗で、他のアカウントのダイジェストの記録を止めないようにします
+    let mut failure = None;
+    for (account, result) in accounts.iter_mut().zip(results) {
+        match result {
+            Ok(report) => {
+                account.log_report(&report);
+                if let Some(digest) = &mut account.digest {
+                    // 出力先ごとの失敗は`DigestCollector`がログに記録する
+                    let _ = digest.record(&report, started_at, Utc::now()).await;
+                }
+            }
+            Err(e) => {
+                if let Some(name) = account.processor.account() {
+                    error!("{}", t!("app.account_failed", account = name, error = e));
+                }
+                failure = Some(e);
+            }
+        }
+    }
+
+    // 実行イベントはバックグラウンドで送信するため、終了する前に送信し終えるのを待つ
+    for account in &accounts {
+        account.processor.flush_events().await;
+    }
+
+    match failure {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// 1つのアカウントの既読処理と、そのアカウントのダイジェストです。
+struct Account {
+    processor: MessageProcessor<ChatworkClient>,
+    digest: Option<DigestCollector>,
+}
+
+impl Account {
+    /// `[[accounts]]`を使用している場合に、アカウントごとの処理結果をログに記録します。
+    fn log_report(&self, report: &RunReport) {
+        if let Some(name) = self.processor.account() {
+            info!(
+                "{}",
+                t!("app.account_done", account = name, report = report)
+            );
+        }
+    }
+}
+
+/// 設定に従って、アカウントごとの`MessageProcessor`と`DigestCollector`を作成します。
+///
+/// アカウントごとに別の`ChatworkClient`を使用するため、レート制限による待機は他のアカウントに影響しません。
+/// 実行イベントの送信先は全てのアカウントで共有します。
+fn build_accounts(settings: &Settings, daily: bool) -> Result<Vec<Account>> {
+    let event_sink = settings
+        .event_webhook
+        .clone()
+        .map(|webhook| Arc::new(WebhookEventSink::new(webhook)));
+    settings
+        .per_account()
+        .into_iter()
+        .map(|(name, settings)| {
+            let digest = build_digest(&settings, daily)?;
+            // 通知済みのキーはジャーナルの隣に、アカウントごとのファイルとして保存する
+            let file_name = match &name {
+                Some(name) => format!("notified.{}.json", name),
+                None => "notified.json".to_string(),
+            };
+            let notified_keys =
+                NotifiedKeys::load(&settings.journal.path.with_file_name(file_name))?;
+            health::health().register(name.as_deref());
+            let processor = build_processor(settings, name, event_sink.clone())?
+                .with_notified_keys(notified_keys);
+            Ok(Account { processor, digest })
+        })
+        .collect()
+}
+
+/// 設定された方法（順番または同時）で、全てのアカウントのルームを処理します。
+///
+/// # 戻り値
+///
+/// アカウントごとの処理結果を、`accounts`と同じ順に返します。
+async fn process_accounts(
+    accounts: &[Account],
+    mode: AccountMode,
+) -> Vec<Result<RunReport, Error>> {
+    let runs = accounts
+        .iter()
+        .map(|account| account.processor.process_all_rooms());
+    match mode {
+        AccountMode::Sequential => {
+            let mut results = Vec::with_capacity(accounts.len());
+            for run in runs {
+                results.push(run.await);
+            }
+            results
+        }
+        AccountMode::Concurrent => futures::future::join_all(runs).await,
+    }
+}
+
+/// 設定を読み込み、設定に従ってメッセージの言語とロギングを設定します。
+///
+/// 設定の読み込みに失敗した場合も、そのエラーをログに記録できるよう、デフォルトの設定でロギングを設定します。
+fn load_settings() -> Result<Settings> {
+    let settings = read_settings();
+    let logging = settings
+        .as_ref()
+        .map(|settings| settings.logging.clone())
+        .unwrap_or_default();
+    utils::setup_logging(&logging);
+    for warning in settings.iter().flat_map(|settings| &settings.warnings) {
+        warn!("{}", warning);
+    }
+    settings
+}
 
+/// 設定を読み込み、言語が設定されている場合はメッセージの言語にします。
+fn read_settings() -> Result<Settings> {
     let settings = Settings::new()?;
-    let client = ChatworkClient::new(&settings.chatwork.api_token);
-    let processor = MessageProcessor::new(client, settings);
+    if let Some(language) = settings.language {
+        i18n::set_language(language);
+    }
+    Ok(settings)
+}
+
+/// 設定された間隔で既読処理を繰り返し実行します。
+///
+/// 個々の実行の失敗はログに記録され、次の実行は継続されます。
+/// Ctrl+Cを受け取ると、記録中のダイジェストを書き込んで終了します。
+///
+/// # エラー
+///
+/// 設定の読み込みに失敗した場合、`anyhow::Error`でラップされたエラーを返します。
+pub async fn daemon() -> Result<()> {
+    let settings = load_settings()?;
+    let interval = Duration::from_secs(settings.daemon.interval_secs.max(1));
+    let server = match &settings.daemon.listen {
+        Some(listen) => Some(start_server(listen, status_router(&settings))?),
+        None => None,
+    };
+    let mut accounts =
+        build_accounts(&settings, settings.digest.schedule == DigestSchedule::Daily)?;
+
+    info!("{}", t!("app.daemon_started", secs = interval.as_secs()));
+    loop {
+        let started_at = Utc::now();
+        let results = process_accounts(&accounts, settings.account_mode).await;
+        for (account, result) in accounts.iter_mut().zip(results) {
+            if let Ok(report) = &result {
+                account.log_report(report);
+            }
+            record_run(
+                account.processor.account(),
+                &mut account.digest,
+                result,
+                started_at,
+            )
+            .await;
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                info!("{}", t!("app.daemon_stopped"));
+                break;
+            }
+        }
+    }
+    if let Some(server) = server {
+        server.abort();
+    }
+
+    for account in &mut accounts {
+        account.processor.flush_events().await;
+        if let Some(digest) = &mut account.digest {
+            digest.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// ChatworkのWebhookを受信し、メッセージが届いたルームだけを処理するサーバーを起動します。
+///
+/// 起動時に一度全てのルームを処理し、その後はWebhookを受信したルームのみを処理します。
+/// Ctrl+Cを受け取ると、記録中のダイジェストを書き込んで終了します。
+/// Webhookはアカウントごとに設定されるため、処理できるアカウントは1つのみです。
+///
+/// # エラー
+///
+/// 設定の読み込みやサーバーの起動に失敗した場合、複数のアカウントが設定されている場合に、
+/// `anyhow::Error`でラップされたエラーを返します。
+pub async fn serve() -> Result<()> {
+    let settings = load_settings()?;
+    if settings.accounts.len() > 1 {
+        anyhow::bail!(t!("app.serve_single_account"));
+    }
+    let receiver_settings = settings
+        .webhook_receiver
+        .clone()
+        .with_context(|| t!("app.webhook_receiver_missing"))?;
+    let debounce = Duration::from_millis(receiver_settings.debounce_ms);
+    let receiver = Arc::new(WebhookReceiver::new(receiver_settings.token.expose())?);
+    let router = receiver
+        .clone()
+        .router(&receiver_settings.path)
+        .merge(status_router(&settings));
+    let Account {
+        processor,
+        mut digest,
+    } = build_accounts(&settings, settings.digest.schedule == DigestSchedule::Daily)?.remove(0);
+
+    let mut server = start_server(&receiver_settings.listen, router)?;
+    info!(
+        "{}",
+        t!(
+            "app.webhook_listening",
+            listen = receiver_settings.listen,
+            path = receiver_settings.path
+        )
+    );
+
+    // 起動前に届いていたメッセージを既読にするため、最初に全てのルームを処理する
+    let started_at = Utc::now();
+    let result = processor.process_all_rooms().await;
+    record_run(processor.account(), &mut digest, result, started_at).await;
+
+    loop {
+        tokio::select! {
+            room_ids = receiver.wait_pending(debounce) => {
+                let started_at = Utc::now();
+                let result = processor.process_rooms_by_id(&room_ids).await;
+                record_run(processor.account(), &mut digest, result, started_at).await;
+            }
+            _ = &mut server => {
+                anyhow::bail!(t!("app.webhook_server_stopped"));
+            }
+            _ = tokio::signal::ctrl_c() => {
+                info!("{}", t!("app.webhook_stopped"));
+                server.abort();
+                break;
+            }
+        }
+    }
+
+    processor.flush_events().await;
+    if let Some(digest) = &mut digest {
+        digest.flush().await?;
+    }
+    Ok(())
+}
+
+/// `/metrics`・`/healthz`・`/readyz`を公開するルーターを作成します。
+fn status_router(settings: &Settings) -> axum::Router {
+    metrics::router().merge(health::router(settings.health.clone()))
+}
+
+/// 指定されたアドレスでHTTPサーバーを起動します。
+///
+/// ルームの処理中もリクエストに応答できるよう、サーバーは別のタスクで実行します。
+/// サーバーが停止した場合は、その原因をログに記録します。
+///
+/// # エラー
+///
+/// アドレスが正しくない場合や、待ち受けを開始できない場合に`anyhow::Error`を返します。
+fn start_server(listen: &str, router: axum::Router) -> Result<JoinHandle<()>> {
+    let addr: SocketAddr = listen
+        .parse()
+        .with_context(|| t!("app.invalid_listen", listen = listen))?;
+    let server = axum::Server::try_bind(&addr)?.serve(router.into_make_service());
+    info!("{}", t!("app.server_started", addr = addr));
+    Ok(tokio::spawn(async move {
+        if let Err(e) = server.await {
+            error!("{}", t!("app.server_failed", error = e));
+        }
+    }))
+}
+
+/// 常駐して実行するモードで、1回分の処理結果をダイジェストとアカウントの稼働状態に記録します。
+///
+/// 処理の失敗はログに記録し、次の実行を継続できるようにします。
+async fn record_run(
+    account: Option<&str>,
+    digest: &mut Option<DigestCollector>,
+    result: Result<RunReport, Error>,
+    started_at: chrono::DateTime<Utc>,
+) {
+    match result {
+        Ok(report) => {
+            let finished_at = Utc::now();
+            health::health().record_success(account, finished_at);
+            for room in &report.rooms {
+                if let report::RoomOutcome::Failed(e) = &room.outcome {
+                    health::health().record_error(
+                        account,
+                        &t!("app.room_error", room_id = room.room_id, error = e),
+                        finished_at,
+                    );
+                }
+            }
+            if let Some(digest) = digest {
+                // 出力先ごとの失敗は`DigestCollector`がログに記録する
+                let _ = digest.record(&report, started_at, finished_at).await;
+            }
+        }
+        Err(e) => {
+            error!("{}", t!("app.run_failed", error = e));
+            health::health().record_error(account, &e.to_string(), Utc::now());
+        }
+    }
+}
+
+/// 設定に従って、出力先を設定した`DigestCollector`を作成します。
+///
+/// ダイジェストが無効な場合は`None`を返します。
+///
+/// # エラー
+///
+/// Chatworkに投稿する設定で、OAuthのトークンを読み込めない場合に`anyhow::Error`を返します。
+fn build_digest(settings: &Settings, daily: bool) -> Result<Option<DigestCollector>> {
+    if !settings.digest.enabled {
+        return Ok(None);
+    }
+
+    let mut collector = DigestCollector::new(daily);
+    if settings.digest.write_file {
+        collector = collector.with_sink(FileSink::new(settings.digest.clone()));
+    }
+    if settings.digest.post_to_chatwork {
+        collector = collector.with_sink(ChatworkSink::new(
+            chatwork_client(settings)?,
+            settings.digest.clone(),
+        ));
+    }
+    Ok(Some(collector))
+}
+
+/// 設定されたAPIトークンまたはOAuthのトークンと、ログの秘匿の設定で`ChatworkClient`を作成します。
+///
+/// 環境変数`APP_CHATWORK_API_TOKEN`でAPIトークンが指定されている場合は、OAuthより優先します。
+///
+/// # エラー
+///
+/// OAuthのトークンのファイルがない場合や、読み込めない場合に`anyhow::Error`を返します。
+fn chatwork_client(settings: &Settings) -> Result<ChatworkClient> {
+    let client = match &settings.chatwork.oauth {
+        Some(oauth) if settings.chatwork.api_token.is_empty() => {
+            ChatworkClient::from_oauth(oauth::session(oauth)?)
+        }
+        _ => ChatworkClient::new(settings.chatwork.api_token.expose()),
+    };
+    Ok(client.with_redaction(settings.logging.redaction.clone()))
+}
+
+/// 設定に従って、ジャーナルやアーカイブを設定した`MessageProcessor`を作成します。
+///
+/// # 引数
+///
+/// * `settings` - 処理するアカウントの設定
+/// * `name` - `[[accounts]]`に記述されたアカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+/// * `event_sink` - 実行イベントの送信先（アカウント間で共有）
+fn build_processor(
+    settings: Settings,
+    name: Option<String>,
+    event_sink: Option<Arc<WebhookEventSink>>,
+) -> Result<MessageProcessor<ChatworkClient>> {
+    let mut client = chatwork_client(&settings)?;
+    if let Some(name) = &name {
+        client = client.with_account(name);
+    }
+    let journal = settings
+        .journal
+        .enabled
+        .then(|| Journal::new(&settings.journal.path));
+    let archive = if settings.archive.enabled {
+        Some(Archive::open(&settings.archive.path)?)
+    } else {
+        None
+    };
+    let notifiers = settings
+        .notifiers
+        .iter()
+        .map(notifier::build_notifier)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut processor = MessageProcessor::new(client, settings);
+    if let Some(name) = name {
+        processor = processor.with_account(name);
+    }
+    for notifier in notifiers {
+        processor = processor.with_notifier(notifier);
+    }
+    if let Some(sink) = event_sink {
+        processor = processor.with_event_sink(Box::new(sink));
+    }
+    if let Some(journal) = journal {
+        processor = processor.with_journal(journal);
+    }
+    if let Some(archive) = archive {
+        processor = processor.with_archive(archive);
+    }
+    Ok(processor)
+}
+
+/// コマンドライン引数に従って処理を実行します。
+///
+/// サブコマンドが指定されていない場合は`run`を実行します。
+///
+/// # エラー
+///
+/// 各サブコマンドの処理中にエラーが発生した場合、`anyhow::Error`でラップされたエラーを返します。
+pub async fn execute(cli: Cli) -> Result<()> {
+    let result = match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run().await,
+        Command::Daemon => daemon().await,
+        Command::Serve => serve().await,
+        Command::Undo(args) => undo(args).await,
+        Command::Journal(args) => show_journal(args),
+        Command::Search(args) => search(args),
+        Command::StoreToken(args) => store_token(args),
+        Command::Authorize(args) => authorize(args).await,
+        Command::CheckConfig(args) => check_config(args).await,
+        Command::ConfigSchema => {
+            print!("{}", validation::schema_json());
+            Ok(())
+        }
+    };
+    #[cfg(feature = "otlp")]
+    telemetry::shutdown();
+    result
+}
+
+/// ジャーナルの記録に従って、ツールが既読にしたメッセージを未読に戻します。
+///
+/// 期間が指定されていない場合は、最後の実行で既読にしたメッセージを未読に戻します。
+///
+/// # エラー
+///
+/// 設定やジャーナルの読み込みに失敗した場合、`anyhow::Error`でラップされたエラーを返します。
+pub async fn undo(args: UndoArgs) -> Result<()> {
+    let settings = load_settings()?;
+    let journal = Journal::new(&settings.journal.path);
+
+    let mut undone = false;
+    for (account, settings) in settings.per_account() {
+        let entries: Vec<_> = if args.since.is_none() && args.until.is_none() {
+            journal.last_run(account.as_deref())?
+        } else {
+            journal
+                .read_between(args.since, args.until)?
+                .into_iter()
+                .filter(|entry| entry.account == account)
+                .collect()
+        };
+        if entries.is_empty() {
+            continue;
+        }
+        undone = true;
+        if let Some(account) = &account {
+            println!("{}", t!("cli.undo_account", account = account));
+        }
+        undo_account(&chatwork_client(&settings)?, &journal, &entries).await;
+    }
+    if !undone {
+        println!("{}", t!("cli.undo_nothing"));
+    }
+    Ok(())
+}
+
+/// 1つのアカウントの既読の記録を取り消し、ルームごとの結果を表示します。
+async fn undo_account(client: &ChatworkClient, journal: &Journal, entries: &[JournalEntry]) {
+    for outcome in undo::undo_entries(client, journal, entries).await {
+        match outcome.result {
+            Ok(unread_num) => println!(
+                "{}",
+                t!(
+                    "cli.undo_done",
+                    room_id = outcome.room_id,
+                    message_id = outcome.message_id.unwrap_or_default(),
+                    unread = unread_num
+                )
+            ),
+            Err(e) => println!(
+                "{}",
+                t!("cli.undo_failed", room_id = outcome.room_id, error = e)
+            ),
+        }
+    }
+}
+
+/// ジャーナルの記録を期間やルームで絞り込んで表示します。
+///
+/// # エラー
+///
+/// 設定やジャーナルの読み込みに失敗した場合、`anyhow::Error`でラップされたエラーを返します。
+pub fn show_journal(args: JournalArgs) -> Result<()> {
+    let settings = load_settings()?;
+    let journal = Journal::new(&settings.journal.path);
+
+    let (since, until) = args.period.range();
+    let entries = journal.query(since, until, args.room)?;
+    if entries.is_empty() && !args.json {
+        println!("{}", t!("cli.journal_empty"));
+        return Ok(());
+    }
+
+    for entry in entries {
+        if args.json {
+            println!("{}", serde_json::to_string(&entry)?);
+        } else {
+            println!("{}", entry);
+        }
+    }
+    Ok(())
+}
+
+/// 標準入力から1行読み込んだAPIトークンを、OSのキーリングに保存します。
+///
+/// 保存したトークンは、設定の`api_token_keyring`に同じサービス名とユーザー名を指定すると読み込まれます。
+///
+/// # エラー
+///
+/// トークンが空の場合や、キーリングへの保存に失敗した場合、`anyhow::Error`でラップされたエラーを返します。
+pub fn store_token(args: StoreTokenArgs) -> Result<()> {
+    // トークンを保存する前に設定ファイルを用意する必要がないよう、設定は読み込みません
+    utils::setup_logging(&settings::LoggingSettings::default());
+    eprintln!("{}", t!("cli.store_token_prompt"));
+    let mut token = String::new();
+    std::io::stdin().read_line(&mut token)?;
+
+    let entry = settings::KeyringEntry {
+        service: args.service,
+        user: args.user,
+    };
+    token::store_keyring(&entry, &token)?;
+    println!(
+        "{}",
+        t!(
+            "cli.store_token_done",
+            service = entry.service,
+            user = entry.user
+        )
+    );
+    Ok(())
+}
+
+/// ブラウザでChatworkへのアクセスを許可してもらい、OAuthのトークンを取得して保存します。
+///
+/// `[[accounts]]`を使用している場合は、`--account`で指定したアカウント、
+/// または`oauth`を設定した全てのアカウントを順番に認可します。
+///
+/// # エラー
+///
+/// 設定の読み込みや認可に失敗した場合、OAuthを設定したアカウントがない場合に、
+/// `anyhow::Error`でラップされたエラーを返します。
+pub async fn authorize(args: AuthorizeArgs) -> Result<()> {
+    let settings = load_settings()?;
+
+    let mut authorized = false;
+    for (account, settings) in settings.per_account() {
+        if args.account.is_some() && account != args.account {
+            continue;
+        }
+        let Some(oauth) = &settings.chatwork.oauth else {
+            continue;
+        };
+        if let Some(account) = &account {
+            println!("{}", t!("cli.authorize_account", account = account));
+        }
+        oauth::authorize(oauth).await?;
+        println!(
+            "{}",
+            t!("cli.authorize_done", path = oauth.token_path.display())
+        );
+        authorized = true;
+    }
+    if !authorized {
+        anyhow::bail!(t!("cli.authorize_not_configured"));
+    }
+    Ok(())
+}
+
+/// 設定を検証し、見つかった問題を場所とともに表示します。
+///
+/// `--offline`を指定しない場合は、アカウントごとにAPIでトークンが有効か、
+/// 設定で参照しているルームとアカウントが存在するかも確認します。
+///
+/// # エラー
+///
+/// 設定を読み込めない場合（誤りがある場合を含む）、APIによる確認でエラーが見つかった場合に、
+/// `anyhow::Error`でラップされたエラーを返します。
+pub async fn check_config(args: CheckConfigArgs) -> Result<()> {
+    // APIの呼び出しごとのログで結果が読みにくくならないよう、警告以上のみを出力します
+    utils::setup_logging(&settings::LoggingSettings {
+        level: "warn".to_string(),
+        ..Default::default()
+    });
+    let settings = read_settings()?;
+
+    let mut warnings = settings.warnings.clone();
+    let mut errors = Vec::new();
+    if !args.offline {
+        for (index, (account, account_settings)) in settings.per_account().into_iter().enumerate() {
+            let location = match &account {
+                Some(_) => format!("accounts[{}]", index),
+                None => "chatwork".to_string(),
+            };
+            let digest_room = account_settings
+                .digest
+                .post_room_id
+                .filter(|_| settings.digest.enabled && settings.digest.post_to_chatwork)
+                .map(|room_id| {
+                    let account_room = settings.accounts.get(index).and_then(|a| a.digest_room_id);
+                    match account_room {
+                        Some(_) => (room_id, format!("{}.digest_room_id", location)),
+                        None => (room_id, "digest.post_room_id".to_string()),
+                    }
+                });
+            let client = match chatwork_client(&account_settings) {
+                Ok(client) => client,
+                Err(e) => {
+                    errors
+                        .push(validation::ConfigIssue::error(location, e.to_string()).to_string());
+                    continue;
+                }
+            };
+            let (issues, me) =
+                validation::check_api(&client, &account_settings.chatwork, &location, digest_room)
+                    .await;
+            if let Some(me) = me {
+                println!(
+                    "{}",
+                    t!(
+                        "cli.check_config_account",
+                        account = account.as_deref().unwrap_or("chatwork"),
+                        name = me.name,
+                        account_id = me.account_id
+                    )
+                );
+            }
+            for issue in issues {
+                if issue.is_error() {
+                    errors.push(issue.to_string());
+                } else {
+                    warnings.push(issue.to_string());
+                }
+            }
+        }
+    }
+
+    for issue in &errors {
+        println!("{}", t!("cli.check_config_error", issue = issue));
+    }
+    for issue in &warnings {
+        println!("{}", t!("cli.check_config_warning", issue = issue));
+    }
+    if !errors.is_empty() {
+        anyhow::bail!(t!(
+            "cli.check_config_failed",
+            errors = errors.len(),
+            warnings = warnings.len()
+        ));
+    }
+    println!("{}", t!("cli.check_config_ok", warnings = warnings.len()));
+    Ok(())
+}
+
+/// アーカイブしたメッセージを検索し、ルーム名とパーマリンクを添えて表示します。
+///
+/// # エラー
+///
+/// 設定やアーカイブの読み込みに失敗した場合、`anyhow::Error`でラップされたエラーを返します。
+pub fn search(args: SearchArgs) -> Result<()> {
+    let settings = load_settings()?;
+    let archive = Archive::open(&settings.archive.path)?;
 
-    processor.process_all_rooms().await?;
+    let (since, until) = args.period.range();
+    let hits = archive.search(&SearchQuery {
+        text: args.terms.join(" "),
+        room_id: args.room,
+        sender: args.sender,
+        since,
+        until,
+        limit: args.limit,
+    })?;
+    if hits.is_empty() {
+        println!("{}", t!("cli.search_empty"));
+        return Ok(());
+    }
 
+    for hit in hits {
+        let send_time = chrono::DateTime::from_timestamp(hit.message.send_time, 0)
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        println!(
+            "{} [{}] {}: {}",
+            send_time,
+            hit.room_name,
+            hit.message.account.name,
+            utils::excerpt(&hit.message.body, 80)
+        );
+        println!("  {}", hit.permalink());
+    }
     Ok(())
 }
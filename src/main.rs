@@ -4,19 +4,22 @@
 //! 起動し、実行します。
 
 use anyhow::Result;
-use chatwork_auto_read::run;
+use chatwork_auto_read::{cli::Cli, execute, i18n, settings::Settings, t};
+use clap::Parser;
 use log::error;
 
 /// プログラムのメインエントリーポイントです。
 ///
 /// この関数は以下の処理を行います：
-/// 1. `run`関数を呼び出してChatwork自動既読システムを実行します。
-/// 2. エラーが発生した場合、エラーメッセージをログに記録し、
+/// 1. 設定ファイルの`language`、またはOSのロケールからメッセージの言語を選択します（ヘルプにも適用されます）。
+/// 2. コマンドライン引数を解析し、`execute`関数で指定されたサブコマンドを実行します。
+///    サブコマンドが省略された場合はChatwork自動既読システムを実行します。
+/// 3. エラーが発生した場合、エラーメッセージをログに記録し、
 ///    プログラムを異常終了させます。
 ///
 /// # エラー処理
 ///
-/// - `run`関数がエラーを返した場合、エラーメッセージがログに記録され、
+/// - `execute`関数がエラーを返した場合、エラーメッセージがログに記録され、
 ///   プログラムは終了コード1で終了します。
 /// - 正常に実行された場合、`Ok(())`を返します。
 ///
@@ -25,10 +28,13 @@ use log::error;
 /// この関数は`tokio`ランタイム上で非同期的に実行されます。
 #[tokio::main]
 async fn main() -> Result<()> {
-    if let Err(e) = run().await {
+    // 設定で言語が指定されていない場合は、OSのロケールに従います
+    i18n::set_language(Settings::configured_language().unwrap_or_else(i18n::Language::detect));
+    let cli = Cli::parse();
+    if let Err(e) = execute(cli).await {
         // エラーが発生した場合、ログにエラーメッセージを記録し、
         // プログラムを異常終了させます。
-        error!("アプリケーションエラー: {}", e);
+        error!("{}", t!("app.error", error = e));
         std::process::exit(1);
     }
     Ok(())
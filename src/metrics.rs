@@ -0,0 +1,320 @@
+//! メトリクスモジュール
+//!
+//! このモジュールは、ルームの処理結果やChatwork APIの呼び出しを計測し、
+//! Prometheusのテキスト形式で`/metrics`として公開するための機能を提供します。
+//! 全てのメトリクスには、`[[accounts]]`に記述されたアカウントの名前を`account`ラベルとして付けます
+//! （`[[accounts]]`を使用していない場合は空文字列）。
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+use crate::report::{RoomOutcome, RoomReport};
+
+/// アプリケーション全体で共有するメトリクスです。
+pub struct Metrics {
+    registry: Registry,
+    /// 処理したルームの数（処理結果別）
+    rooms_processed: IntCounterVec,
+    /// 既読にしたメッセージの数
+    messages_read: IntCounterVec,
+    /// スキップしたルームの数（理由別）
+    rooms_skipped: IntCounterVec,
+    /// APIリクエストの数（エンドポイント・ステータス別）
+    api_requests: IntCounterVec,
+    /// レート制限（429）を受けた数（エンドポイント別）
+    api_rate_limited: IntCounterVec,
+    /// APIリクエストを再試行した数（エンドポイント別）
+    api_retries: IntCounterVec,
+    /// APIリクエストの所要時間（エンドポイント別）
+    api_request_duration: HistogramVec,
+}
+
+impl Metrics {
+    /// 新しいレジストリにメトリクスを登録して作成します。
+    fn new() -> Self {
+        let registry = Registry::new();
+        let rooms_processed = IntCounterVec::new(
+            Opts::new(
+                "chatwork_rooms_processed_total",
+                "処理したルームの数（処理結果別）",
+            ),
+            &["account", "outcome"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let messages_read = IntCounterVec::new(
+            Opts::new("chatwork_messages_read_total", "既読にしたメッセージの数"),
+            &["account"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let rooms_skipped = IntCounterVec::new(
+            Opts::new(
+                "chatwork_rooms_skipped_total",
+                "スキップしたルームの数（理由別）",
+            ),
+            &["account", "reason"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let api_requests = IntCounterVec::new(
+            Opts::new(
+                "chatwork_api_requests_total",
+                "APIリクエストの数（エンドポイント・ステータス別）",
+            ),
+            &["account", "endpoint", "status"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let api_rate_limited = IntCounterVec::new(
+            Opts::new(
+                "chatwork_api_rate_limited_total",
+                "レート制限（429）を受けた数",
+            ),
+            &["account", "endpoint"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let api_retries = IntCounterVec::new(
+            Opts::new("chatwork_api_retries_total", "APIリクエストを再試行した数"),
+            &["account", "endpoint"],
+        )
+        .expect("メトリクスの定義が不正です");
+        let api_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "chatwork_api_request_duration_seconds",
+                "APIリクエストの所要時間（秒）",
+            ),
+            &["account", "endpoint"],
+        )
+        .expect("メトリクスの定義が不正です");
+
+        for collector in [
+            Box::new(rooms_processed.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(messages_read.clone()),
+            Box::new(rooms_skipped.clone()),
+            Box::new(api_requests.clone()),
+            Box::new(api_rate_limited.clone()),
+            Box::new(api_retries.clone()),
+            Box::new(api_request_duration.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("メトリクスの登録に失敗しました");
+        }
+
+        Self {
+            registry,
+            rooms_processed,
+            messages_read,
+            rooms_skipped,
+            api_requests,
+            api_rate_limited,
+            api_retries,
+            api_request_duration,
+        }
+    }
+
+    /// ルームの処理結果を記録します。
+    ///
+    /// # 引数
+    ///
+    /// * `account` - アカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+    /// * `room` - ルームの処理結果
+    pub fn record_room(&self, account: Option<&str>, room: &RoomReport) {
+        let account = account.unwrap_or_default();
+        let outcome = match &room.outcome {
+            RoomOutcome::Read { consumed, .. } => {
+                self.messages_read
+                    .with_label_values(&[account])
+                    .inc_by(*consumed as u64);
+                "read"
+            }
+            RoomOutcome::NothingToRead => {
+                self.rooms_skipped
+                    .with_label_values(&[account, "nothing_to_read"])
+                    .inc();
+                "skipped"
+            }
+            RoomOutcome::Skipped(reason) => {
+                self.rooms_skipped
+                    .with_label_values(&[account, reason.key()])
+                    .inc();
+                "skipped"
+            }
+            RoomOutcome::Failed(_) => "failed",
+        };
+        self.rooms_processed
+            .with_label_values(&[account, outcome])
+            .inc();
+    }
+
+    /// APIリクエストの結果と所要時間を記録します。
+    ///
+    /// # 引数
+    ///
+    /// * `account` - アカウントの名前（`[[accounts]]`を使用していない場合は`None`）
+    /// * `endpoint` - エンドポイント（例: `GET /rooms/{room_id}/messages`）
+    /// * `status` - レスポンスのステータスコード（通信に失敗した場合は`None`）
+    /// * `elapsed` - リクエストの所要時間
+    pub fn observe_request(
+        &self,
+        account: Option<&str>,
+        endpoint: &str,
+        status: Option<reqwest::StatusCode>,
+        elapsed: Duration,
+    ) {
+        let account = account.unwrap_or_default();
+        let status =
+            status.map_or_else(|| "error".to_string(), |status| status.as_u16().to_string());
+        self.api_requests
+            .with_label_values(&[account, endpoint, &status])
+            .inc();
+        self.api_request_duration
+            .with_label_values(&[account, endpoint])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// レート制限を受けたことを記録します。
+    pub fn record_rate_limited(&self, account: Option<&str>, endpoint: &str) {
+        self.api_rate_limited
+            .with_label_values(&[account.unwrap_or_default(), endpoint])
+            .inc();
+    }
+
+    /// APIリクエストを再試行したことを記録します。
+    pub fn record_retry(&self, account: Option<&str>, endpoint: &str) {
+        self.api_retries
+            .with_label_values(&[account.unwrap_or_default(), endpoint])
+            .inc();
+    }
+
+    /// 全てのメトリクスをPrometheusのテキスト形式で返します。
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("メトリクスのエンコードに失敗しました");
+        String::from_utf8(buffer).expect("メトリクスはUTF-8で出力されます")
+    }
+}
+
+/// アプリケーション全体で共有するメトリクスを返します。
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+/// `/metrics`でメトリクスを公開するルーターを作成します。
+pub fn router() -> Router {
+    Router::new().route(
+        "/metrics",
+        get(|| async {
+            (
+                [(CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+                metrics().render(),
+            )
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{RunReport, SkipReason};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_record_room() {
+        let metrics = Metrics::new();
+        let mut report = RunReport::default();
+        report.push(
+            1,
+            "開発",
+            RoomOutcome::Read {
+                message_id: "10".to_string(),
+                consumed: 3,
+            },
+        );
+        report.push(2, "雑談", RoomOutcome::Skipped(SkipReason::Mention));
+        report.push(3, "連絡", RoomOutcome::NothingToRead);
+        report.push(4, "障害", RoomOutcome::Failed("APIエラー".to_string()));
+        for room in &report.rooms {
+            metrics.record_room(None, room);
+        }
+        metrics.record_room(Some("work"), &report.rooms[0]);
+
+        let text = metrics.render();
+        assert!(text.contains("chatwork_rooms_processed_total{account=\"\",outcome=\"read\"} 1"));
+        assert!(text.contains("chatwork_rooms_processed_total{account=\"\",outcome=\"skipped\"} 2"));
+        assert!(text.contains("chatwork_rooms_processed_total{account=\"\",outcome=\"failed\"} 1"));
+        assert!(text.contains("chatwork_messages_read_total{account=\"\"} 3"));
+        assert!(text.contains("chatwork_rooms_skipped_total{account=\"\",reason=\"mention\"} 1"));
+        assert!(text
+            .contains("chatwork_rooms_skipped_total{account=\"\",reason=\"nothing_to_read\"} 1"));
+        assert!(
+            text.contains("chatwork_rooms_processed_total{account=\"work\",outcome=\"read\"} 1")
+        );
+        assert!(text.contains("chatwork_messages_read_total{account=\"work\"} 3"));
+    }
+
+    #[test]
+    fn test_observe_request() {
+        let metrics = Metrics::new();
+        let endpoint = "GET /rooms";
+        let account = Some("work");
+        metrics.observe_request(
+            account,
+            endpoint,
+            Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
+            Duration::from_millis(20),
+        );
+        metrics.record_rate_limited(account, endpoint);
+        metrics.record_retry(account, endpoint);
+        metrics.observe_request(
+            account,
+            endpoint,
+            Some(reqwest::StatusCode::OK),
+            Duration::from_millis(30),
+        );
+        metrics.observe_request(account, endpoint, None, Duration::from_millis(5));
+
+        let text = metrics.render();
+        let labels = "account=\"work\",endpoint=\"GET /rooms\"";
+        assert!(text.contains(&format!(
+            "chatwork_api_requests_total{{{labels},status=\"429\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "chatwork_api_requests_total{{{labels},status=\"200\"}} 1"
+        )));
+        assert!(text.contains(&format!(
+            "chatwork_api_requests_total{{{labels},status=\"error\"}} 1"
+        )));
+        assert!(text.contains(&format!("chatwork_api_rate_limited_total{{{labels}}} 1")));
+        assert!(text.contains(&format!("chatwork_api_retries_total{{{labels}}} 1")));
+        assert!(text.contains(&format!(
+            "chatwork_api_request_duration_seconds_count{{{labels}}} 3"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_router() {
+        metrics().record_retry(None, "GET /my/tasks");
+
+        let response = router()
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], prometheus::TEXT_FORMAT);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            text.contains("chatwork_api_retries_total{account=\"\",endpoint=\"GET /my/tasks\"}")
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Chatworkのアカウント情報を表す構造体です。
+///
+/// メッセージの送信者など、APIレスポンスに含まれるアカウント情報をデシリアライズするために使用されます。
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Account {
+    /// アカウントの一意識別子です。
+    pub account_id: i32,
+
+    /// アカウントの表示名です。
+    #[serde(default)]
+    pub name: String,
+}
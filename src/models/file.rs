@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+use super::Account;
+
+/// Chatworkのルームにアップロードされたファイルを表す構造体です。
+///
+/// この構造体は、`/rooms/{room_id}/files`のレスポンスをデシリアライズするために使用されます。
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct File {
+    /// ファイルの一意識別子です。
+    pub file_id: i64,
+
+    /// ファイルをアップロードしたアカウントです。
+    #[serde(default)]
+    pub account: Account,
+
+    /// ファイルが添付されたメッセージのIDです。
+    #[serde(default)]
+    pub message_id: String,
+
+    /// ファイル名です。
+    #[serde(default)]
+    pub filename: String,
+
+    /// ファイルサイズ（バイト）です。
+    #[serde(default)]
+    pub filesize: u64,
+
+    /// アップロード日時（UNIX時間、秒）です。
+    #[serde(default)]
+    pub upload_date: i64,
+}
@@ -5,6 +5,12 @@
 //! エンティティがここで定義され、アプリケーション全体で
 //! 使用されます。
 
+/// アカウント関連の構造体を含むモジュール
+mod account;
+
+/// ファイル関連の構造体を含むモジュール
+mod file;
+
 /// メッセージ関連の構造体と機能を含むモジュール
 mod message;
 
@@ -13,6 +19,19 @@ mod room;
 
 mod read_status;
 
+/// タスク関連の構造体を含むモジュール
+mod task;
+
+/// Chatworkのアカウントを表す構造体
+///
+/// メッセージの送信者などの情報を保持します。
+pub use account::Account;
+
+/// Chatworkのルームにアップロードされたファイルを表す構造体
+///
+/// 添付ファイルを含むメッセージの保護に使用されます。
+pub use file::File;
+
 /// Chatworkのメッセージを表す構造体
 ///
 /// この構造体は、個々のChatworkメッセージのデータを
@@ -26,3 +45,8 @@ pub use message::Message;
 pub use room::Room;
 
 pub use read_status::ReadStatus;
+
+/// Chatworkのタスクを表す構造体
+///
+/// 自分に割り当てられた未完了タスクの判定に使用されます。
+pub use task::{Task, TaskRoom};
@@ -1,7 +1,13 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+/// メッセージを既読・未読にした後のルームの状態を表す構造体です。
+///
+/// `/rooms/{room_id}/messages/read`および`/rooms/{room_id}/messages/unread`の
+/// レスポンスをデシリアライズするために使用されます。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ReadStatus {
+    /// 操作後の未読メッセージ数です。
     pub unread_num: i32,
+    /// 操作後の未読メンション数です。
     pub mention_num: i32,
 }
@@ -8,13 +8,16 @@ use serde::Deserialize;
 /// # フィールド
 ///
 /// * `room_id` - ルームの一意識別子
+/// * `name` - ルーム名
 /// * `unread_num` - 未読メッセージ数
 /// * `mention_num` - メンション（呼びかけ）の数
+/// * `mytask_num` - 自分に割り当てられた未完了タスクの数
+/// * `room_type` - ルームの種類（`my`、`direct`、`group`）
 ///
 /// # 使用例
 ///
 /// ```
-/// use your_crate_name::Room;
+/// use chatwork_auto_read::models::Room;
 ///
 /// let json_data = r#"
 ///     {
@@ -27,13 +30,19 @@ use serde::Deserialize;
 /// let room: Room = serde_json::from_str(json_data).unwrap();
 /// assert_eq!(room.room_id, 123);
 /// ```
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Room {
     /// ルームの一意識別子です。
     ///
     /// この識別子は整数形式で、Chatwork内でルームを一意に特定するために使用されます。
     pub room_id: i32,
 
+    /// ルームの名前です。
+    ///
+    /// インクルードモードなど、ルーム名のパターンによる判定に使用されます。
+    #[serde(default)]
+    pub name: String,
+
     /// ルーム内の未読メッセージ数です。
     ///
     /// この値は、ユーザーがまだ読んでいないメッセージの総数を示します。
@@ -44,4 +53,14 @@ pub struct Room {
     /// この値は、ユーザーに対する未読のメンションの数を示します。
     /// メンションは通常、ユーザーの注意を特定のメッセージに向けるために使用されます。
     pub mention_num: i32,
+
+    /// ルーム内で自分に割り当てられた未完了タスクの数です。
+    #[serde(default)]
+    pub mytask_num: i32,
+
+    /// ルームの種類です。
+    ///
+    /// マイチャットは`my`、個人チャットは`direct`、グループチャットは`group`になります。
+    #[serde(rename = "type", default)]
+    pub room_type: String,
 }
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+/// タスクが属するルームの情報を表す構造体です。
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct TaskRoom {
+    /// ルームの一意識別子です。
+    pub room_id: i32,
+
+    /// ルームの名前です。
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Chatworkのタスクを表す構造体です。
+///
+/// この構造体は、`/my/tasks`や`/rooms/{room_id}/tasks`のレスポンスをデシリアライズするために使用されます。
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Task {
+    /// タスクの一意識別子です。
+    pub task_id: i64,
+
+    /// タスクが属するルームです。
+    ///
+    /// `/my/tasks`のレスポンスにのみ含まれます。
+    #[serde(default)]
+    pub room: Option<TaskRoom>,
+
+    /// タスクが作成されたメッセージのIDです。
+    #[serde(default)]
+    pub message_id: String,
+
+    /// タスクの内容です。
+    #[serde(default)]
+    pub body: String,
+
+    /// タスクの状態（"open" または "done"）です。
+    #[serde(default)]
+    pub status: String,
+}
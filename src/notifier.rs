@@ -0,0 +1,632 @@
+//! 通知モジュール
+//!
+//! このモジュールは、メンションなどのルールによって既読を止めたときに、
+//! Webhook・メール・コマンド実行で利用者に知らせるための機能を提供します。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use log::debug;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::Error;
+use crate::models::{Message, Room};
+use crate::report::{BlockReason, SkipReason};
+use crate::settings::{EmailNotifierSettings, NotifierKind, NotifierSettings, SmtpSecurity};
+use crate::t;
+
+/// 既読を止めたことを表すイベントです。
+///
+/// Webhookではこの構造体のJSONが送信され、コマンドでは標準入力に渡されます。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockedEvent {
+    /// ルームID
+    pub room_id: i32,
+    /// ルーム名
+    pub room_name: String,
+    /// 既読を止めたルールの識別子
+    pub rule: String,
+    /// 既読を止めた理由
+    pub reason: String,
+    /// 既読を止めたメッセージ（ルーム全体をスキップした場合は`None`）
+    pub message: Option<Message>,
+    /// ChatworkのWebアプリでルームまたはメッセージを開くURL
+    pub permalink: String,
+}
+
+impl BlockedEvent {
+    /// メッセージによって既読を止めたイベントを作成します。
+    pub fn for_message(room: &Room, message: &Message, reason: &BlockReason) -> Self {
+        Self {
+            room_id: room.room_id,
+            room_name: room.name.clone(),
+            rule: reason.key().to_string(),
+            reason: reason.to_string(),
+            message: Some(message.clone()),
+            permalink: format!(
+                "https://www.chatwork.com/#!rid{}-{}",
+                room.room_id, message.message_id
+            ),
+        }
+    }
+
+    /// ルーム全体をスキップしたイベントを作成します。
+    pub fn for_room(room: &Room, reason: &SkipReason) -> Self {
+        Self {
+            room_id: room.room_id,
+            room_name: room.name.clone(),
+            rule: reason.key().to_string(),
+            reason: reason.to_string(),
+            message: None,
+            permalink: format!("https://www.chatwork.com/#!rid{}", room.room_id),
+        }
+    }
+
+    /// メールの件名などに使用する1行の説明を返します。
+    pub fn subject(&self) -> String {
+        t!(
+            "notifier.subject",
+            room = self.room_name,
+            reason = self.reason
+        )
+    }
+
+    /// メール本文などに使用する複数行の説明を返します。
+    pub fn describe(&self) -> String {
+        let mut text = t!(
+            "notifier.describe_room",
+            room = self.room_name,
+            room_id = self.room_id,
+            rule = self.rule,
+            reason = self.reason
+        );
+        if let Some(message) = &self.message {
+            text.push_str(&t!(
+                "notifier.describe_message",
+                sender = message.account.name,
+                account_id = message.account.account_id,
+                body = message.body
+            ));
+        }
+        text.push_str(&format!("URL: {}\n", self.permalink));
+        text
+    }
+}
+
+/// Webhookの送信を待つ時間の上限です。
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 通知済みのイベントのキー（ルームIDとメッセージIDなど）です。
+///
+/// ファイルを指定した場合は変更のたびに保存するため、1回ずつ実行する場合（cronなど）も
+/// 同じイベントを再び通知しません。
+#[derive(Debug, Default)]
+pub struct NotifiedKeys {
+    path: Option<PathBuf>,
+    keys: BTreeSet<String>,
+}
+
+impl NotifiedKeys {
+    /// ファイルから通知済みのキーを読み込みます。ファイルがない場合は空の状態から始めます。
+    ///
+    /// # エラー
+    ///
+    /// ファイルを読み込めない場合や、内容が不正な場合に`Error`を返します。
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let keys = match fs::read(path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            keys,
+        })
+    }
+
+    /// 通知済みのキーかどうかを返します。
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// キーを通知済みにし、ファイルに保存します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルに保存できない場合に`Error`を返します。
+    pub fn insert(&mut self, key: String) -> Result<(), Error> {
+        if self.keys.insert(key) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// キーを未通知に戻し、ファイルに保存します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルに保存できない場合に`Error`を返します。
+    pub fn remove(&mut self, key: &str) -> Result<(), Error> {
+        if self.keys.remove(key) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 条件を満たすキーのみを残し、取り除いたキーがあればファイルに保存します。
+    ///
+    /// 再び通知することがなくなったキーを取り除き、ファイルが大きくなり続けないようにするために使用します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルに保存できない場合に`Error`を返します。
+    pub fn retain(&mut self, keep: impl FnMut(&String) -> bool) -> Result<(), Error> {
+        let len = self.keys.len();
+        self.keys.retain(keep);
+        if self.keys.len() != len {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// 一時ファイルに書き込んでから置き換え、書き込み中に中断しても内容が壊れないようにします。
+    fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_vec(&self.keys)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// 既読を止めたことを通知する方法を表すトレイトです。
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// イベントを通知します。
+    ///
+    /// # エラー
+    ///
+    /// 通知の送信に失敗した場合、`Error`を返します。
+    async fn notify(&self, event: &BlockedEvent) -> Result<(), Error>;
+}
+
+/// イベントのJSONを指定したURLにPOSTする通知です。
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl WebhookNotifier {
+    /// 新しい`WebhookNotifier`を作成します。
+    pub fn new(url: &str, headers: BTreeMap<String, String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            headers,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+        let mut request = self.client.post(&self.url).json(event);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::NotificationError(t!(
+                "notifier.webhook_status",
+                status = response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// SMTPでメールを送信する通知です。
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    /// 設定から新しい`EmailNotifier`を作成します。
+    ///
+    /// # エラー
+    ///
+    /// メールアドレスの形式が正しくない場合や、SMTPサーバーの設定が不正な場合、`Error`を返します。
+    pub fn new(settings: &EmailNotifierSettings) -> Result<Self, Error> {
+        let mut builder = match settings.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.smtp_host)
+            }
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.smtp_host)
+                    .map_err(notification_error)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.smtp_host)
+                .map_err(notification_error)?,
+        };
+        if let Some(port) = settings.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                password.expose().to_string(),
+            ));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: settings.from.parse().map_err(notification_error)?,
+            to: settings
+                .to
+                .iter()
+                .map(|to| to.parse().map_err(notification_error))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+        let mut builder = lettre::Message::builder()
+            .from(self.from.clone())
+            .subject(format!("[chatwork_auto_read] {}", event.subject()));
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let email = builder.body(event.describe()).map_err(notification_error)?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(notification_error)?;
+        Ok(())
+    }
+}
+
+/// コマンドを実行する通知です。
+///
+/// イベントのJSONを標準入力に渡し、主な項目を環境変数（`CHATWORK_ROOM_ID`、
+/// `CHATWORK_ROOM_NAME`、`CHATWORK_MESSAGE_ID`、`CHATWORK_RULE`、`CHATWORK_REASON`）に設定します。
+pub struct CommandNotifier {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandNotifier {
+    /// 新しい`CommandNotifier`を作成します。
+    pub fn new(program: &str, args: Vec<String>) -> Self {
+        Self {
+            program: program.to_string(),
+            args,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for CommandNotifier {
+    async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+        let mut child = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .env("CHATWORK_ROOM_ID", event.room_id.to_string())
+            .env("CHATWORK_ROOM_NAME", &event.room_name)
+            .env(
+                "CHATWORK_MESSAGE_ID",
+                event
+                    .message
+                    .as_ref()
+                    .map(|message| message.message_id.as_str())
+                    .unwrap_or_default(),
+            )
+            .env("CHATWORK_RULE", &event.rule)
+            .env("CHATWORK_REASON", &event.reason)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&serde_json::to_vec(event)?).await?;
+        }
+        let status = child.wait().await?;
+        if !status.success() {
+            return Err(Error::NotificationError(t!(
+                "notifier.command_failed",
+                status = status
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 指定したルールのイベントのみを通知する通知です。
+pub struct RuleFilter {
+    rules: Vec<String>,
+    inner: Box<dyn Notifier>,
+}
+
+#[async_trait]
+impl Notifier for RuleFilter {
+    async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+        if self.rules.contains(&event.rule) {
+            self.inner.notify(event).await
+        } else {
+            debug!("{}", t!("notifier.rule_filtered", rule = event.rule));
+            Ok(())
+        }
+    }
+}
+
+/// 設定から通知を作成します。
+///
+/// # エラー
+///
+/// 通知の設定が不正な場合、`Error`を返します。
+pub fn build_notifier(settings: &NotifierSettings) -> Result<Box<dyn Notifier>, Error> {
+    let notifier: Box<dyn Notifier> = match &settings.kind {
+        NotifierKind::Webhook { url, headers } => {
+            Box::new(WebhookNotifier::new(url, headers.clone()))
+        }
+        NotifierKind::Email(email) => Box::new(EmailNotifier::new(email)?),
+        NotifierKind::Command { program, args } => {
+            Box::new(CommandNotifier::new(program, args.clone()))
+        }
+    };
+
+    if settings.rules.is_empty() {
+        Ok(notifier)
+    } else {
+        Ok(Box::new(RuleFilter {
+            rules: settings.rules.clone(),
+            inner: notifier,
+        }))
+    }
+}
+
+fn notification_error(e: impl std::fmt::Display) -> Error {
+    Error::NotificationError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Account;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn sample_event() -> BlockedEvent {
+        let room = Room {
+            room_id: 10,
+            name: "開発".to_string(),
+            ..Default::default()
+        };
+        let message = Message {
+            message_id: "100".to_string(),
+            account: Account {
+                account_id: 1,
+                name: "山田".to_string(),
+            },
+            body: "[To:123]確認お願いします".to_string(),
+            send_time: 0,
+        };
+        BlockedEvent::for_message(&room, &message, &BlockReason::Mention("123".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("x-token", "secret"))
+            .and(body_partial_json(serde_json::json!({
+                "room_id": 10,
+                "rule": "mention",
+                "message": { "message_id": "100" },
+                "permalink": "https://www.chatwork.com/#!rid10-100",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let notifier = WebhookNotifier::new(
+            &format!("{}/hook", server.uri()),
+            BTreeMap::from([("x-token".to_string(), "secret".to_string())]),
+        );
+        notifier.notify(&sample_event()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let notifier = WebhookNotifier::new(&server.uri(), BTreeMap::new());
+        let result = notifier.notify(&sample_event()).await;
+        assert!(matches!(result, Err(Error::NotificationError(_))));
+    }
+
+    /// 1通のメールを受け取り、DATAの内容を返すだけのSMTPサーバーです。
+    async fn start_smtp_server() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+            let mut data = String::new();
+            let mut in_data = false;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 OK\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250 localhost\r\n"
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            data
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn test_email_notifier() {
+        let (port, server) = start_smtp_server().await;
+        let notifier = EmailNotifier::new(&EmailNotifierSettings {
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: Some(port),
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "bot@example.com".to_string(),
+            to: vec!["me@example.com".to_string()],
+        })
+        .unwrap();
+
+        notifier.notify(&sample_event()).await.unwrap();
+        drop(notifier);
+
+        let data = server.await.unwrap();
+        assert!(data.contains("To: me@example.com"));
+        assert!(data.contains("Subject: "));
+    }
+
+    #[test]
+    fn test_email_notifier_invalid_address() {
+        let result = EmailNotifier::new(&EmailNotifierSettings {
+            smtp_host: "localhost".to_string(),
+            smtp_port: None,
+            security: SmtpSecurity::None,
+            username: None,
+            password: None,
+            from: "not an address".to_string(),
+            to: Vec::new(),
+        });
+        assert!(matches!(result, Err(Error::NotificationError(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_notifier() {
+        let temp_dir = tempfile::TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let output = temp_dir.path().join("event.json");
+        let notifier = CommandNotifier::new(
+            "sh",
+            vec![
+                "-c".to_string(),
+                "cat > \"$1\"; printf '%s' \"$CHATWORK_RULE\" > \"$1.rule\"".to_string(),
+                "sh".to_string(),
+                output.to_string_lossy().to_string(),
+            ],
+        );
+        notifier.notify(&sample_event()).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(json["message"]["account"]["name"], "山田");
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("event.json.rule")).unwrap(),
+            "mention"
+        );
+
+        let failing = CommandNotifier::new("sh", vec!["-c".to_string(), "exit 3".to_string()]);
+        assert!(failing.notify(&sample_event()).await.is_err());
+    }
+
+    #[test]
+    fn test_notified_keys() {
+        let temp_dir = tempfile::TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let path = temp_dir.path().join("data/notified.json");
+
+        let mut keys = NotifiedKeys::load(&path).unwrap();
+        assert!(!keys.contains("10:100"));
+        keys.insert("10:100".to_string()).unwrap();
+        keys.insert("10:mention".to_string()).unwrap();
+        keys.remove("10:mention").unwrap();
+
+        // 次の実行（別のプロセス）でも通知済みのキーを引き継ぐ
+        let mut keys = NotifiedKeys::load(&path).unwrap();
+        assert!(keys.contains("10:100"));
+        assert!(!keys.contains("10:mention"));
+
+        // 取り除いたキーは保存され、次の実行では通知済みにならない
+        keys.insert("10:101".to_string()).unwrap();
+        keys.insert("20:200".to_string()).unwrap();
+        keys.retain(|key| key != "10:100").unwrap();
+        let keys = NotifiedKeys::load(&path).unwrap();
+        assert!(!keys.contains("10:100"));
+        assert!(keys.contains("10:101"));
+        assert!(keys.contains("20:200"));
+    }
+
+    #[tokio::test]
+    async fn test_rule_filter() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let notifier = build_notifier(&NotifierSettings {
+            kind: NotifierKind::Webhook {
+                url: server.uri(),
+                headers: BTreeMap::new(),
+            },
+            rules: vec!["toall".to_string()],
+        })
+        .unwrap();
+        notifier.notify(&sample_event()).await.unwrap();
+    }
+}
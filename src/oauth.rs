@@ -0,0 +1,772 @@
+//! OAuth 2.0認証モジュール
+//!
+//! このモジュールは、Chatwork OAuth 2.0の認可コードフロー（PKCE、ループバックアドレスへのリダイレクト）で
+//! トークンを取得し、アクセストークンの期限が近づくとリフレッシュトークンで更新して
+//! ファイルに保存する機能を提供します。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use rand::RngCore;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::secret::{scrub, Secret};
+use crate::settings::OAuthSettings;
+use crate::t;
+use crate::utils::escape_html;
+
+/// 認可エンドポイント（ユーザーがアクセスを許可する画面）のURLです。
+const AUTHORIZATION_ENDPOINT: &str = "https://www.chatwork.com/packages/oauth2/login.php";
+/// トークンエンドポイントのURLです。
+const TOKEN_ENDPOINT: &str = "https://oauth.chatwork.com/token";
+/// 有効期限のこの時間前になったら、アクセストークンを更新します。
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+/// ブラウザでの認可を待つ時間です。
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// PKCE（RFC 7636）のコードベリファイアとコードチャレンジです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkce {
+    /// トークンの取得時に送るコードベリファイア
+    pub verifier: String,
+    /// 認可リクエストで送るコードチャレンジ（`S256`）
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// ランダムなコードベリファイアから`Pkce`を作成します。
+    pub fn new() -> Self {
+        Self::from_verifier(random_string())
+    }
+
+    /// 指定されたコードベリファイアから`Pkce`を作成します。
+    ///
+    /// ```
+    /// use chatwork_auto_read::oauth::Pkce;
+    ///
+    /// let pkce = Pkce::from_verifier("verifier".to_string());
+    /// assert_eq!(pkce.challenge, "iMnq5o6zALKXGivsnlom_0F5_WYda32GHkxlV7mq7hQ");
+    /// ```
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 32バイトの乱数をBase64URL（パディングなし）で表した文字列を返します。
+fn random_string() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// ユーザーがアクセスを許可する画面のURLを返します。
+///
+/// # 引数
+///
+/// * `settings` - OAuthの設定
+/// * `state` - リダイレクトがこの認可リクエストによるものかを確認するための値
+/// * `pkce` - コードチャレンジ
+pub fn authorization_url(settings: &OAuthSettings, state: &str, pkce: &Pkce) -> String {
+    Url::parse_with_params(
+        AUTHORIZATION_ENDPOINT,
+        [
+            ("response_type", "code"),
+            ("client_id", settings.client_id.as_str()),
+            ("redirect_uri", settings.redirect_uri().as_str()),
+            ("scope", settings.scope.as_str()),
+            ("state", state),
+            ("code_challenge", pkce.challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .expect("認可エンドポイントのURLが不正です")
+    .to_string()
+}
+
+/// OAuthで取得したトークンです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthToken {
+    /// APIリクエストに使用するアクセストークン
+    pub access_token: Secret,
+    /// アクセストークンの更新に使用するリフレッシュトークン
+    pub refresh_token: Secret,
+    /// アクセストークンの有効期限
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OAuthToken {
+    /// アクセストークンの有効期限が切れているか、まもなく切れるかどうかを返します。
+    pub fn expires_soon(&self, now: DateTime<Utc>) -> bool {
+        let margin = chrono::Duration::from_std(REFRESH_MARGIN).unwrap_or_default();
+        self.expires_at - margin <= now
+    }
+}
+
+/// トークンを保存するファイルの形式です。
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    access_token: String,
+    refresh_token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// ファイルに保存したトークンを読み込みます。
+///
+/// ファイルが存在しない場合は`None`を返します。
+///
+/// # エラー
+///
+/// ファイルを読み込めない場合や、ファイルの内容が正しくない場合に`Error`を返します。
+pub fn load_token(path: &Path) -> Result<Option<OAuthToken>, Error> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(read_failed(path, e)),
+    };
+    let stored: StoredToken = serde_json::from_str(&content).map_err(|e| read_failed(path, e))?;
+    Ok(Some(OAuthToken {
+        access_token: stored.access_token.into(),
+        refresh_token: stored.refresh_token.into(),
+        expires_at: stored.expires_at,
+    }))
+}
+
+/// トークンをファイルに保存します。
+///
+/// 書き込み中に中断してもトークンを失わないよう、一時ファイルに書き込んでから置き換えます。
+/// Unixでは、所有者のみが読み書きできるパーミッション（`0600`）で作成します。
+///
+/// # エラー
+///
+/// ディレクトリの作成やファイルの書き込みに失敗した場合に`Error`を返します。
+pub fn save_token(path: &Path, token: &OAuthToken) -> Result<(), Error> {
+    let stored = StoredToken {
+        access_token: token.access_token.expose().to_string(),
+        refresh_token: token.refresh_token.expose().to_string(),
+        expires_at: token.expires_at,
+    };
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_path)?;
+        std::io::Write::write_all(&mut file, serde_json::to_string(&stored)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+    write().map_err(|e| {
+        Error::OAuthError(t!(
+            "oauth.token_write_failed",
+            path = path.display(),
+            error = e
+        ))
+    })
+}
+
+/// トークンのファイルを読み込めない場合のエラーを作成します。
+fn read_failed(path: &Path, error: impl std::fmt::Display) -> Error {
+    Error::OAuthError(t!(
+        "oauth.token_read_failed",
+        path = path.display(),
+        error = error
+    ))
+}
+
+/// トークンエンドポイントのレスポンスです。
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// 更新のたびに新しいリフレッシュトークンが発行されます
+    #[serde(default)]
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+/// トークンエンドポイントのエラーレスポンスです。
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// トークンエンドポイントにトークンを要求します。
+///
+/// クライアントシークレットが設定されている場合はBasic認証で、
+/// 設定されていない場合（publicクライアント）は`client_id`をパラメータに含めて送信します。
+///
+/// # 引数
+///
+/// * `previous_refresh_token` - レスポンスに新しいリフレッシュトークンがない場合に引き続き使用するリフレッシュトークン
+async fn request_token(
+    client: &Client,
+    endpoint: &str,
+    settings: &OAuthSettings,
+    params: &[(&str, &str)],
+    previous_refresh_token: Option<&Secret>,
+) -> Result<OAuthToken, Error> {
+    let mut request = client.post(endpoint);
+    let mut form = params.to_vec();
+    match &settings.client_secret {
+        Some(secret) => request = request.basic_auth(&settings.client_id, Some(secret.expose())),
+        None => form.push(("client_id", &settings.client_id)),
+    }
+    let response = request.form(&form).send().await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        let error = serde_json::from_str::<TokenErrorResponse>(&body)
+            .map(|e| match e.error_description {
+                Some(description) => format!("{} ({})", e.error, description),
+                None => e.error,
+            })
+            .unwrap_or_else(|_| status.to_string());
+        let secrets: Vec<&Secret> = settings
+            .client_secret
+            .iter()
+            .chain(previous_refresh_token)
+            .collect();
+        return Err(Error::OAuthError(t!(
+            "oauth.token_request_failed",
+            status = status,
+            error = scrub(&error, &secrets)
+        )));
+    }
+
+    let response: TokenResponse = serde_json::from_str(&body)?;
+    let refresh_token = match (response.refresh_token, previous_refresh_token) {
+        (Some(token), _) => token.into(),
+        (None, Some(previous)) => previous.clone(),
+        (None, None) => Secret::default(),
+    };
+    Ok(OAuthToken {
+        access_token: response.access_token.into(),
+        refresh_token,
+        expires_at: Utc::now() + chrono::Duration::seconds(response.expires_in),
+    })
+}
+
+/// ブラウザでユーザーに認可してもらい、取得したトークンを`token_path`に保存します。
+///
+/// 認可画面のURLを標準エラー出力に表示し、ループバックアドレスへのリダイレクトで認可コードを受け取ります。
+///
+/// # エラー
+///
+/// リダイレクトを待ち受けるポートを使用できない場合、認可されなかった場合、
+/// 一定時間内に認可されなかった場合、トークンの取得や保存に失敗した場合に`Error`を返します。
+pub async fn authorize(settings: &OAuthSettings) -> Result<OAuthToken, Error> {
+    let listener = TcpListener::bind(("127.0.0.1", settings.redirect_port))
+        .await
+        .map_err(|e| {
+            Error::OAuthError(t!(
+                "oauth.listen_failed",
+                port = settings.redirect_port,
+                error = e
+            ))
+        })?;
+    let pkce = Pkce::new();
+    let state = random_string();
+
+    eprintln!(
+        "{}",
+        t!(
+            "oauth.open_url",
+            url = authorization_url(settings, &state, &pkce)
+        )
+    );
+    eprintln!(
+        "{}",
+        t!("oauth.waiting", redirect_uri = settings.redirect_uri())
+    );
+    let code = tokio::time::timeout(AUTHORIZE_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| {
+            Error::OAuthError(t!(
+                "oauth.authorize_timeout",
+                secs = AUTHORIZE_TIMEOUT.as_secs()
+            ))
+        })??;
+
+    let redirect_uri = settings.redirect_uri();
+    let token = request_token(
+        &Client::new(),
+        TOKEN_ENDPOINT,
+        settings,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("code_verifier", &pkce.verifier),
+        ],
+        None,
+    )
+    .await?;
+    save_token(&settings.token_path, &token)?;
+    Ok(token)
+}
+
+/// ループバックアドレスへのリダイレクトを待ち、認可コードを返します。
+///
+/// `/callback`以外へのリクエスト（ブラウザが要求するファビコンなど）は404を、
+/// `state`が一致しない（または含まれていない）リダイレクトは400を返して待ち続けます。
+/// 他のページから送られたリクエストで認可を中断させないよう、`state`は`error`より先に確認します。
+///
+/// # エラー
+///
+/// 認可されなかった場合や、認可コードが含まれていない場合に`Error`を返します。
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String, Error> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let Some(target) = read_request_target(&mut stream).await? else {
+            continue;
+        };
+        let url = Url::parse("http://127.0.0.1")
+            .and_then(|base| base.join(&target))
+            .ok();
+        let Some(url) = url.filter(|url| url.path() == "/callback") else {
+            respond(&mut stream, "404 Not Found", "").await;
+            continue;
+        };
+
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        if params.get("state").map(String::as_str) != Some(state) {
+            let message = t!("oauth.state_mismatch");
+            warn!("{}", message);
+            respond(&mut stream, "400 Bad Request", &message).await;
+            continue;
+        }
+        let result = if let Some(error) = params.get("error") {
+            Err(t!("oauth.denied", error = error))
+        } else if let Some(code) = params.get("code") {
+            Ok(code.clone())
+        } else {
+            Err(t!("oauth.code_missing"))
+        };
+        return match result {
+            Ok(code) => {
+                respond(&mut stream, "200 OK", &t!("oauth.callback_succeeded")).await;
+                Ok(code)
+            }
+            Err(message) => {
+                respond(&mut stream, "400 Bad Request", &message).await;
+                Err(Error::OAuthError(message))
+            }
+        };
+    }
+}
+
+/// HTTPリクエストのヘッダーを読み込み、リクエストラインのパスとクエリを返します。
+///
+/// リクエストを読み込めない場合は`None`を返します。
+async fn read_request_target(stream: &mut TcpStream) -> Result<Option<String>, Error> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") && buffer.len() < 8192 {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let request = String::from_utf8_lossy(&buffer);
+    let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+    Ok(match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    })
+}
+
+/// ブラウザに簡単なHTMLのページを返します。
+///
+/// メッセージにはクエリの値（`error`など）が含まれるため、エスケープしてから埋め込みます。
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><html lang=\"{}\"><meta charset=\"utf-8\"><p>{}</p></html>",
+        crate::i18n::language().code(),
+        escape_html(message)
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    // ブラウザへの表示に失敗しても、認可の結果には影響しません
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// 保存したトークンを使用し、必要に応じて更新するセッションです。
+///
+/// リフレッシュトークンは更新のたびに新しいものに置き換わるため、
+/// 同じトークンのファイルを使用するクライアントは1つのセッションを共有します（[`session`]を参照）。
+pub struct OAuthSession {
+    settings: OAuthSettings,
+    client: Client,
+    token_endpoint: String,
+    token: Mutex<OAuthToken>,
+}
+
+impl OAuthSession {
+    /// 新しい`OAuthSession`インスタンスを作成します。
+    ///
+    /// # 引数
+    ///
+    /// * `settings` - OAuthの設定
+    /// * `token` - 保存したトークン
+    pub fn new(settings: OAuthSettings, token: OAuthToken) -> Self {
+        Self {
+            settings,
+            client: Client::new(),
+            token_endpoint: TOKEN_ENDPOINT.to_string(),
+            token: Mutex::new(token),
+        }
+    }
+
+    /// APIリクエストに使用するアクセストークンを返します。
+    ///
+    /// 有効期限が近づいている場合は、先にリフレッシュトークンで更新します。
+    ///
+    /// # エラー
+    ///
+    /// トークンの更新や保存に失敗した場合に`Error`を返します。
+    pub async fn access_token(&self) -> Result<Secret, Error> {
+        let mut token = self.token.lock().await;
+        if token.expires_soon(Utc::now()) {
+            self.refresh(&mut token).await?;
+        }
+        Ok(token.access_token.clone())
+    }
+
+    /// APIに拒否されたアクセストークンを更新します。
+    ///
+    /// 同時に実行している他のリクエストが既に更新していた場合は、何もしません。
+    ///
+    /// # 引数
+    ///
+    /// * `rejected` - APIに拒否されたアクセストークン
+    ///
+    /// # エラー
+    ///
+    /// トークンの更新や保存に失敗した場合に`Error`を返します。
+    pub async fn refresh_rejected(&self, rejected: &Secret) -> Result<(), Error> {
+        let mut token = self.token.lock().await;
+        if token.access_token == *rejected {
+            self.refresh(&mut token).await?;
+        }
+        Ok(())
+    }
+
+    /// ログから取り除く秘密情報（アクセストークン・リフレッシュトークン・クライアントシークレット）を返します。
+    pub async fn secrets(&self) -> Vec<Secret> {
+        let token = self.token.lock().await;
+        let mut secrets = vec![token.access_token.clone(), token.refresh_token.clone()];
+        secrets.extend(self.settings.client_secret.clone());
+        secrets
+    }
+
+    /// リフレッシュトークンでアクセストークンを更新し、新しいトークンをファイルに保存します。
+    ///
+    /// 他のプロセスが先に更新してファイルに保存していた場合は、そのトークンを使用します。
+    async fn refresh(&self, token: &mut OAuthToken) -> Result<(), Error> {
+        if let Some(stored) = load_token(&self.settings.token_path)? {
+            if stored.refresh_token != token.refresh_token {
+                *token = stored;
+                if !token.expires_soon(Utc::now()) {
+                    debug!("{}", t!("oauth.token_reloaded"));
+                    return Ok(());
+                }
+            }
+        }
+
+        let refreshed = request_token(
+            &self.client,
+            &self.token_endpoint,
+            &self.settings,
+            &[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", token.refresh_token.expose()),
+            ],
+            Some(&token.refresh_token),
+        )
+        .await?;
+        save_token(&self.settings.token_path, &refreshed)?;
+        *token = refreshed;
+        info!(
+            "{}",
+            t!("oauth.token_refreshed", expires_at = token.expires_at)
+        );
+        Ok(())
+    }
+}
+
+/// 設定されたトークンのファイルを使用するセッションを返します。
+///
+/// 同じファイルを使用するセッションはプロセス内で共有するため、
+/// 既読処理とダイジェストの投稿などで別々に更新してリフレッシュトークンが無効になることはありません。
+///
+/// # エラー
+///
+/// トークンのファイルがない（`authorize`コマンドを実行していない）場合や、
+/// ファイルを読み込めない場合に`Error`を返します。
+pub fn session(settings: &OAuthSettings) -> Result<Arc<OAuthSession>, Error> {
+    static SESSIONS: OnceLock<StdMutex<HashMap<PathBuf, Arc<OAuthSession>>>> = OnceLock::new();
+    let mut sessions = SESSIONS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(session) = sessions.get(&settings.token_path) {
+        return Ok(session.clone());
+    }
+
+    let token = load_token(&settings.token_path)?.ok_or_else(|| {
+        Error::OAuthError(t!(
+            "oauth.not_authorized",
+            path = settings.token_path.display()
+        ))
+    })?;
+    let session = Arc::new(OAuthSession::new(settings.clone(), token));
+    sessions.insert(settings.token_path.clone(), session.clone());
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn oauth_settings(token_path: PathBuf, client_secret: Option<&str>) -> OAuthSettings {
+        OAuthSettings {
+            client_id: "client".to_string(),
+            client_secret: client_secret.map(Secret::from),
+            redirect_port: 0,
+            scope: "rooms.all:read_write".to_string(),
+            token_path,
+        }
+    }
+
+    fn token(access_token: &str, refresh_token: &str, expires_in: i64) -> OAuthToken {
+        OAuthToken {
+            access_token: access_token.into(),
+            refresh_token: refresh_token.into(),
+            expires_at: Utc::now() + chrono::Duration::seconds(expires_in),
+        }
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let settings = oauth_settings(PathBuf::from("token.json"), None);
+        let pkce = Pkce::from_verifier("verifier".to_string());
+        let url = Url::parse(&authorization_url(&settings, "state123", &pkce)).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], "client");
+        assert_eq!(params["redirect_uri"], "http://127.0.0.1:0/callback");
+        assert_eq!(params["state"], "state123");
+        assert_eq!(params["code_challenge"], pkce.challenge);
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        // 毎回異なるコードベリファイアを生成する
+        assert_ne!(Pkce::new(), Pkce::new());
+        assert_eq!(Pkce::new().verifier.len(), 43);
+    }
+
+    #[test]
+    fn test_save_and_load_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("oauth_token.json");
+        assert_eq!(load_token(&path).unwrap(), None);
+
+        let saved = token("access", "refresh", 1800);
+        save_token(&path, &saved).unwrap();
+        assert_eq!(load_token(&path).unwrap(), Some(saved));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load_token(&path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_code() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let browser = tokio::spawn(async move {
+            let favicon = reqwest::get(format!("{}/favicon.ico", base)).await.unwrap();
+            assert_eq!(favicon.status(), 404);
+            reqwest::get(format!("{}/callback?code=abc&state=state123", base))
+                .await
+                .unwrap()
+                .status()
+        });
+
+        assert_eq!(wait_for_code(&listener, "state123").await.unwrap(), "abc");
+        assert_eq!(browser.await.unwrap(), 200);
+
+        // stateが一致しない・含まれていないリダイレクトは400を返して待ち続ける
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let browser = tokio::spawn(async move {
+            let mut statuses = Vec::new();
+            for query in [
+                "code=evil&state=other",
+                "error=access_denied",
+                "code=abc&state=state123",
+            ] {
+                let response = reqwest::get(format!("{}/callback?{}", base, query))
+                    .await
+                    .unwrap();
+                statuses.push(response.status());
+            }
+            statuses
+        });
+        assert_eq!(wait_for_code(&listener, "state123").await.unwrap(), "abc");
+        assert_eq!(browser.await.unwrap(), vec![400, 400, 200]);
+
+        // クエリの値はエスケープしてからページに表示する
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let browser = tokio::spawn(async move {
+            reqwest::get(format!(
+                "{}/callback?error=%3Cscript%3Ealert(1)%3C/script%3E&state=state123",
+                base
+            ))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+        });
+        assert!(wait_for_code(&listener, "state123").await.is_err());
+        let page = browser.await.unwrap();
+        assert!(!page.contains("<script>"), "{}", page);
+        assert!(
+            page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"),
+            "{}",
+            page
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_and_persists_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(header_exists("authorization"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains("refresh_token=old_refresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "new_access",
+                "token_type": "Bearer",
+                "expires_in": 1800,
+                "refresh_token": "new_refresh",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("oauth_token.json");
+        let mut session = OAuthSession::new(
+            oauth_settings(path.clone(), Some("client_secret")),
+            token("old_access", "old_refresh", 10),
+        );
+        session.token_endpoint = format!("{}/token", server.uri());
+
+        // 期限が近いため更新し、新しいリフレッシュトークンを保存する
+        assert_eq!(session.access_token().await.unwrap().expose(), "new_access");
+        let stored = load_token(&path).unwrap().unwrap();
+        assert_eq!(stored.refresh_token.expose(), "new_refresh");
+
+        // 有効期限内は更新しない
+        assert_eq!(session.access_token().await.unwrap().expose(), "new_access");
+        // 既に更新済みのアクセストークンが拒否されたとしても、二重には更新しない
+        session
+            .refresh_rejected(&Secret::from("old_access"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_uses_token_saved_by_other_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("oauth_token.json");
+        save_token(&path, &token("other_access", "other_refresh", 1800)).unwrap();
+
+        // トークンエンドポイントに接続できなくても、保存されたトークンを使用できる
+        let mut session = OAuthSession::new(
+            oauth_settings(path, None),
+            token("old_access", "old_refresh", 10),
+        );
+        session.token_endpoint = "http://127.0.0.1:1/token".to_string();
+        assert_eq!(
+            session.access_token().await.unwrap().expose(),
+            "other_access"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_failure_hides_secrets() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "error": "invalid_grant",
+                "error_description": "refresh token old_refresh is expired",
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = OAuthSession::new(
+            oauth_settings(dir.path().join("oauth_token.json"), None),
+            token("old_access", "old_refresh", 0),
+        );
+        session.token_endpoint = format!("{}/token", server.uri());
+
+        let error = session.access_token().await.unwrap_err().to_string();
+        assert!(error.contains("invalid_grant"), "{}", error);
+        assert!(!error.contains("old_refresh"), "{}", error);
+    }
+}
@@ -1,15 +1,41 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::{OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::archive::Archive;
 use crate::client::ChatworkClientTrait;
 use crate::error::Error;
-use crate::models::{Message, Room};
-use crate::settings::Settings;
+use crate::events::{EventSink, RunEvent};
+use crate::journal::{new_run_id, Journal, JournalAction, JournalEntry};
+use crate::metrics::metrics;
+use crate::models::{File, Message, Room};
+use crate::notifier::{BlockedEvent, NotifiedKeys, Notifier};
+use crate::report::{BlockReason, RoomOutcome, RoomReport, RunReport, SkipReason};
+use crate::settings::{Settings, ToallPolicy};
+use crate::t;
+use chrono::Utc;
 use log::{info, warn};
+use regex::Regex;
+use tracing::{info_span, Instrument};
 
 /// Chatworkのメッセージを処理するための構造体です。
 pub struct MessageProcessor<T: ChatworkClientTrait> {
     client: T,
     settings: Settings,
+    /// 自分に割り当てられた未完了タスクのメッセージIDのセット（実行ごとに更新）
+    task_message_ids: RwLock<HashSet<String>>,
+    /// 既読位置の移動を記録するジャーナル
+    journal: Option<Journal>,
+    /// 既読にしたメッセージを保存するアーカイブ
+    archive: Option<Archive>,
+    /// 既読を止めたときの通知先
+    notifiers: Vec<Box<dyn Notifier>>,
+    /// 通知済みのメッセージやルームを表すキー（同じ内容を繰り返し通知しないために使用）
+    notified: RwLock<NotifiedKeys>,
+    /// 実行イベントの送信先
+    event_sinks: Vec<Box<dyn EventSink>>,
+    /// 処理するアカウントの名前（ジャーナルとログに記録）
+    account: Option<String>,
 }
 
 impl<T: ChatworkClientTrait> MessageProcessor<T> {
@@ -20,75 +46,357 @@ impl<T: ChatworkClientTrait> MessageProcessor<T> {
     /// * `client` - Chatwork APIクライアントの実装
     /// * `settings` - アプリケーション設定
     pub fn new(client: T, settings: Settings) -> Self {
-        Self { client, settings }
+        Self {
+            client,
+            settings,
+            task_message_ids: RwLock::new(HashSet::new()),
+            journal: None,
+            archive: None,
+            notifiers: Vec::new(),
+            notified: RwLock::new(NotifiedKeys::default()),
+            event_sinks: Vec::new(),
+            account: None,
+        }
+    }
+
+    /// 処理するアカウントの名前を設定します。
+    ///
+    /// 設定した名前は、ジャーナルの記録と実行のスパンに含まれます。
+    ///
+    /// # 引数
+    ///
+    /// * `account` - `[[accounts]]`に記述されたアカウントの名前
+    pub fn with_account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// 処理するアカウントの名前を返します。
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// 既読位置の移動を記録するジャーナルを設定します。
+    ///
+    /// # 引数
+    ///
+    /// * `journal` - 記録先のジャーナル
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// 既読にしたメッセージを保存するアーカイブを設定します。
+    ///
+    /// # 引数
+    ///
+    /// * `archive` - 保存先のアーカイブ
+    pub fn with_archive(mut self, archive: Archive) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// 既読を止めたときの通知先を追加します。
+    ///
+    /// # 引数
+    ///
+    /// * `notifier` - 追加する通知先
+    pub fn with_notifier(mut self, notifier: Box<dyn Notifier>) -> Self {
+        self.notifiers.push(notifier);
+        self
+    }
+
+    /// 通知済みのキーを設定します。
+    ///
+    /// ファイルから読み込んだキーを設定すると、実行をまたいで同じ内容を繰り返し通知しません。
+    ///
+    /// # 引数
+    ///
+    /// * `keys` - 通知済みのキー
+    pub fn with_notified_keys(mut self, keys: NotifiedKeys) -> Self {
+        self.notified = RwLock::new(keys);
+        self
+    }
+
+    /// 実行イベントの送信先を追加します。
+    ///
+    /// # 引数
+    ///
+    /// * `sink` - 追加する送信先
+    pub fn with_event_sink(mut self, sink: Box<dyn EventSink>) -> Self {
+        self.event_sinks.push(sink);
+        self
     }
 
     /// 全てのルームのメッセージを処理します。
     ///
+    /// # 戻り値
+    ///
+    /// 各ルームの処理結果をまとめた`RunReport`を返します。
+    ///
     /// # エラー
     ///
-    /// APIリクエストが失敗した場合、`Error`を返します。
-    pub async fn process_all_rooms(&self) -> Result<(), Error> {
-        let rooms = self.client.fetch_rooms().await?;
-        info!("処理対象のルームが{}個見つかりました", rooms.len());
+    /// ルーム一覧の取得に失敗した場合、`Error`を返します。
+    /// 個々のルームの処理の失敗は`RunReport`に記録されます。
+    pub async fn process_all_rooms(&self) -> Result<RunReport, Error> {
+        self.run(None).await
+    }
+
+    /// 指定されたルームのメッセージのみを処理します。
+    ///
+    /// ルーム一覧を取得する代わりに、指定されたルームの情報を個別に取得します。
+    /// スキップの判定などは`process_all_rooms`と同じです。
+    ///
+    /// # 引数
+    ///
+    /// * `room_ids` - 処理対象のルームのID
+    ///
+    /// # 戻り値
+    ///
+    /// 各ルームの処理結果をまとめた`RunReport`を返します。
+    ///
+    /// # エラー
+    ///
+    /// タスクの取得に失敗した場合、`Error`を返します。
+    /// ルームの情報の取得や処理の失敗は`RunReport`に記録されます。
+    pub async fn process_rooms_by_id(&self, room_ids: &[i32]) -> Result<RunReport, Error> {
+        self.run(Some(room_ids)).await
+    }
+
+    /// 実行の開始と終了のイベントを送信しながら、ルームのメッセージを処理します。
+    ///
+    /// # 引数
+    ///
+    /// * `room_ids` - 処理対象のルームのID（`None`の場合は全てのルーム）
+    async fn run(&self, room_ids: Option<&[i32]>) -> Result<RunReport, Error> {
+        let run_id = new_run_id();
+        self.emit(&RunEvent::run_started(&run_id)).await;
+
+        let result = self
+            .process_rooms(&run_id, room_ids)
+            .instrument(info_span!("run", run_id = %run_id, account = self.account.as_deref()))
+            .await;
+        let finished = match &result {
+            Ok(report) => RunEvent::run_finished(&run_id, report, None),
+            Err(e) => RunEvent::run_finished(&run_id, &RunReport::default(), Some(e.to_string())),
+        };
+        self.emit(&finished).await;
+        result
+    }
+
+    /// ルームの情報を取得し、各ルームのメッセージを処理します。
+    ///
+    /// # 引数
+    ///
+    /// * `run_id` - ジャーナルやイベントに記録する実行の識別子
+    /// * `room_ids` - 処理対象のルームのID（`None`の場合はルーム一覧を取得して全てのルーム）
+    async fn process_rooms(
+        &self,
+        run_id: &str,
+        room_ids: Option<&[i32]>,
+    ) -> Result<RunReport, Error> {
+        let mut report = RunReport::default();
+        let rooms = match room_ids {
+            None => self.client.fetch_rooms().await?,
+            Some(room_ids) => {
+                let mut rooms = Vec::with_capacity(room_ids.len());
+                for &room_id in room_ids {
+                    match self.client.fetch_room(room_id).await {
+                        Ok(room) => rooms.push(room),
+                        Err(e) => {
+                            warn!(
+                                "{}",
+                                t!(
+                                    "processor.fetch_room_failed",
+                                    room_id = room_id,
+                                    error = format!("{:?}", e)
+                                )
+                            );
+                            report.push(room_id, "", RoomOutcome::Failed(e.to_string()));
+                            if let Some(room_report) = report.rooms.last() {
+                                self.finish_room(run_id, room_report).await;
+                            }
+                        }
+                    }
+                }
+                rooms
+            }
+        };
+        info!("{}", t!("processor.rooms_found", count = rooms.len()));
+
+        // タスクを取得できない場合も実行は続け、自分のタスクがあるルームのみ失敗として記録する
+        let mut tasks_error = None;
+        if self.settings.chatwork.protect_task_messages {
+            let task_message_ids = match self.client.fetch_my_tasks().await {
+                Ok(tasks) => {
+                    info!("{}", t!("processor.open_tasks_found", count = tasks.len()));
+                    tasks.into_iter().map(|task| task.message_id).collect()
+                }
+                Err(e) => {
+                    warn!(
+                        "{}",
+                        t!(
+                            "processor.fetch_my_tasks_failed",
+                            error = format!("{:?}", e)
+                        )
+                    );
+                    tasks_error = Some(e.to_string());
+                    HashSet::new()
+                }
+            };
+            *self
+                .task_message_ids
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = task_message_ids;
+        }
 
         for (index, room) in rooms.iter().enumerate() {
             info!(
-                "ルームを処理中: {} / {} (ID: {})",
-                index + 1,
-                rooms.len(),
-                room.room_id
+                "{}",
+                t!(
+                    "processor.processing_room",
+                    index = index + 1,
+                    total = rooms.len(),
+                    room_id = room.room_id
+                )
+            );
+            let (outcome, messages) = match &tasks_error {
+                // どのメッセージが自分のタスクか判定できないため、自分のタスクがあるルームは既読にしない
+                Some(error)
+                    if room.mytask_num > 0 && !self.settings.chatwork.skip_rooms_with_my_tasks =>
+                {
+                    (RoomOutcome::Failed(error.clone()), Vec::new())
+                }
+                _ => {
+                    self.handle_room(room, run_id)
+                        .instrument(
+                            info_span!("room", room_id = room.room_id, room_name = %room.name),
+                        )
+                        .await
+                }
+            };
+            report.push_with_messages(room.room_id, &room.name, outcome, messages);
+            if let Some(room_report) = report.rooms.last() {
+                self.finish_room(run_id, room_report).await;
+            }
+        }
+        info!("{}", t!("processor.all_rooms_done", report = report));
+        Ok(report)
+    }
+
+    /// スキップの判定から既読までの、1つのルームの処理を行います。
+    ///
+    /// # 引数
+    ///
+    /// * `room` - 処理対象のルーム
+    /// * `run_id` - ジャーナルに記録する実行の識別子
+    ///
+    /// # 戻り値
+    ///
+    /// ルームの処理結果と、既読にした未読メッセージを返します。処理の失敗も処理結果として返します。
+    async fn handle_room(&self, room: &Room, run_id: &str) -> (RoomOutcome, Vec<Message>) {
+        let skip = self.should_skip_room(room);
+        let mention_key = format!("{}:mention", room.room_id);
+        if skip == Some(SkipReason::Mention) {
+            self.notify_blocked(
+                mention_key,
+                BlockedEvent::for_room(room, &SkipReason::Mention),
+            )
+            .await;
+        } else {
+            // メンションが解消されたら、次のメンションで再び通知する
+            if let Err(e) = self
+                .notified
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&mention_key)
+            {
+                warn!("{}", t!("processor.notified_save_failed", error = e));
+            }
+        }
+        if let Some(reason) = skip {
+            info!(
+                "{}",
+                t!(
+                    "processor.room_skipped",
+                    room_id = room.room_id,
+                    reason = reason
+                )
             );
-            if self.should_skip_room(room) {
-                continue;
+            return (RoomOutcome::Skipped(reason), Vec::new());
+        }
+
+        match self.process_room(room, run_id).await {
+            Ok(result) => {
+                info!("{}", t!("processor.room_succeeded", room_id = room.room_id));
+                result
             }
-            if let Err(e) = self.process_room(room).await {
-                warn!("ルーム{}の処理に失敗しました: {:?}", room.room_id, e);
-            } else {
-                info!("ルーム{}の処理が成功しました", room.room_id);
+            Err(e) => {
+                warn!(
+                    "{}",
+                    t!(
+                        "processor.room_failed",
+                        room_id = room.room_id,
+                        error = format!("{:?}", e)
+                    )
+                );
+                (RoomOutcome::Failed(e.to_string()), Vec::new())
             }
         }
-        info!("全てのルームの処理が完了しました");
-        Ok(())
     }
 
     /// 指定されたルームをスキップすべきかどうかを判断します。
     ///
+    /// 判定は以下の優先順位で行われます：
+    /// 1. 除外リスト（`exclude_room_ids` / `exclude_room_patterns`）に一致するルームはスキップ
+    /// 2. インクルードモードの場合、対象リスト（`include_room_ids` / `include_room_patterns`）に
+    ///    一致しないルームはスキップ
+    /// 3. 未読メッセージがない、または未読数が`min_unread_num`未満のルームはスキップ
+    /// 4. メンションを含むルームはスキップ（`read_until_mention`が有効な場合を除く）
+    /// 5. `skip_rooms_with_my_tasks`が有効な場合、自分宛ての未完了タスクがあるルームはスキップ
+    ///
     /// # 引数
     ///
     /// * `room` - 判断対象のルーム
     ///
     /// # 戻り値
     ///
-    /// ルームをスキップすべき場合は`true`、そうでない場合は`false`を返します。
-    fn should_skip_room(&self, room: &Room) -> bool {
-        if self
-            .settings
-            .chatwork
-            .exclude_room_ids
-            .contains(&room.room_id)
+    /// ルームをスキップすべき場合はその理由を`Some`で、そうでない場合は`None`を返します。
+    fn should_skip_room(&self, room: &Room) -> Option<SkipReason> {
+        let chatwork = &self.settings.chatwork;
+        if chatwork.exclude_room_ids.contains(&room.room_id)
+            || chatwork
+                .exclude_room_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(&room.name))
         {
-            info!(
-                "ルーム{}をスキップします: スキップリストに含まれています",
-                room.room_id
-            );
-            return true;
+            return Some(SkipReason::Excluded);
+        }
+        if chatwork.is_include_mode()
+            && !chatwork.include_room_ids.contains(&room.room_id)
+            && !chatwork
+                .include_room_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(&room.name))
+        {
+            return Some(SkipReason::NotIncluded);
         }
         if room.unread_num == 0 {
-            info!(
-                "ルーム{}をスキップします: 未読メッセージがありません",
-                room.room_id
-            );
-            return true;
+            return Some(SkipReason::NoUnread);
         }
-        if room.mention_num > 0 {
-            info!(
-                "ルーム{}をスキップします: メンションが含まれています",
-                room.room_id
-            );
-            return true;
+        if room.unread_num < chatwork.min_unread_num {
+            return Some(SkipReason::BelowMinUnread {
+                unread: room.unread_num,
+                min: chatwork.min_unread_num,
+            });
         }
-        false
+        if room.mention_num > 0 && !chatwork.read_until_mention {
+            return Some(SkipReason::Mention);
+        }
+        if room.mytask_num > 0 && chatwork.skip_rooms_with_my_tasks {
+            return Some(SkipReason::OpenTasks(room.mytask_num));
+        }
+        None
     }
 
     /// 指定されたルームのメッセージを処理します。
@@ -96,31 +404,260 @@ impl<T: ChatworkClientTrait> MessageProcessor<T> {
     /// # 引数
     ///
     /// * `room` - 処理対象のルーム
+    /// * `run_id` - ジャーナルに記録する実行の識別子
+    ///
+    /// # 戻り値
+    ///
+    /// ルームの処理結果と、既読にした未読メッセージを返します。
     ///
     /// # エラー
     ///
     /// APIリクエストが失敗した場合、`Error`を返します。
-    async fn process_room(&self, room: &Room) -> Result<(), Error> {
+    async fn process_room(
+        &self,
+        room: &Room,
+        run_id: &str,
+    ) -> Result<(RoomOutcome, Vec<Message>), Error> {
         let messages = self.client.fetch_messages(room.room_id).await?;
+        self.prune_notified_keys(room, &messages);
+        // 添付ファイルを保護する場合、最初の未読メッセージを取得できていないと、取得していないメッセージに
+        // 添付ファイルが含まれていても判定できないため、既読にしない。
+        // 保護しない場合は、従来どおり取得したメッセージの範囲で判定する
+        if self.settings.chatwork.attachments.protect
+            && room.unread_num.max(0) as usize > messages.len()
+        {
+            return Ok((
+                RoomOutcome::Skipped(SkipReason::UnreadNotFetched {
+                    unread: room.unread_num,
+                    fetched: messages.len(),
+                }),
+                Vec::new(),
+            ));
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+
+        // 未読メッセージは取得したメッセージの末尾にあるものとして扱う。
+        // `force=1`では既読のメッセージも取得されるため、保護対象の判定は未読メッセージのみで行い、
+        // 見つかったインデックスは`messages`での位置に戻す
+        let unread_start = messages
+            .len()
+            .saturating_sub(room.unread_num.max(0) as usize);
+        let unread = &messages[unread_start..];
+
+        // メンションを含むルームでは、未読メッセージの中でメンションの位置を特定できた場合のみ、
+        // その直前まで既読にする
+        if room.mention_num > 0
+            && !unread
+                .iter()
+                .any(|message| self.mentioned_account(message).is_some())
+        {
+            return Ok((
+                RoomOutcome::Skipped(SkipReason::MentionNotFound),
+                Vec::new(),
+            ));
+        }
+
+        // 保護対象の添付ファイルを含むメッセージより前のメッセージのみを対象にする
+        let attachment_block = self
+            .find_protected_attachment(room, unread)
+            .await?
+            .map(|(index, reason)| (unread_start + index, reason));
+        let limit = match &attachment_block {
+            Some((index, reason)) => {
+                info!(
+                    "{}",
+                    t!(
+                        "processor.blocked_at",
+                        message_id = messages[*index].message_id,
+                        reason = reason
+                    )
+                );
+                *index
+            }
+            None => messages.len(),
+        };
+
+        // 既読を止めた場合は通知する
+        let block = self
+            .find_blocking_message(room, &messages[unread_start..limit])
+            .map(|(index, reason)| (unread_start + index, reason))
+            .or(attachment_block);
+        if let Some((index, reason)) = &block {
+            let message = &messages[*index];
+            self.notify_blocked(
+                format!("{}:{}", room.room_id, message.message_id),
+                BlockedEvent::for_message(room, message, reason),
+            )
+            .await;
+        }
+
+        let Some(target_message) =
+            self.find_target_message(room, &messages[unread_start..limit], now)
+        else {
+            return Ok((RoomOutcome::NothingToRead, Vec::new()));
+        };
+
+        let target_index = messages
+            .iter()
+            .position(|message| message.message_id == target_message.message_id)
+            .unwrap_or_default();
+        let consumed = (target_index + 1).saturating_sub(unread_start);
+
+        let read_status = self
+            .client
+            .mark_message_as_read(room.room_id, &target_message.message_id)
+            .await?;
+
+        let read_messages = &messages[unread_start..=target_index];
+        if let Some(archive) = &self.archive {
+            if let Err(e) = archive.store(room, read_messages) {
+                warn!("{}", t!("processor.archive_failed", error = e));
+            }
+        }
+
+        if let Some(journal) = &self.journal {
+            // 既読を止めたルールを記録する
+            let boundary = block.as_ref().map_or(messages.len(), |(index, _)| *index);
+            let mut rules: Vec<String> = block
+                .iter()
+                .map(|(_, reason)| reason.key().to_string())
+                .collect();
+            if target_index + 1 < boundary {
+                rules.push("min_message_age".to_string());
+            }
+
+            let entry = JournalEntry {
+                run_id: run_id.to_string(),
+                timestamp: Utc::now(),
+                action: JournalAction::Read,
+                room_id: room.room_id,
+                previous_boundary: messages
+                    .get(unread_start)
+                    .map(|message| message.message_id.clone()),
+                new_boundary: target_message.message_id.clone(),
+                consumed,
+                rules,
+                read_status: Some(read_status),
+                account: self.account.clone(),
+            };
+            if let Err(e) = journal.append(&entry) {
+                warn!("{}", t!("journal.write_failed", error = e));
+            }
+        }
+
+        Ok((
+            RoomOutcome::Read {
+                message_id: target_message.message_id.clone(),
+                consumed,
+            },
+            read_messages.to_vec(),
+        ))
+    }
 
-        if let Some(target_message) = self.find_target_message(&messages) {
-            self.client
-                .mark_message_as_read(room.room_id, &target_message.message_id)
-                .await?;
+    /// ルームの処理結果をメトリクスに記録し、実行イベントとして送信します。
+    async fn finish_room(&self, run_id: &str, room: &RoomReport) {
+        metrics().record_room(self.account.as_deref(), room);
+        self.emit(&RunEvent::room(run_id, room)).await;
+    }
+
+    /// 実行イベントを全ての送信先に送信します。
+    async fn emit(&self, event: &RunEvent) {
+        for sink in &self.event_sinks {
+            sink.emit(event).await;
+        }
+    }
+
+    /// 送信中の実行イベントを全ての送信先が送信し終えるまで待ちます。
+    ///
+    /// 実行イベントはバックグラウンドで送信されるため、プロセスを終了する前に呼び出します。
+    pub async fn flush_events(&self) {
+        for sink in &self.event_sinks {
+            sink.flush().await;
+        }
+    }
+
+    /// 既読を止めたことを全ての通知先に通知します。
+    ///
+    /// 同じキーについては、いずれかの通知先への通知に成功するまで通知を繰り返します。
+    /// 通知の失敗はログに記録するのみで、処理は継続します。
+    ///
+    /// # 引数
+    ///
+    /// * `key` - 通知済みかどうかの判定に使用するキー
+    /// * `event` - 通知するイベント
+    async fn notify_blocked(&self, key: String, event: BlockedEvent) {
+        if self.notifiers.is_empty()
+            || self
+                .notified
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(&key)
+        {
+            return;
+        }
+
+        info!(
+            "{}",
+            t!("processor.notifying_blocked", subject = event.subject())
+        );
+        let mut delivered = false;
+        for notifier in &self.notifiers {
+            match notifier.notify(&event).await {
+                Ok(()) => delivered = true,
+                Err(e) => warn!("{}", t!("processor.notify_failed", error = e)),
+            }
+        }
+        // 全ての通知先に失敗した場合は、次の実行で再び通知する
+        if delivered {
+            if let Err(e) = self
+                .notified
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key)
+            {
+                warn!("{}", t!("processor.notified_save_failed", error = e));
+            }
         }
+    }
 
-        Ok(())
+    /// 取得したメッセージの範囲から外れたメッセージの通知済みのキーを取り除きます。
+    ///
+    /// 範囲から外れたメッセージで既読を止めることはないため、再び通知することはありません。
+    /// 保存の失敗はログに記録するのみで、処理は継続します。
+    fn prune_notified_keys(&self, room: &Room, messages: &[Message]) {
+        let prefix = format!("{}:", room.room_id);
+        let message_ids: HashSet<&str> = messages
+            .iter()
+            .map(|message| message.message_id.as_str())
+            .collect();
+        if let Err(e) = self
+            .notified
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|key| match key.strip_prefix(&prefix) {
+                Some(id) => id == "mention" || message_ids.contains(id),
+                None => true,
+            })
+        {
+            warn!("{}", t!("processor.notified_save_failed", error = e));
+        }
     }
 
     /// メッセージのリストから対象のメッセージを見つけます。
     ///
-    /// この関数は、与えられたメッセージのリストを逆順に走査し、
-    /// 除外すべきメッセージの直後のメッセージを返します。
-    /// 除外すべきメッセージが見つからない場合、最初のメッセージを返します。
+    /// メッセージのリストは古い順に並んでいるものとし、
+    /// 最初に現れる除外すべきメッセージより前にあり、かつ既読までの猶予時間を
+    /// 経過したメッセージのうち、最も新しいものを返します。
+    /// 除外すべきメッセージが見つからない場合、猶予時間を経過した最新のメッセージを返します。
     ///
     /// # 引数
     ///
+    /// * `room` - メッセージが属するルーム（ルームごとの個別設定の判定に使用）
     /// * `messages` - 検索対象のメッセージのスライス
+    /// * `now` - 現在時刻（UNIX時間、秒）
     ///
     /// # 戻り値
     ///
@@ -128,90 +665,257 @@ impl<T: ChatworkClientTrait> MessageProcessor<T> {
     ///
     /// # 例
     ///
-    /// ```
+    /// ```ignore
     /// let processor = MessageProcessor::new();
     /// let messages = vec![/* メッセージのリスト */];
-    /// let target_message = processor.find_target_message(&messages);
+    /// let target_message = processor.find_target_message(&room, &messages, now);
     /// ```
-    fn find_target_message<'a>(&self, messages: &'a [Message]) -> Option<&'a Message> {
+    fn find_target_message<'a>(
+        &self,
+        room: &Room,
+        messages: &'a [Message],
+        now: i64,
+    ) -> Option<&'a Message> {
         info!(
-            "{}個のメッセージから対象のメッセージを検索中",
-            messages.len()
+            "{}",
+            t!("processor.searching_target", count = messages.len())
         );
 
-        let exclude_account_ids: HashSet<&String> =
-            self.settings.chatwork.exclude_account_ids.iter().collect();
+        let boundary = match self.find_blocking_message(room, messages) {
+            Some((index, reason)) => {
+                info!(
+                    "{}",
+                    t!(
+                        "processor.blocked_at",
+                        message_id = messages[index].message_id,
+                        reason = reason
+                    )
+                );
+                index
+            }
+            None => messages.len(),
+        };
 
-        let target_index = messages
+        // 非常に大きな値が設定されても桁あふれしないよう、秒への変換は飽和させる
+        let min_age_secs = i64::try_from(self.settings.chatwork.min_message_age_for(room))
+            .unwrap_or(i64::MAX)
+            .saturating_mul(60);
+        let result = messages[..boundary]
             .iter()
-            .rposition(|message| self.is_message_to_be_excluded(message, &exclude_account_ids))
-            .map(|index| index.saturating_add(1))
-            .unwrap_or(0);
-
-        let result = messages.get(target_index);
+            .rev()
+            .find(|message| min_age_secs == 0 || message.send_time <= now - min_age_secs);
         if let Some(message) = result {
             info!(
-                "対象のメッセージが見つかりました: ID {}",
-                message.message_id
+                "{}",
+                t!("processor.target_found", message_id = message.message_id)
             );
         } else {
-            warn!("対象のメッセージが見つかりませんでした");
+            warn!("{}", t!("processor.target_not_found"));
         }
         result
     }
 
-    /// 指定されたメッセージが除外対象かどうかを判断します。
+    /// 最初に現れる保護対象の添付ファイルを含むメッセージのインデックスと、その理由を返します。
+    ///
+    /// 本文に`[download:...]`または`[preview ...]`タグを含むメッセージがある場合のみ
+    /// ルームのファイル一覧を取得し、ファイル名とサイズを`attachments`の設定と照合します。
+    /// ファイル一覧に見つからない添付ファイルは、安全のため保護対象として扱います。
+    ///
+    /// # エラー
+    ///
+    /// ファイル一覧の取得に失敗した場合、`Error`を返します。
+    async fn find_protected_attachment(
+        &self,
+        room: &Room,
+        messages: &[Message],
+    ) -> Result<Option<(usize, BlockReason)>, Error> {
+        let attachments = &self.settings.chatwork.attachments;
+        if !attachments.protect {
+            return Ok(None);
+        }
+
+        let attached: Vec<(usize, Vec<i64>)> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| (index, attached_file_ids(&message.body)))
+            .filter(|(_, file_ids)| !file_ids.is_empty())
+            .collect();
+        if attached.is_empty() {
+            return Ok(None);
+        }
+
+        let files: HashMap<i64, File> = self
+            .client
+            .fetch_files(room.room_id)
+            .await?
+            .into_iter()
+            .map(|file| (file.file_id, file))
+            .collect();
+
+        for (index, file_ids) in attached {
+            for file_id in file_ids {
+                let reason = match files.get(&file_id) {
+                    Some(file) if attachments.protects(&file.filename, file.filesize) => {
+                        BlockReason::Attachment(file.filename.clone())
+                    }
+                    Some(_) => continue,
+                    None => {
+                        BlockReason::Attachment(t!("processor.unknown_file", file_id = file_id))
+                    }
+                };
+                return Ok(Some((index, reason)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 最初に現れる除外すべきメッセージのインデックスと、除外の理由を返します。
     ///
-    /// このメソッドは、メッセージが全体メンション（"toall"）を含むか、
-    /// または特定のアカウントへのメンションを含むかをチェックします。
+    /// # 引数
+    ///
+    /// * `room` - メッセージが属するルーム
+    /// * `messages` - 古い順に並んだメッセージのスライス
+    fn find_blocking_message(
+        &self,
+        room: &Room,
+        messages: &[Message],
+    ) -> Option<(usize, BlockReason)> {
+        let exclude_account_ids: HashSet<&String> =
+            self.settings.chatwork.exclude_account_ids.iter().collect();
+        let toall_policy = self.settings.chatwork.toall_policy_for(room);
+
+        messages.iter().enumerate().find_map(|(index, message)| {
+            self.exclusion_reason(message, &exclude_account_ids, toall_policy)
+                .map(|reason| (index, reason))
+        })
+    }
+
+    /// 指定されたメッセージが除外対象かどうかを判断し、除外対象の場合はその理由を返します。
+    ///
+    /// このメソッドは、メッセージが全体メンション（"toall"）を含み、かつルームの
+    /// `[toall]`ポリシーが既読を止める設定になっているか、
+    /// または特定のアカウントへのメンション（`[To:...]`）や返信（`[rp aid=...]`）を
+    /// 含むかをチェックします。`protect_task_messages`が有効な場合は、
+    /// 自分宛ての未完了タスクのメッセージや、除外対象のアカウント宛ての`[task aid=...]`を含む
+    /// メッセージも除外対象とします。
     ///
     /// # 引数
     ///
     /// * `message` - チェック対象のメッセージ
     /// * `exclude_account_ids` - 除外すべきアカウントIDのセット
+    /// * `toall_policy` - ルームに適用される`[toall]`ポリシー
     ///
     /// # 戻り値
     ///
-    /// * `bool` - メッセージが除外対象の場合は `true`、そうでない場合は `false`
+    /// * `Option<BlockReason>` - メッセージが除外対象の場合はその理由、そうでない場合は `None`
     ///
     /// # 例
     ///
-    /// ```
+    /// ```ignore
     /// let exclude_ids = HashSet::from(["123", "456"]);
     /// let message = Message { body: "[To:123] Hello".to_string(), .. };
-    /// let is_excluded = processor.is_message_to_be_excluded(&message, &exclude_ids);
-    /// assert!(is_excluded);
+    /// let reason = processor.exclusion_reason(&message, &exclude_ids, &ToallPolicy::default());
+    /// assert_eq!(reason, Some(BlockReason::Mention("123".to_string())));
     /// ```
-    fn is_message_to_be_excluded(
+    fn exclusion_reason(
         &self,
         message: &Message,
         exclude_account_ids: &HashSet<&String>,
-    ) -> bool {
-        if message.body.contains("[toall]") {
-            return true;
+        toall_policy: &ToallPolicy,
+    ) -> Option<BlockReason> {
+        if message.body.contains("[toall]")
+            && toall_policy.blocks(&message.account.account_id.to_string())
+        {
+            return Some(BlockReason::Toall);
         }
 
-        exclude_account_ids.iter().any(|&id| {
-            let mention = format!("[To:{}]", id);
-            message.body.contains(&mention)
-        })
+        if self.settings.chatwork.protect_task_messages {
+            let is_my_task = self
+                .task_message_ids
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(&message.message_id);
+            if is_my_task
+                || exclude_account_ids
+                    .iter()
+                    .any(|id| message.body.contains(&format!("[task aid={} ", id)))
+            {
+                return Some(BlockReason::Task);
+            }
+        }
+
+        if let Some(account_id) = self.mentioned_account(message) {
+            return Some(BlockReason::Mention(account_id));
+        }
+
+        let chatwork = &self.settings.chatwork;
+        if let Some(pattern) = chatwork
+            .protect_keywords
+            .iter()
+            .find(|pattern| pattern.is_match(&message.body))
+        {
+            return Some(BlockReason::Keyword(pattern.as_str().to_string()));
+        }
+
+        let sender = message.account.account_id.to_string();
+        chatwork
+            .protect_senders
+            .contains(&sender)
+            .then_some(BlockReason::ProtectedSender(sender))
+    }
+
+    /// メッセージがメンション（`[To:...]`）または返信（`[rp aid=...]`）している
+    /// 除外対象のアカウントIDを返します。
+    fn mentioned_account(&self, message: &Message) -> Option<String> {
+        self.settings
+            .chatwork
+            .exclude_account_ids
+            .iter()
+            .find(|id| {
+                let mention = format!("[To:{}]", id);
+                let reply = format!("[rp aid={} ", id);
+                message.body.contains(&mention) || message.body.contains(&reply)
+            })
+            .cloned()
     }
 }
 
+/// メッセージ本文の`[download:...]`および`[preview id=...]`タグから添付ファイルのIDを抽出します。
+fn attached_file_ids(body: &str) -> Vec<i64> {
+    static ATTACHMENT_TAG: OnceLock<Regex> = OnceLock::new();
+    let regex = ATTACHMENT_TAG.get_or_init(|| {
+        Regex::new(r"\[(?:download:|preview id=)(\d+)").expect("正規表現が不正です")
+    });
+
+    let mut file_ids: Vec<i64> = regex
+        .captures_iter(body)
+        .filter_map(|captures| captures[1].parse().ok())
+        .collect();
+    file_ids.dedup();
+    file_ids
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{client::MockChatworkClientTrait, models::ReadStatus, settings::ChatworkSettings};
+    use crate::{
+        client::MockChatworkClientTrait,
+        models::{Account, ReadStatus, Task},
+        settings::AttachmentSettings,
+        settings::{ChatworkSettings, Pattern, RoomRule, ToallAction},
+    };
     use mockall::predicate::*;
     use std::collections::HashSet;
 
     fn create_test_settings() -> Settings {
         Settings {
             chatwork: ChatworkSettings {
-                api_token: "test_token".to_string(),
+                api_token: "test_token".into(),
                 exclude_account_ids: vec!["123".to_string()],
                 exclude_room_ids: HashSet::from([999]),
+                ..Default::default()
             },
+            ..Default::default()
         }
     }
 
@@ -225,18 +929,21 @@ mod tests {
             Ok(vec![
                 Room {
                     room_id: 1,
-                    unread_num: 1,
+                    unread_num: 2,
                     mention_num: 0,
+                    ..Default::default()
                 },
                 Room {
                     room_id: 2,
                     unread_num: 0,
                     mention_num: 0,
+                    ..Default::default()
                 },
                 Room {
                     room_id: 3,
                     unread_num: 1,
                     mention_num: 1,
+                    ..Default::default()
                 },
             ])
         });
@@ -251,10 +958,12 @@ mod tests {
                     Message {
                         message_id: "1".to_string(),
                         body: "Test message".to_string(),
+                        ..Default::default()
                     },
                     Message {
                         message_id: "2".to_string(),
                         body: "[To:123] Test mention".to_string(),
+                        ..Default::default()
                     },
                 ])
             });
@@ -295,6 +1004,7 @@ mod tests {
                 room_id: 1,
                 unread_num: 0,
                 mention_num: 0,
+                ..Default::default()
             }])
         });
 
@@ -315,6 +1025,7 @@ mod tests {
                 room_id: 1,
                 unread_num: 1,
                 mention_num: 1,
+                ..Default::default()
             }])
         });
 
@@ -335,6 +1046,7 @@ mod tests {
                 room_id: 999,
                 unread_num: 1,
                 mention_num: 0,
+                ..Default::default()
             }])
         });
 
@@ -350,7 +1062,7 @@ mod tests {
         let settings = create_test_settings();
         let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
         let messages = vec![];
-        let target_message = processor.find_target_message(&messages);
+        let target_message = processor.find_target_message(&Room::default(), &messages, 0);
         assert!(target_message.is_none());
     }
 
@@ -362,13 +1074,1211 @@ mod tests {
             Message {
                 message_id: "1".to_string(),
                 body: "Test message 1".to_string(),
+                ..Default::default()
             },
             Message {
                 message_id: "2".to_string(),
                 body: "Test message 2".to_string(),
+                ..Default::default()
             },
         ];
-        let target_message = processor.find_target_message(&messages);
+        let target_message = processor.find_target_message(&Room::default(), &messages, 0);
         assert_eq!(target_message.unwrap().message_id, "2");
     }
+
+    #[test]
+    fn test_should_skip_room_include_mode() {
+        let mut settings = create_test_settings();
+        settings.chatwork.include_room_ids = HashSet::from([1, 999]);
+        settings.chatwork.include_room_patterns = vec![Pattern::new("^お知らせ").unwrap()];
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+
+        let room = |room_id: i32, name: &str| Room {
+            room_id,
+            name: name.to_string(),
+            unread_num: 1,
+            mention_num: 0,
+            ..Default::default()
+        };
+
+        // IDで対象リストに含まれるルームは処理される
+        assert_eq!(processor.should_skip_room(&room(1, "開発")), None);
+        // パターンで対象リストに一致するルームは処理される
+        assert_eq!(
+            processor.should_skip_room(&room(2, "お知らせ（全社）")),
+            None
+        );
+        // 対象リストに含まれないルームはスキップされる
+        assert_eq!(
+            processor.should_skip_room(&room(3, "開発")),
+            Some(SkipReason::NotIncluded)
+        );
+        // 除外リストは対象リストより優先される
+        assert_eq!(
+            processor.should_skip_room(&room(999, "お知らせ")),
+            Some(SkipReason::Excluded)
+        );
+    }
+
+    #[test]
+    fn test_should_skip_room_exclude_pattern() {
+        let mut settings = create_test_settings();
+        settings.chatwork.exclude_room_patterns = vec![Pattern::new("雑談").unwrap()];
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+
+        let room = Room {
+            room_id: 1,
+            name: "雑談部屋".to_string(),
+            unread_num: 1,
+            mention_num: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            processor.should_skip_room(&room),
+            Some(SkipReason::Excluded)
+        );
+    }
+
+    fn toall_message(message_id: &str, sender_id: i32) -> Message {
+        Message {
+            message_id: message_id.to_string(),
+            account: Account {
+                account_id: sender_id,
+                ..Default::default()
+            },
+            body: "[toall] お知らせです".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_target_message_with_toall_policy() {
+        let mut settings = create_test_settings();
+        settings.chatwork.room_rules = vec![
+            RoomRule {
+                room_patterns: vec![Pattern::new("全社").unwrap()],
+                toall: Some(ToallPolicy {
+                    action: ToallAction::Ignore,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            RoomRule {
+                room_ids: HashSet::from([20]),
+                toall: Some(ToallPolicy {
+                    action: ToallAction::BlockFromSenders,
+                    senders: vec!["777".to_string()],
+                }),
+                ..Default::default()
+            },
+        ];
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+        let messages = vec![
+            Message {
+                message_id: "1".to_string(),
+                body: "Test message".to_string(),
+                ..Default::default()
+            },
+            toall_message("2", 555),
+            Message {
+                message_id: "3".to_string(),
+                body: "Test message".to_string(),
+                ..Default::default()
+            },
+            toall_message("4", 777),
+        ];
+        let room = |room_id: i32, name: &str| Room {
+            room_id,
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        // デフォルトでは最初の[toall]の直前まで既読にする
+        let target = processor.find_target_message(&room(10, "チーム"), &messages, 0);
+        assert_eq!(target.unwrap().message_id, "1");
+
+        // ignoreのルームでは[toall]を無視する
+        let target = processor.find_target_message(&room(11, "全社連絡"), &messages, 0);
+        assert_eq!(target.unwrap().message_id, "4");
+
+        // block_from_sendersのルームでは指定された送信者の[toall]のみで止まる
+        let target = processor.find_target_message(&room(20, "チーム"), &messages, 0);
+        assert_eq!(target.unwrap().message_id, "3");
+    }
+
+    #[test]
+    fn test_find_target_message_with_protected_keywords_and_senders() {
+        let mut settings = create_test_settings();
+        settings.chatwork.protect_keywords = vec![Pattern::new("(?i)urgent|至急").unwrap()];
+        settings.chatwork.protect_senders = vec!["777".to_string()];
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+        let message = |message_id: &str, sender_id: i32, body: &str| Message {
+            message_id: message_id.to_string(),
+            account: Account {
+                account_id: sender_id,
+                ..Default::default()
+            },
+            body: body.to_string(),
+            ..Default::default()
+        };
+
+        let messages = vec![
+            message("1", 555, "Test message"),
+            message("2", 555, "【至急】確認してください"),
+            message("3", 555, "Test message"),
+        ];
+        let target = processor.find_target_message(&Room::default(), &messages, 0);
+        assert_eq!(target.unwrap().message_id, "1");
+
+        let messages = vec![
+            message("1", 555, "Test message"),
+            message("2", 555, "Test message"),
+            message("3", 777, "Test message"),
+        ];
+        let target = processor.find_target_message(&Room::default(), &messages, 0);
+        assert_eq!(target.unwrap().message_id, "2");
+
+        let exclude_account_ids = HashSet::new();
+        let toall_policy = ToallPolicy::default();
+        assert_eq!(
+            processor.exclusion_reason(
+                &message("4", 555, "URGENT"),
+                &exclude_account_ids,
+                &toall_policy
+            ),
+            Some(BlockReason::Keyword("(?i)urgent|至急".to_string()))
+        );
+        assert_eq!(
+            processor.exclusion_reason(
+                &message("5", 777, "Test message"),
+                &exclude_account_ids,
+                &toall_policy
+            ),
+            Some(BlockReason::ProtectedSender("777".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_find_target_message_with_min_message_age() {
+        let mut settings = create_test_settings();
+        settings.chatwork.min_message_age_minutes = 30;
+        settings.chatwork.room_rules = vec![RoomRule {
+            room_ids: HashSet::from([20]),
+            min_message_age_minutes: Some(120),
+            ..Default::default()
+        }];
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+
+        let now = 10_000;
+        let message = |message_id: &str, minutes_ago: i64, body: &str| Message {
+            message_id: message_id.to_string(),
+            body: body.to_string(),
+            send_time: now - minutes_ago * 60,
+            ..Default::default()
+        };
+        let messages = vec![
+            message("1", 150, "Test message"),
+            message("2", 60, "Test message"),
+            message("3", 10, "Test message"),
+        ];
+        let room = |room_id: i32| Room {
+            room_id,
+            ..Default::default()
+        };
+
+        // 全ルーム共通の猶予時間（30分）を経過した最新のメッセージ
+        let target = processor.find_target_message(&room(10), &messages, now);
+        assert_eq!(target.unwrap().message_id, "2");
+
+        // ルームごとの猶予時間（120分）が優先される
+        let target = processor.find_target_message(&room(20), &messages, now);
+        assert_eq!(target.unwrap().message_id, "1");
+
+        // 除外すべきメッセージより後のメッセージは対象にならない
+        let messages = vec![
+            message("1", 150, "[To:123] Test mention"),
+            message("2", 60, "Test message"),
+        ];
+        let target = processor.find_target_message(&room(10), &messages, now);
+        assert!(target.is_none());
+
+        // 秒に変換すると桁あふれする猶予時間では、どのメッセージも対象にならない
+        let mut settings = create_test_settings();
+        settings.chatwork.min_message_age_minutes = u64::MAX;
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+        let messages = vec![message("1", 150, "Test message")];
+        let target = processor.find_target_message(&room(10), &messages, now);
+        assert!(target.is_none());
+    }
+
+    #[test]
+    fn test_should_skip_room_min_unread_and_mentions() {
+        let mut settings = create_test_settings();
+        settings.chatwork.min_unread_num = 5;
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+        let room = |unread_num: i32, mention_num: i32| Room {
+            room_id: 1,
+            unread_num,
+            mention_num,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            processor.should_skip_room(&room(3, 0)),
+            Some(SkipReason::BelowMinUnread { unread: 3, min: 5 })
+        );
+        assert_eq!(processor.should_skip_room(&room(5, 0)), None);
+        assert_eq!(
+            processor.should_skip_room(&room(5, 1)),
+            Some(SkipReason::Mention)
+        );
+
+        let mut settings = create_test_settings();
+        settings.chatwork.read_until_mention = true;
+        let processor = MessageProcessor::new(MockChatworkClientTrait::new(), settings);
+        assert_eq!(processor.should_skip_room(&room(5, 1)), None);
+    }
+
+    /// 受け取ったイベントを記録するだけの通知です。
+    struct RecordingNotifier(std::sync::Arc<std::sync::Mutex<Vec<BlockedEvent>>>);
+
+    #[async_trait::async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+            self.0.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_notifies_blocked_once() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(2).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    name: "開発".to_string(),
+                    unread_num: 2,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 2,
+                    name: "雑談".to_string(),
+                    unread_num: 1,
+                    mention_num: 1,
+                    ..Default::default()
+                },
+            ])
+        });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(2)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "1".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "2".to_string(),
+                        body: "[To:123] Test mention".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("1"))
+            .times(2)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 1,
+                    mention_num: 0,
+                })
+            });
+
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_notifier(Box::new(RecordingNotifier(events.clone())));
+        processor.process_all_rooms().await.unwrap();
+        processor.process_all_rooms().await.unwrap();
+
+        // 同じメッセージやルームについては一度だけ通知する
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].room_id, 1);
+        assert_eq!(events[0].rule, "mention");
+        assert_eq!(events[0].message.as_ref().unwrap().message_id, "2");
+        assert_eq!(events[1].room_id, 2);
+        assert_eq!(events[1].rule, "mention");
+        assert!(events[1].message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_prunes_notified_keys() {
+        let temp_dir = tempfile::TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let path = temp_dir.path().join("notified.json");
+        let mut keys = NotifiedKeys::load(&path).unwrap();
+        keys.insert("1:1".to_string()).unwrap();
+        keys.insert("1:2".to_string()).unwrap();
+        keys.insert("2:10".to_string()).unwrap();
+
+        // メッセージ1は取得したメッセージの範囲から外れた
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 1,
+                ..Default::default()
+            }])
+        });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "2".to_string(),
+                    body: "[To:123] Test mention".to_string(),
+                    ..Default::default()
+                }])
+            });
+
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_notifier(Box::new(RecordingNotifier(events.clone())))
+            .with_notified_keys(keys);
+        processor.process_all_rooms().await.unwrap();
+
+        // 範囲内のメッセージのキーと、処理していないルームのキーは残る
+        assert!(events.lock().unwrap().is_empty());
+        let keys = NotifiedKeys::load(&path).unwrap();
+        assert!(!keys.contains("1:1"));
+        assert!(keys.contains("1:2"));
+        assert!(keys.contains("2:10"));
+    }
+
+    /// 最初の通知にのみ失敗し、受け取ったイベントを記録する通知です。
+    struct FlakyNotifier(std::sync::Arc<std::sync::Mutex<Vec<BlockedEvent>>>);
+
+    #[async_trait::async_trait]
+    impl Notifier for FlakyNotifier {
+        async fn notify(&self, event: &BlockedEvent) -> Result<(), Error> {
+            let mut events = self.0.lock().unwrap();
+            events.push(event.clone());
+            if events.len() == 1 {
+                return Err(Error::NotificationError("接続できません".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_retries_failed_notification() {
+        let temp_dir = tempfile::TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let path = temp_dir.path().join("notified.json");
+        let mention_room = || {
+            Ok(vec![Room {
+                room_id: 2,
+                name: "雑談".to_string(),
+                unread_num: 1,
+                mention_num: 1,
+                ..Default::default()
+            }])
+        };
+
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client
+            .expect_fetch_rooms()
+            .times(3)
+            .returning(mention_room);
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_notifier(Box::new(FlakyNotifier(events.clone())))
+            .with_notified_keys(NotifiedKeys::load(&path).unwrap());
+        processor.process_all_rooms().await.unwrap();
+        processor.process_all_rooms().await.unwrap();
+        processor.process_all_rooms().await.unwrap();
+
+        // 通知に失敗した場合は次の実行で再び通知し、成功した後は通知しない
+        assert_eq!(events.lock().unwrap().len(), 2);
+
+        // 通知済みのキーはファイルに保存され、次のプロセスに引き継がれる
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client
+            .expect_fetch_rooms()
+            .times(1)
+            .returning(mention_room);
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_notifier(Box::new(RecordingNotifier(events.clone())))
+            .with_notified_keys(NotifiedKeys::load(&path).unwrap());
+        processor.process_all_rooms().await.unwrap();
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    /// 受け取ったイベントを記録するだけの送信先です。
+    struct RecordingSink(std::sync::Arc<std::sync::Mutex<Vec<RunEvent>>>);
+
+    #[async_trait::async_trait]
+    impl EventSink for RecordingSink {
+        async fn emit(&self, event: &RunEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_emits_events() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    unread_num: 1,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 2,
+                    unread_num: 0,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 3,
+                    unread_num: 1,
+                    ..Default::default()
+                },
+            ])
+        });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "1".to_string(),
+                    body: "Test message".to_string(),
+                    ..Default::default()
+                }])
+            });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(3))
+            .times(1)
+            .returning(|_| {
+                Err(Error::ApiError(
+                    reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+                    "APIエラー".to_string(),
+                ))
+            });
+        mock_client
+            .expect_mark_message_as_read()
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_event_sink(Box::new(RecordingSink(events.clone())));
+        processor.process_all_rooms().await.unwrap();
+
+        let events = events.lock().unwrap();
+        let names: Vec<String> = events
+            .iter()
+            .map(|event| serde_json::to_value(event).unwrap()["event"].to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "\"run_started\"",
+                "\"room_read\"",
+                "\"room_skipped\"",
+                "\"room_failed\"",
+                "\"run_finished\""
+            ]
+        );
+        assert!(matches!(
+            &events[4],
+            RunEvent::RunFinished {
+                read_rooms: 1,
+                skipped_rooms: 1,
+                failed_rooms: 1,
+                error: None,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_emits_run_error() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Err(Error::ApiError(
+                reqwest::StatusCode::UNAUTHORIZED,
+                "認証エラー".to_string(),
+            ))
+        });
+
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_event_sink(Box::new(RecordingSink(events.clone())));
+        assert!(processor.process_all_rooms().await.is_err());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[1],
+            RunEvent::RunFinished { error: Some(_), .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_process_rooms_by_id() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        mock_client.expect_fetch_rooms().times(0);
+        mock_client
+            .expect_fetch_room()
+            .with(eq(1))
+            .times(1)
+            .returning(|room_id| {
+                Ok(Room {
+                    room_id,
+                    unread_num: 1,
+                    ..Default::default()
+                })
+            });
+        mock_client
+            .expect_fetch_room()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| {
+                Err(Error::ApiError(
+                    reqwest::StatusCode::NOT_FOUND,
+                    "ルームが見つかりません".to_string(),
+                ))
+            });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "1".to_string(),
+                    body: "Test message".to_string(),
+                    ..Default::default()
+                }])
+            });
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("1"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, create_test_settings());
+        let report = processor.process_rooms_by_id(&[1, 2]).await.unwrap();
+        assert_eq!(report.rooms.len(), 2);
+        assert!(report
+            .rooms
+            .iter()
+            .any(|room| room.room_id == 1 && matches!(room.outcome, RoomOutcome::Read { .. })));
+        assert!(report
+            .rooms
+            .iter()
+            .any(|room| room.room_id == 2 && matches!(room.outcome, RoomOutcome::Failed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_read_until_mention() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.read_until_mention = true;
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    unread_num: 3,
+                    mention_num: 1,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 2,
+                    unread_num: 1,
+                    mention_num: 1,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 3,
+                    unread_num: 1,
+                    mention_num: 1,
+                    ..Default::default()
+                },
+            ])
+        });
+
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "1".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "2".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "3".to_string(),
+                        body: "[rp aid=123 to=1-2] Test reply".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        // メンションが見つからないルームは既読にしない
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "4".to_string(),
+                    body: "Test message".to_string(),
+                    ..Default::default()
+                }])
+            });
+
+        // 既読のメッセージにあるメンションは、未読のメンションの位置として扱わない
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(3))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "5".to_string(),
+                        body: "[To:123] Read mention".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "6".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("2"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 1,
+                    mention_num: 1,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Read {
+                message_id: "2".to_string(),
+                consumed: 2,
+            }
+        );
+        assert_eq!(
+            report.rooms[1].outcome,
+            RoomOutcome::Skipped(SkipReason::MentionNotFound)
+        );
+        assert_eq!(
+            report.rooms[2].outcome,
+            RoomOutcome::Skipped(SkipReason::MentionNotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_with_tasks() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.skip_rooms_with_my_tasks = true;
+        settings.chatwork.protect_task_messages = true;
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    unread_num: 3,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 2,
+                    unread_num: 1,
+                    mytask_num: 1,
+                    ..Default::default()
+                },
+            ])
+        });
+
+        mock_client.expect_fetch_my_tasks().times(1).returning(|| {
+            Ok(vec![Task {
+                task_id: 1,
+                message_id: "3".to_string(),
+                ..Default::default()
+            }])
+        });
+
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "1".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "2".to_string(),
+                        body: "[task aid=123 st=open lt=0]資料確認[/task]".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "3".to_string(),
+                        body: "[dtext:task_added]".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("1"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 2,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+
+        assert_eq!(
+            report.rooms[1].outcome,
+            RoomOutcome::Skipped(SkipReason::OpenTasks(1))
+        );
+        assert_eq!(
+            processor.find_blocking_message(
+                &Room::default(),
+                &[Message {
+                    message_id: "3".to_string(),
+                    ..Default::default()
+                }]
+            ),
+            Some((0, BlockReason::Task))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_when_fetching_tasks_fails() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.protect_task_messages = true;
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![
+                Room {
+                    room_id: 1,
+                    unread_num: 1,
+                    ..Default::default()
+                },
+                Room {
+                    room_id: 2,
+                    unread_num: 1,
+                    mytask_num: 1,
+                    ..Default::default()
+                },
+            ])
+        });
+        mock_client.expect_fetch_my_tasks().times(1).returning(|| {
+            Err(Error::ApiError(
+                reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+                "APIエラー".to_string(),
+            ))
+        });
+
+        // 自分のタスクがないルームは通常どおり既読にする
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "1".to_string(),
+                    body: "Test message".to_string(),
+                    ..Default::default()
+                }])
+            });
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("1"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        // 自分のタスクがあるルームはメッセージを取得せず、失敗として記録する
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Read {
+                message_id: "1".to_string(),
+                consumed: 1,
+            }
+        );
+        assert!(matches!(report.rooms[1].outcome, RoomOutcome::Failed(_)));
+    }
+
+    #[test]
+    fn test_attached_file_ids() {
+        let body = "[info][title][dtext:file_uploaded][/title][preview id=12 ht=100][download:12]spec.pdf (1.00 KB)[/download][/info][download:34]a.txt (10 B)[/download]";
+        assert_eq!(attached_file_ids(body), vec![12, 34]);
+        assert!(attached_file_ids("Test message").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_with_attachments() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.attachments = AttachmentSettings {
+            protect: true,
+            name_patterns: vec![Pattern::new(r"\.pdf$").unwrap()],
+            min_size_bytes: 0,
+        };
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 3,
+                ..Default::default()
+            }])
+        });
+
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "1".to_string(),
+                        body: "[download:10]memo.txt (10 B)[/download]".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "2".to_string(),
+                        body: "[download:20]contract.pdf (1.00 MB)[/download]".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "3".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        mock_client
+            .expect_fetch_files()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    File {
+                        file_id: 10,
+                        message_id: "1".to_string(),
+                        filename: "memo.txt".to_string(),
+                        filesize: 10,
+                        ..Default::default()
+                    },
+                    File {
+                        file_id: 20,
+                        message_id: "2".to_string(),
+                        filename: "contract.pdf".to_string(),
+                        filesize: 1_048_576,
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        // パターンに一致しないmemo.txtは既読にし、contract.pdfの直前で止める
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("1"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 2,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Read {
+                message_id: "1".to_string(),
+                consumed: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_without_first_unread_message() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.attachments.protect = true;
+
+        // 前回の実行で添付ファイルのメッセージ2の直前まで既読にし、その後メッセージ4が届いた
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 3,
+                ..Default::default()
+            }])
+        });
+        // 取得したメッセージに、既読を止めたメッセージ2が含まれていない
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Message {
+                    message_id: "4".to_string(),
+                    body: "Test message".to_string(),
+                    ..Default::default()
+                }])
+            });
+
+        // メッセージ2を越えて既読にしない（mark_message_as_readは呼ばれない）
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Skipped(SkipReason::UnreadNotFetched {
+                unread: 3,
+                fetched: 1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_with_many_unread_messages() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let settings = create_test_settings();
+
+        // 未読メッセージが取得できる件数（100件）より多い
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 150,
+                ..Default::default()
+            }])
+        });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok((1..=100)
+                    .map(|id| Message {
+                        message_id: id.to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    })
+                    .collect())
+            });
+
+        // 添付ファイルを保護しない場合は、取得したメッセージの範囲で既読にする
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("100"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Read {
+                message_id: "100".to_string(),
+                consumed: 100,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_ignores_read_messages() {
+        let mut mock_client = MockChatworkClientTrait::new();
+        let mut settings = create_test_settings();
+        settings.chatwork.attachments.protect = true;
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 2,
+                ..Default::default()
+            }])
+        });
+        // `force=1`で取得したメッセージには、既読の[toall]や添付ファイルのメッセージが含まれる
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    Message {
+                        message_id: "1".to_string(),
+                        body: "[toall] お知らせです".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "2".to_string(),
+                        body: "[download:10]contract.pdf (1.00 MB)[/download]".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "3".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                    Message {
+                        message_id: "4".to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    },
+                ])
+            });
+
+        // 既読のメッセージは判定の対象にならず、未読メッセージを既読にする
+        // （既読の添付ファイルは確認しないため、fetch_filesは呼ばれない）
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("4"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, settings);
+        let report = processor.process_all_rooms().await.unwrap();
+        assert_eq!(
+            report.rooms[0].outcome,
+            RoomOutcome::Read {
+                message_id: "4".to_string(),
+                consumed: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_all_rooms_writes_journal_and_archive() {
+        let temp_dir = tempfile::TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let mut mock_client = MockChatworkClientTrait::new();
+
+        mock_client.expect_fetch_rooms().times(1).returning(|| {
+            Ok(vec![Room {
+                room_id: 1,
+                unread_num: 2,
+                ..Default::default()
+            }])
+        });
+        mock_client
+            .expect_fetch_messages()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| {
+                Ok((1..=4)
+                    .map(|id| Message {
+                        message_id: id.to_string(),
+                        body: "Test message".to_string(),
+                        ..Default::default()
+                    })
+                    .collect())
+            });
+        mock_client
+            .expect_mark_message_as_read()
+            .with(eq(1), eq("4"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 0,
+                    mention_num: 0,
+                })
+            });
+
+        let processor = MessageProcessor::new(mock_client, create_test_settings())
+            .with_journal(Journal::new(temp_dir.path().join("journal.jsonl")))
+            .with_archive(Archive::open(temp_dir.path().join("archive.sqlite3")).unwrap())
+            .with_account("company");
+        processor.process_all_rooms().await.unwrap();
+
+        // 既読にした未読メッセージのみが保存される
+        let archived = processor
+            .archive
+            .as_ref()
+            .unwrap()
+            .room_messages(1)
+            .unwrap();
+        assert_eq!(
+            archived
+                .iter()
+                .map(|m| m.message_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["3", "4"]
+        );
+
+        let entries = processor.journal.as_ref().unwrap().entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].room_id, 1);
+        assert_eq!(entries[0].previous_boundary.as_deref(), Some("3"));
+        assert_eq!(entries[0].new_boundary, "4");
+        assert_eq!(entries[0].consumed, 2);
+        assert_eq!(entries[0].account.as_deref(), Some("company"));
+        assert!(entries[0].rules.is_empty());
+        assert_eq!(entries[0].read_status.as_ref().unwrap().unread_num, 0);
+    }
 }
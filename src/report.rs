@@ -0,0 +1,309 @@
+//! 実行結果のレポートモジュール
+//!
+//! このモジュールは、`MessageProcessor`による1回の実行で各ルームが
+//! どのように処理されたか（既読・スキップ・失敗）を集計するための型を提供します。
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::models::Message;
+use crate::t;
+
+/// ルームをスキップした理由を表します。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+    /// 除外リストに含まれている
+    Excluded,
+    /// インクルードモードで対象リストに含まれていない
+    NotIncluded,
+    /// 未読メッセージがない
+    NoUnread,
+    /// 未読メッセージ数がしきい値未満
+    BelowMinUnread {
+        /// ルームの未読メッセージ数
+        unread: i32,
+        /// 設定されたしきい値
+        min: i32,
+    },
+    /// メンションが含まれている
+    Mention,
+    /// 自分に割り当てられた未完了タスクがある
+    OpenTasks(i32),
+    /// 取得したメッセージ内にメンションが見つからない
+    MentionNotFound,
+    /// 添付ファイルを保護する設定で、取得したメッセージに最初の未読メッセージが含まれていない（未読が多すぎる）
+    UnreadNotFetched {
+        /// ルームの未読メッセージ数
+        unread: i32,
+        /// 取得したメッセージ数
+        fetched: usize,
+    },
+}
+
+impl SkipReason {
+    /// 集計に使用する理由の識別子を返します。
+    pub fn key(&self) -> &'static str {
+        match self {
+            SkipReason::Excluded => "excluded",
+            SkipReason::NotIncluded => "not_included",
+            SkipReason::NoUnread => "no_unread",
+            SkipReason::BelowMinUnread { .. } => "below_min_unread",
+            SkipReason::Mention => "mention",
+            SkipReason::OpenTasks(_) => "open_tasks",
+            SkipReason::MentionNotFound => "mention_not_found",
+            SkipReason::UnreadNotFetched { .. } => "unread_not_fetched",
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Excluded => write!(f, "{}", t!("skip.excluded")),
+            SkipReason::NotIncluded => write!(f, "{}", t!("skip.not_included")),
+            SkipReason::NoUnread => write!(f, "{}", t!("skip.no_unread")),
+            SkipReason::BelowMinUnread { unread, min } => write!(
+                f,
+                "{}",
+                t!("skip.below_min_unread", unread = unread, min = min)
+            ),
+            SkipReason::Mention => write!(f, "{}", t!("skip.mention")),
+            SkipReason::OpenTasks(count) => {
+                write!(f, "{}", t!("skip.open_tasks", count = count))
+            }
+            SkipReason::MentionNotFound => write!(f, "{}", t!("skip.mention_not_found")),
+            SkipReason::UnreadNotFetched { unread, fetched } => write!(
+                f,
+                "{}",
+                t!(
+                    "skip.unread_not_fetched",
+                    unread = unread,
+                    fetched = fetched
+                )
+            ),
+        }
+    }
+}
+
+/// メッセージの既読を止めた理由を表します。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockReason {
+    /// `[toall]`を含むメッセージ
+    Toall,
+    /// 除外対象のアカウントへのメンション（返信を含む）
+    Mention(String),
+    /// 自分宛てのタスク
+    Task,
+    /// 保護対象の添付ファイル（ファイル名）
+    Attachment(String),
+    /// 保護対象のキーワード（一致したパターン）
+    Keyword(String),
+    /// 保護対象の送信者（アカウントID）
+    ProtectedSender(String),
+}
+
+impl BlockReason {
+    /// ジャーナルなどに記録するルールの識別子を返します。
+    pub fn key(&self) -> &'static str {
+        match self {
+            BlockReason::Toall => "toall",
+            BlockReason::Mention(_) => "mention",
+            BlockReason::Task => "task",
+            BlockReason::Attachment(_) => "attachment",
+            BlockReason::Keyword(_) => "keyword",
+            BlockReason::ProtectedSender(_) => "protected_sender",
+        }
+    }
+}
+
+impl fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockReason::Toall => write!(f, "{}", t!("block.toall")),
+            BlockReason::Mention(account_id) => {
+                write!(f, "{}", t!("block.mention", account_id = account_id))
+            }
+            BlockReason::Task => write!(f, "{}", t!("block.task")),
+            BlockReason::Attachment(filename) => {
+                write!(f, "{}", t!("block.attachment", filename = filename))
+            }
+            BlockReason::Keyword(pattern) => {
+                write!(f, "{}", t!("block.keyword", pattern = pattern))
+            }
+            BlockReason::ProtectedSender(account_id) => {
+                write!(
+                    f,
+                    "{}",
+                    t!("block.protected_sender", account_id = account_id)
+                )
+            }
+        }
+    }
+}
+
+/// 1つのルームの処理結果を表します。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomOutcome {
+    /// メッセージを既読にした
+    Read {
+        /// 既読にした最新のメッセージのID
+        message_id: String,
+        /// 既読にした未読メッセージの数
+        consumed: usize,
+    },
+    /// 既読にできるメッセージがなかった
+    NothingToRead,
+    /// ルームをスキップした
+    Skipped(SkipReason),
+    /// 処理に失敗した
+    Failed(String),
+}
+
+/// 1つのルームの処理結果と、そのルームの情報をまとめた構造体です。
+#[derive(Debug, Clone)]
+pub struct RoomReport {
+    /// ルームID
+    pub room_id: i32,
+    /// ルーム名
+    pub room_name: String,
+    /// 処理結果
+    pub outcome: RoomOutcome,
+    /// 既読にした未読メッセージ（古い順）
+    pub messages: Vec<Message>,
+}
+
+/// 1回の実行全体の処理結果を表す構造体です。
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    /// ルームごとの処理結果
+    pub rooms: Vec<RoomReport>,
+}
+
+impl RunReport {
+    /// ルームの処理結果を追加します。
+    pub fn push(&mut self, room_id: i32, room_name: &str, outcome: RoomOutcome) {
+        self.push_with_messages(room_id, room_name, outcome, Vec::new());
+    }
+
+    /// 既読にしたメッセージとともに、ルームの処理結果を追加します。
+    pub fn push_with_messages(
+        &mut self,
+        room_id: i32,
+        room_name: &str,
+        outcome: RoomOutcome,
+        messages: Vec<Message>,
+    ) {
+        self.rooms.push(RoomReport {
+            room_id,
+            room_name: room_name.to_string(),
+            outcome,
+            messages,
+        });
+    }
+
+    /// メッセージを既読にしたルームの数を返します。
+    pub fn read_rooms(&self) -> usize {
+        self.rooms
+            .iter()
+            .filter(|room| matches!(room.outcome, RoomOutcome::Read { .. }))
+            .count()
+    }
+
+    /// 既読にした未読メッセージの合計数を返します。
+    pub fn consumed_messages(&self) -> usize {
+        self.rooms
+            .iter()
+            .map(|room| match room.outcome {
+                RoomOutcome::Read { consumed, .. } => consumed,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// スキップしたルームの数を返します。
+    pub fn skipped_rooms(&self) -> usize {
+        self.rooms
+            .iter()
+            .filter(|room| matches!(room.outcome, RoomOutcome::Skipped(_)))
+            .count()
+    }
+
+    /// 処理に失敗したルームの数を返します。
+    pub fn failed_rooms(&self) -> usize {
+        self.rooms
+            .iter()
+            .filter(|room| matches!(room.outcome, RoomOutcome::Failed(_)))
+            .count()
+    }
+
+    /// スキップした理由ごとのルーム数を返します。
+    pub fn skip_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for room in &self.rooms {
+            if let RoomOutcome::Skipped(reason) = &room.outcome {
+                *counts.entry(reason.key()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            t!(
+                "report.summary",
+                read_rooms = self.read_rooms(),
+                consumed = self.consumed_messages(),
+                skipped_rooms = self.skipped_rooms(),
+                failed_rooms = self.failed_rooms()
+            )
+        )?;
+        let skip_counts = self.skip_counts();
+        if !skip_counts.is_empty() {
+            let details: Vec<String> = skip_counts
+                .iter()
+                .map(|(key, count)| format!("{}={}", key, count))
+                .collect();
+            write!(f, " [{}]", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_report_summary() {
+        let mut report = RunReport::default();
+        report.push(
+            1,
+            "開発",
+            RoomOutcome::Read {
+                message_id: "10".to_string(),
+                consumed: 3,
+            },
+        );
+        report.push(2, "雑談", RoomOutcome::Skipped(SkipReason::Mention));
+        report.push(
+            3,
+            "お知らせ",
+            RoomOutcome::Skipped(SkipReason::BelowMinUnread { unread: 1, min: 5 }),
+        );
+        report.push(4, "障害", RoomOutcome::Failed("APIエラー".to_string()));
+        report.push(5, "bot", RoomOutcome::NothingToRead);
+
+        assert_eq!(report.read_rooms(), 1);
+        assert_eq!(report.consumed_messages(), 3);
+        assert_eq!(report.skipped_rooms(), 2);
+        assert_eq!(report.failed_rooms(), 1);
+        assert_eq!(
+            report.to_string(),
+            "既読: 1ルーム（3件）, スキップ: 2ルーム, 失敗: 1ルーム [below_min_unread=1, mention=1]"
+        );
+    }
+}
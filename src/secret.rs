@@ -0,0 +1,100 @@
+//! 秘密情報モジュール
+//!
+//! このモジュールは、APIトークンやパスワードなどの秘密情報を保持し、
+//! `Debug`や`Display`で出力した場合やログに含まれた場合に`***`へ置き換える機能を提供します。
+
+use std::fmt;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// 秘密情報を置き換える文字列です。
+pub const REDACTED: &str = "***";
+
+/// APIトークンやパスワードなどの秘密情報です。
+///
+/// `Debug`と`Display`では常に`***`を出力します。値を使用する場合は[`Secret::expose`]を呼び出します。
+///
+/// ```
+/// use chatwork_auto_read::secret::Secret;
+///
+/// let token = Secret::from("abcdef");
+/// assert_eq!(format!("{:?}", token), "***");
+/// assert_eq!(token.expose(), "abcdef");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// 秘密情報の値を返します。
+    ///
+    /// ログやエラーメッセージに含めないでください。
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// 値が空かどうかを返します。
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl JsonSchema for Secret {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// 文字列に含まれる秘密情報を`***`に置き換えます。
+///
+/// APIのレスポンスなど、秘密情報が含まれる可能性のある文字列をログに記録する前に使用します。
+///
+/// ```
+/// use chatwork_auto_read::secret::{scrub, Secret};
+///
+/// let token = Secret::from("abcdef");
+/// assert_eq!(scrub("token=abcdef", &[&token]), "token=***");
+/// ```
+pub fn scrub(text: &str, secrets: &[&Secret]) -> String {
+    secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret.expose(), REDACTED)
+        })
+}
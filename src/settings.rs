@@ -1,31 +1,910 @@
 use crate::error::Error;
+use crate::i18n::Language;
+use crate::models::Room;
+use crate::secret::Secret;
+use crate::t;
+use crate::token;
+use crate::utils::excerpt;
+use crate::validation::{self, ConfigIssue};
 use config::{Config, Environment, File};
+use regex::Regex;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::Deserialize;
-use std::{collections::HashSet, env};
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    path::{Path, PathBuf},
+};
+
+/// 設定ファイルに記述される正規表現パターンです。
+///
+/// 設定の読み込み時にコンパイルされるため、不正なパターンは
+/// `Settings::new`の時点で設定エラーになります。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// 文字列から新しいパターンを作成します。
+    ///
+    /// # エラー
+    ///
+    /// 正規表現として不正な場合、`regex::Error`を返します。
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self(Regex::new(pattern)?))
+    }
+
+    /// 指定された文字列がパターンに一致するかどうかを返します。
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    /// 元のパターン文字列を返します。
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl JsonSchema for Pattern {
+    fn schema_name() -> String {
+        "Pattern".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("regex".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+/// `[toall]`（全体宛て）を含むメッセージに対する動作です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToallAction {
+    /// `[toall]`を含むメッセージ以降は既読にしません（デフォルト）
+    #[default]
+    Block,
+    /// `[toall]`を無視し、通常のメッセージとして扱います
+    Ignore,
+    /// `senders`に含まれるアカウントからの`[toall]`のみ既読を止めます
+    BlockFromSenders,
+}
+
+/// `[toall]`を含むメッセージの扱いを定めるポリシーです。
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ToallPolicy {
+    /// `[toall]`に対する動作
+    #[serde(default)]
+    pub action: ToallAction,
+    /// `block_from_senders`の場合に既読を止める送信者のアカウントIDのリスト
+    #[schemars(inner(regex(pattern = r"^[0-9]+$")))]
+    #[serde(default)]
+    pub senders: Vec<String>,
+}
+
+impl ToallPolicy {
+    /// 指定された送信者からの`[toall]`で既読を止めるべきかどうかを返します。
+    pub fn blocks(&self, sender_id: &str) -> bool {
+        match self.action {
+            ToallAction::Block => true,
+            ToallAction::Ignore => false,
+            ToallAction::BlockFromSenders => self.senders.iter().any(|id| id == sender_id),
+        }
+    }
+}
+
+/// 添付ファイルを含むメッセージの保護に関する設定です。
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct AttachmentSettings {
+    /// 添付ファイルを含むメッセージ以降を既読にしないかどうか（デフォルトは`false`）
+    #[serde(default)]
+    pub protect: bool,
+    /// 保護するファイル名のパターン（空の場合は全てのファイルを保護）
+    #[serde(default)]
+    pub name_patterns: Vec<Pattern>,
+    /// 保護するファイルの最小サイズ（バイト、デフォルトは0）
+    #[serde(default)]
+    pub min_size_bytes: u64,
+}
+
+impl AttachmentSettings {
+    /// 指定されたファイル名とサイズのファイルを保護すべきかどうかを返します。
+    pub fn protects(&self, filename: &str, filesize: u64) -> bool {
+        self.protect
+            && filesize >= self.min_size_bytes
+            && (self.name_patterns.is_empty()
+                || self
+                    .name_patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(filename)))
+    }
+}
+
+/// 特定のルームにのみ適用される個別設定です。
+///
+/// `room_ids`または`room_patterns`（ルーム名の正規表現）に一致するルームに適用されます。
+/// 複数のルールが一致する場合、項目ごとに先に記述されたルールが優先されます。
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct RoomRule {
+    /// ルールを適用するルームIDのセット
+    #[schemars(inner(range(min = 1)))]
+    #[serde(default)]
+    pub room_ids: HashSet<i32>,
+    /// ルールを適用するルーム名のパターン
+    #[serde(default)]
+    pub room_patterns: Vec<Pattern>,
+    /// このルームで使用する`[toall]`ポリシー
+    #[serde(default)]
+    pub toall: Option<ToallPolicy>,
+    /// このルームで既読にするまでの猶予時間（分）
+    #[serde(default)]
+    pub min_message_age_minutes: Option<u64>,
+}
+
+impl RoomRule {
+    /// 指定されたルームにこのルールが適用されるかどうかを返します。
+    pub fn matches(&self, room: &Room) -> bool {
+        self.room_ids.contains(&room.room_id)
+            || self
+                .room_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(&room.name))
+    }
+}
+
+/// APIトークンを標準出力に書き出すコマンド（パスワードマネージャーのCLIなど）です。
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct TokenCommand {
+    /// 実行するプログラム（例: "op"）
+    pub program: String,
+    /// プログラムに渡す引数（例: ["read", "op://Private/Chatwork/token"]）
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// APIトークンを保存したOSのキーリングのエントリです。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct KeyringEntry {
+    /// サービス名（デフォルトは"chatwork_auto_read"）
+    #[serde(default = "default_keyring_service")]
+    pub service: String,
+    /// ユーザー名（デフォルトは"api_token"）
+    #[serde(default = "default_keyring_user")]
+    pub user: String,
+}
+
+impl Default for KeyringEntry {
+    fn default() -> Self {
+        Self {
+            service: default_keyring_service(),
+            user: default_keyring_user(),
+        }
+    }
+}
+
+fn default_keyring_service() -> String {
+    env!("CARGO_PKG_NAME").to_string()
+}
+
+fn default_keyring_user() -> String {
+    "api_token".to_string()
+}
+
+/// Chatwork OAuth 2.0で認証する場合の設定です。
+///
+/// トークンは`authorize`コマンドで取得し、`token_path`のファイルに保存します。
+/// アクセストークンの期限が近づくと、リフレッシュトークンで更新してファイルに書き戻します。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct OAuthSettings {
+    /// Chatworkに登録したOAuthクライアントのクライアントID
+    pub client_id: String,
+    /// クライアントシークレット（confidentialクライアントの場合のみ指定します）
+    #[serde(default)]
+    pub client_secret: Option<Secret>,
+    /// 認可コードを受け取るループバックアドレス（`http://127.0.0.1:{ポート}/callback`）のポート（デフォルトは8765）
+    #[serde(default = "default_oauth_redirect_port")]
+    pub redirect_port: u16,
+    /// 要求するスコープ（スペース区切り）
+    #[serde(default = "default_oauth_scope")]
+    pub scope: String,
+    /// 取得したトークンを保存するファイルのパス（デフォルトは"data/oauth_token.json"）
+    #[serde(default = "default_oauth_token_path")]
+    pub token_path: PathBuf,
+}
+
+impl OAuthSettings {
+    /// OAuthクライアントに登録するリダイレクトURIを返します。
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}/callback", self.redirect_port)
+    }
+}
+
+fn default_oauth_redirect_port() -> u16 {
+    8765
+}
+
+fn default_oauth_scope() -> String {
+    "rooms.all:read_write users.tasks.me:read users.profile.me:read".to_string()
+}
+
+fn default_oauth_token_path() -> PathBuf {
+    PathBuf::from("data/oauth_token.json")
+}
 
 /// Chatworkの設定を保持する構造体です。
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
 pub struct ChatworkSettings {
-    /// Chatwork APIのトークン
-    pub api_token: String,
+    /// Chatwork APIのトークン（`Debug`では`***`と出力されます）
+    ///
+    /// `api_token_file`・`api_token_command`・`api_token_keyring`のいずれかを指定した場合は、
+    /// 設定の読み込み時にそこから読み込んだトークンが入ります。
+    #[serde(default)]
+    pub api_token: Secret,
+    /// APIトークンを読み込むファイルのパス（Unixではパーミッションが`0600`または`0400`である必要があります）
+    #[serde(default)]
+    pub api_token_file: Option<PathBuf>,
+    /// APIトークンを標準出力に書き出すコマンド
+    #[serde(default)]
+    pub api_token_command: Option<TokenCommand>,
+    /// APIトークンを読み込むOSのキーリングのエントリ（`keyring`フィーチャーが必要です）
+    #[serde(default)]
+    pub api_token_keyring: Option<KeyringEntry>,
+    /// APIトークンの代わりにOAuth 2.0で認証する場合の設定
+    #[serde(default)]
+    pub oauth: Option<OAuthSettings>,
     /// 対象となるアカウントIDのリスト
+    #[schemars(inner(regex(pattern = r"^[0-9]+$")))]
     pub exclude_account_ids: Vec<String>,
     /// スキップするルームIDのセット（デフォルトは空）
+    #[schemars(inner(range(min = 1)))]
     #[serde(default)]
     pub exclude_room_ids: HashSet<i32>,
+    /// スキップするルーム名のパターン（デフォルトは空）
+    #[serde(default)]
+    pub exclude_room_patterns: Vec<Pattern>,
+    /// 自動既読の対象とするルームIDのセット（デフォルトは空）
+    ///
+    /// `include_room_patterns`と合わせていずれかが指定されている場合、
+    /// 一致するルームのみが処理されます（インクルードモード）。
+    #[schemars(inner(range(min = 1)))]
+    #[serde(default)]
+    pub include_room_ids: HashSet<i32>,
+    /// 自動既読の対象とするルーム名のパターン（デフォルトは空）
+    #[serde(default)]
+    pub include_room_patterns: Vec<Pattern>,
+    /// 全ルーム共通の`[toall]`ポリシー（デフォルトは`block`）
+    #[serde(default)]
+    pub toall: ToallPolicy,
+    /// 処理対象とする未読メッセージ数の下限（デフォルトは0）
+    ///
+    /// 未読メッセージ数がこの値未満のルームはスキップされます。
+    #[serde(default)]
+    pub min_unread_num: i32,
+    /// メンションを含むルームでも、最初のメンションの直前までは既読にするかどうか（デフォルトは`false`）
+    #[serde(default)]
+    pub read_until_mention: bool,
+    /// 自分に割り当てられた未完了タスクがあるルームをスキップするかどうか（デフォルトは`false`）
+    #[serde(default)]
+    pub skip_rooms_with_my_tasks: bool,
+    /// 自分宛てのタスクを含むメッセージ以降を既読にしないかどうか（デフォルトは`false`）
+    #[serde(default)]
+    pub protect_task_messages: bool,
+    /// 本文が一致するメッセージ以降を既読にしないキーワードのパターン（デフォルトは空）
+    #[serde(default)]
+    pub protect_keywords: Vec<Pattern>,
+    /// 送信したメッセージ以降を既読にしないアカウントIDのリスト（デフォルトは空）
+    #[schemars(inner(regex(pattern = r"^[0-9]+$")))]
+    #[serde(default)]
+    pub protect_senders: Vec<String>,
+    /// 添付ファイルの保護に関する設定
+    #[serde(default)]
+    pub attachments: AttachmentSettings,
+    /// 既読にするまでの猶予時間（分、デフォルトは0）
+    ///
+    /// 送信から指定した時間が経過していないメッセージは既読にしません。
+    #[serde(default)]
+    pub min_message_age_minutes: u64,
+    /// ルームごとの個別設定のリスト
+    #[serde(default)]
+    pub room_rules: Vec<RoomRule>,
+}
+
+impl ChatworkSettings {
+    /// 設定されたトークンの読み込み元からAPIトークンを読み込み、`api_token`に設定します。
+    ///
+    /// `api_token`・`api_token_file`・`api_token_command`・`api_token_keyring`・`oauth`のうち、
+    /// 指定できるのはいずれか1つのみです。`oauth`を指定した場合、トークンは実行時に保存したファイルから読み込みます。
+    ///
+    /// # エラー
+    ///
+    /// 読み込み元が複数指定されている場合、トークンを読み込めない場合、
+    /// トークンがどこにも指定されていない場合に`Error`を返します。
+    fn resolve_api_token(&mut self) -> Result<(), Error> {
+        if self.token_sources() > 1 {
+            return Err(config::ConfigError::Message(t!("token.multiple_sources")).into());
+        }
+
+        if self.oauth.is_some() {
+            return Ok(());
+        }
+        if let Some(path) = &self.api_token_file {
+            self.api_token = token::read_file(path)?;
+        } else if let Some(command) = &self.api_token_command {
+            self.api_token = token::run_command(command)?;
+        } else if let Some(entry) = &self.api_token_keyring {
+            self.api_token = token::read_keyring(entry)?;
+        } else if self.api_token.is_empty() {
+            return Err(config::ConfigError::Message(t!("token.missing")).into());
+        }
+        Ok(())
+    }
+
+    /// 指定されているトークンの読み込み元の数を返します。
+    pub fn token_sources(&self) -> usize {
+        [
+            !self.api_token.is_empty(),
+            self.api_token_file.is_some(),
+            self.api_token_command.is_some(),
+            self.api_token_keyring.is_some(),
+            self.oauth.is_some(),
+        ]
+        .iter()
+        .filter(|&&source| source)
+        .count()
+    }
+
+    /// インクルードモード（対象ルームを明示的に指定するモード）が有効かどうかを返します。
+    pub fn is_include_mode(&self) -> bool {
+        !self.include_room_ids.is_empty() || !self.include_room_patterns.is_empty()
+    }
+
+    /// 指定されたルームに適用される`[toall]`ポリシーを返します。
+    ///
+    /// `room_rules`の中で最初に一致し、かつ`toall`を指定しているルールのポリシーを返します。
+    /// 該当するルールがない場合は全ルーム共通のポリシーを返します。
+    pub fn toall_policy_for(&self, room: &Room) -> &ToallPolicy {
+        self.room_rules
+            .iter()
+            .filter(|rule| rule.matches(room))
+            .find_map(|rule| rule.toall.as_ref())
+            .unwrap_or(&self.toall)
+    }
+
+    /// 指定されたルームに適用される既読までの猶予時間（分）を返します。
+    ///
+    /// `room_rules`の中で最初に一致し、かつ猶予時間を指定しているルールの値を返します。
+    /// 該当するルールがない場合は全ルーム共通の値を返します。
+    pub fn min_message_age_for(&self, room: &Room) -> u64 {
+        self.room_rules
+            .iter()
+            .filter(|rule| rule.matches(room))
+            .find_map(|rule| rule.min_message_age_minutes)
+            .unwrap_or(self.min_message_age_minutes)
+    }
+}
+
+/// 既読操作のジャーナルに関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct JournalSettings {
+    /// ジャーナルへの記録を行うかどうか（デフォルトは`true`）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// ジャーナルファイルのパス（デフォルトは"data/journal.jsonl"）
+    #[serde(default = "default_journal_path")]
+    pub path: PathBuf,
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            path: default_journal_path(),
+        }
+    }
+}
+
+/// メッセージアーカイブに関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ArchiveSettings {
+    /// 既読にしたメッセージをアーカイブに保存するかどうか（デフォルトは`true`）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// アーカイブのデータベースファイルのパス（デフォルトは"data/archive.sqlite3"）
+    #[serde(default = "default_archive_path")]
+    pub path: PathBuf,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            path: default_archive_path(),
+        }
+    }
+}
+
+/// ダイジェストの出力形式です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFormat {
+    /// Markdown（デフォルト）
+    #[default]
+    Markdown,
+    /// HTML
+    Html,
+    /// プレーンテキスト
+    Text,
+}
+
+/// ダイジェストを出力するタイミングです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestSchedule {
+    /// 実行ごとに出力する（デフォルト）
+    #[default]
+    EveryRun,
+    /// デーモンモードで1日ごとにまとめて出力する
+    Daily,
+}
+
+/// 既読にしたメッセージのダイジェストに関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DigestSettings {
+    /// ダイジェストを出力するかどうか（デフォルトは`false`）
+    pub enabled: bool,
+    /// 出力形式
+    pub format: DigestFormat,
+    /// 出力するタイミング（`run`コマンドでは常に実行ごとに出力されます）
+    pub schedule: DigestSchedule,
+    /// ダイジェストをファイルに書き込むかどうか（デフォルトは`true`）
+    pub write_file: bool,
+    /// 出力先のディレクトリ（デフォルトは"data/digests"）
+    pub output_dir: PathBuf,
+    /// ダイジェストをChatworkに投稿するかどうか（デフォルトは`false`）
+    pub post_to_chatwork: bool,
+    /// 投稿先のルームID（省略時はマイチャット）
+    pub post_room_id: Option<i32>,
+    /// ルームごとに表示する発言の多い送信者の数
+    pub top_senders: usize,
+    /// ルームごとに表示するメッセージの抜粋の数
+    pub max_excerpts: usize,
+    /// 抜粋の最大文字数
+    pub excerpt_chars: usize,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: DigestFormat::default(),
+            schedule: DigestSchedule::default(),
+            write_file: true,
+            output_dir: PathBuf::from("data/digests"),
+            post_to_chatwork: false,
+            post_room_id: None,
+            top_senders: 3,
+            max_excerpts: 10,
+            excerpt_chars: 80,
+        }
+    }
+}
+
+/// メール通知で使用するSMTP接続の暗号化方式です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// 暗号化しない（ローカルのリレーサーバー向け）
+    None,
+    /// STARTTLSで暗号化する（デフォルト）
+    #[default]
+    Starttls,
+    /// 接続時からTLSで暗号化する
+    Tls,
+}
+
+/// メール通知の設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct EmailNotifierSettings {
+    /// SMTPサーバーのホスト名
+    pub smtp_host: String,
+    /// SMTPサーバーのポート番号（省略時は暗号化方式の標準ポート）
+    #[serde(default)]
+    pub smtp_port: Option<u16>,
+    /// 接続の暗号化方式
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// SMTP認証のユーザー名
+    #[serde(default)]
+    pub username: Option<String>,
+    /// SMTP認証のパスワード
+    #[serde(default)]
+    pub password: Option<Secret>,
+    /// 送信元のメールアドレス
+    pub from: String,
+    /// 送信先のメールアドレス
+    pub to: Vec<String>,
+}
+
+/// 通知の種類ごとの設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierKind {
+    /// 指定したURLにJSONをPOSTする
+    Webhook {
+        /// 送信先のURL
+        url: String,
+        /// リクエストに付与するヘッダー
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// SMTPでメールを送信する
+    Email(EmailNotifierSettings),
+    /// コマンドを実行する（イベントのJSONを標準入力に渡す）
+    Command {
+        /// 実行するプログラム
+        program: String,
+        /// プログラムに渡す引数
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// 既読を止めたときの通知の設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct NotifierSettings {
+    /// 通知の種類と、種類ごとの設定
+    #[serde(flatten)]
+    pub kind: NotifierKind,
+    /// 通知するルールの識別子（`mention`、`toall`など）。空の場合は全てのルールで通知する
+    #[serde(default)]
+    pub rules: Vec<String>,
+}
+
+/// 実行イベントを送信するWebhookの設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct EventWebhookSettings {
+    /// 送信先のURL
+    pub url: String,
+    /// リクエストに付与するヘッダー
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// 本文のHMAC-SHA256署名に使用する秘密鍵（省略時は署名しない）
+    #[serde(default)]
+    pub secret: Option<Secret>,
+    /// 送信に失敗したときの再試行回数（デフォルトは3）
+    #[serde(default = "default_event_max_retries")]
+    pub max_retries: u32,
+    /// 最初の再試行までの待ち時間（ミリ秒、デフォルトは1000。再試行ごとに2倍になる）
+    #[serde(default = "default_event_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// 送信できなかったイベントを保存するファイルのパス（デフォルトは"data/event_queue.jsonl"）
+    #[serde(default = "default_event_queue_path")]
+    pub queue_path: PathBuf,
+    /// 保存するイベントの最大数（超えた場合は古いものから破棄、デフォルトは1000）
+    #[serde(default = "default_event_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_event_max_retries() -> u32 {
+    3
+}
+
+fn default_event_retry_delay_ms() -> u64 {
+    1000
+}
+
+fn default_event_queue_path() -> PathBuf {
+    PathBuf::from("data/event_queue.jsonl")
+}
+
+fn default_event_queue_capacity() -> usize {
+    1000
+}
+
+/// デーモンモードに関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DaemonSettings {
+    /// 既読処理を実行する間隔（秒、デフォルトは300）
+    pub interval_secs: u64,
+    /// `/metrics`・`/healthz`・`/readyz`を公開するHTTPサーバーのアドレス（省略時は公開しない）
+    pub listen: Option<String>,
+}
+
+impl Default for DaemonSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            listen: None,
+        }
+    }
+}
+
+/// ログの出力形式です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// 人が読むためのテキスト形式
+    #[default]
+    Text,
+    /// 1行1件のJSON形式
+    Json,
+}
+
+/// ログファイルのローテーションの方法です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    /// 日付が変わったときにローテーションする
+    #[default]
+    Daily,
+    /// ファイルが`max_size_mb`に達したときにローテーションする
+    Size,
+    /// ローテーションしない
+    Never,
+}
+
+/// ログファイルへの出力に関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct LogFileSettings {
+    /// ログファイルのパス（デフォルトは"logs/chatwork_auto_read.log"）
+    #[serde(default = "default_log_file_path")]
+    pub path: PathBuf,
+    /// ローテーションの方法（デフォルトは日付が変わったとき）
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// `rotation = "size"`の場合にローテーションするファイルの大きさ（MB、デフォルトは10）
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+    /// ローテーションした古いファイルを残す数（デフォルトは7）
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+fn default_log_file_path() -> PathBuf {
+    PathBuf::from("logs/chatwork_auto_read.log")
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> usize {
+    7
+}
+
+/// ログの出力に関する設定です。
+///
+/// 環境変数 `RUST_LOG` と `LOG_FORMAT` が設定されている場合は、そちらが優先されます。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LoggingSettings {
+    /// ログレベル（`RUST_LOG`と同じ書式、デフォルトは"info"）
+    pub level: String,
+    /// 出力形式（デフォルトはテキスト）
+    pub format: LogFormat,
+    /// 標準エラー出力に出力するかどうか（デフォルトは出力する）
+    pub stderr: bool,
+    /// ログファイルへの出力（省略時はファイルに出力しない）
+    pub file: Option<LogFileSettings>,
+    /// ログに記録する内容の秘匿に関する設定
+    pub redaction: RedactionSettings,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            stderr: true,
+            file: None,
+            redaction: RedactionSettings::default(),
+        }
+    }
+}
+
+/// ログに記録するメッセージ本文（APIのレスポンスボディなど）の扱いです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyRedaction {
+    /// 本文は記録せず、文字数のみを記録します（デフォルト）
+    #[default]
+    Hide,
+    /// 本文の最初の行を`excerpt_chars`文字まで記録します
+    Excerpt,
+    /// 本文をそのまま記録します
+    Full,
+}
+
+/// ログに記録する内容の秘匿に関する設定です。
+///
+/// いずれの場合も、APIトークンなどの秘密情報は`***`に置き換えて記録します。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RedactionSettings {
+    /// メッセージ本文の扱い（デフォルトは`hide`）
+    pub message_bodies: BodyRedaction,
+    /// `excerpt`の場合に記録する文字数（デフォルトは40）
+    pub excerpt_chars: usize,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            message_bodies: BodyRedaction::Hide,
+            excerpt_chars: 40,
+        }
+    }
+}
+
+impl RedactionSettings {
+    /// 設定に従って、ログに記録する本文を返します。
+    pub fn body(&self, body: &str) -> String {
+        match self.message_bodies {
+            BodyRedaction::Hide => t!("redaction.hidden_body", chars = body.chars().count()),
+            BodyRedaction::Excerpt => excerpt(body, self.excerpt_chars),
+            BodyRedaction::Full => body.to_string(),
+        }
+    }
+}
+
+/// `/healthz`と`/readyz`の判定に関する設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HealthSettings {
+    /// 最後に成功した実行からこの秒数が経過すると`/readyz`を失敗にします（デフォルトは900）
+    pub stale_after_secs: u64,
+    /// 最後に成功した実行からこの秒数が経過すると`/healthz`を失敗にします（デフォルトは3600）
+    pub unhealthy_after_secs: u64,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            stale_after_secs: 900,
+            unhealthy_after_secs: 3600,
+        }
+    }
+}
+
+/// ChatworkのWebhookを受信するサーバーの設定です。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct WebhookReceiverSettings {
+    /// 待ち受けるアドレス（デフォルトは"127.0.0.1:8080"）
+    #[serde(default = "default_webhook_listen")]
+    pub listen: String,
+    /// Webhookを受け付けるパス（デフォルトは"/webhook"）
+    #[serde(default = "default_webhook_path")]
+    pub path: String,
+    /// ChatworkのWebhook設定で発行されたトークン（署名の検証に使用）
+    pub token: Secret,
+    /// Webhookを受信してからルームを処理するまでの待ち時間（ミリ秒、デフォルトは2000）
+    ///
+    /// 待っている間に同じルームで受信したWebhookは、まとめて1回の処理になります。
+    #[serde(default = "default_webhook_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+fn default_webhook_listen() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+fn default_webhook_path() -> String {
+    "/webhook".to_string()
+}
+
+fn default_webhook_debounce_ms() -> u64 {
+    2000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_journal_path() -> PathBuf {
+    PathBuf::from("data/journal.jsonl")
+}
+
+fn default_archive_path() -> PathBuf {
+    PathBuf::from("data/archive.sqlite3")
+}
+
+/// 1つのChatworkアカウントの設定です。
+///
+/// `[chatwork]`と同じ項目（トークン・除外するアカウントやルーム・ルームごとの個別設定など）を
+/// アカウントごとに指定します。
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct AccountSettings {
+    /// アカウントの名前（ログ・ジャーナル・ダイジェストの出力先でアカウントを区別するために使用）
+    pub name: String,
+    /// このアカウントのダイジェストの投稿先のルームID（省略時は`[digest]`の`post_room_id`）
+    #[serde(default)]
+    pub digest_room_id: Option<i32>,
+    /// このアカウントのChatwork関連の設定
+    #[serde(flatten)]
+    pub chatwork: ChatworkSettings,
+}
+
+/// 複数のアカウントを処理する方法です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountMode {
+    /// 設定に記述された順に1つずつ処理します（デフォルト）
+    #[default]
+    Sequential,
+    /// 全てのアカウントを同時に処理します
+    Concurrent,
 }
 
 /// アプリケーション全体の設定を保持する構造体です。
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
 pub struct Settings {
-    /// Chatwork関連の設定
+    /// ログ・コマンドラインへの出力・エラー・レポートの言語（"ja" / "en"、省略時はOSのロケールに従う）
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// Chatwork関連の設定（`accounts`を指定した場合は使用しません）
+    #[serde(default)]
     pub chatwork: ChatworkSettings,
+    /// 処理するChatworkアカウントのリスト（省略時は`[chatwork]`の1つのアカウントのみ）
+    #[serde(default)]
+    pub accounts: Vec<AccountSettings>,
+    /// 複数のアカウントを処理する方法（デフォルトは`sequential`）
+    #[serde(default)]
+    pub account_mode: AccountMode,
+    /// 既読操作のジャーナルに関する設定
+    #[serde(default)]
+    pub journal: JournalSettings,
+    /// メッセージアーカイブに関する設定
+    #[serde(default)]
+    pub archive: ArchiveSettings,
+    /// ダイジェストに関する設定
+    #[serde(default)]
+    pub digest: DigestSettings,
+    /// デーモンモードに関する設定
+    #[serde(default)]
+    pub daemon: DaemonSettings,
+    /// ログの出力に関する設定
+    #[serde(default)]
+    pub logging: LoggingSettings,
+    /// ヘルスチェックに関する設定
+    #[serde(default)]
+    pub health: HealthSettings,
+    /// 既読を止めたときの通知先
+    #[serde(default)]
+    pub notifiers: Vec<NotifierSettings>,
+    /// 実行イベントを送信するWebhook
+    #[serde(default)]
+    pub event_webhook: Option<EventWebhookSettings>,
+    /// ChatworkのWebhookを受信するサーバー
+    #[serde(default)]
+    pub webhook_receiver: Option<WebhookReceiverSettings>,
+    /// 設定の読み込み時に見つかった警告（ロギングの設定後に出力します）
+    #[serde(skip)]
+    pub warnings: Vec<String>,
 }
 
+/// 設定ファイルとして読み込まれる拡張子です。
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "json", "yaml", "yml", "ini", "ron", "json5"];
+
 impl Settings {
     /// 環境変数 RUN_MODE に基づいて新しい Settings インスタンスを作成します。
     ///
     /// RUN_MODE が設定されていない場合、デフォルトで "development" を使用します。
+    /// 設定ファイルは環境変数 CONFIG_DIR のディレクトリ（デフォルトは "config"）から読み込みます。
     ///
     /// # エラー
     ///
@@ -35,6 +914,25 @@ impl Settings {
         Self::new_with_mode(&run_mode)
     }
 
+    /// 設定ファイルと環境変数から、メッセージの言語（`language`）のみを読み込みます。
+    ///
+    /// コマンドラインのヘルプを設定した言語で表示するため、引数の解析より前に使用します。
+    /// 設定の検証やトークンの読み込みは行わず、読み込めない場合は`None`を返します。
+    pub fn configured_language() -> Option<Language> {
+        let run_mode = env::var("RUN_MODE").unwrap_or_else(|_| "development".into());
+        let config_dir = env::var("CONFIG_DIR").unwrap_or_else(|_| "config".into());
+        let config_dir = Path::new(&config_dir);
+
+        Config::builder()
+            .add_source(File::from(config_dir.join("default")).required(false))
+            .add_source(File::from(config_dir.join(run_mode)).required(false))
+            .add_source(Environment::with_prefix("APP"))
+            .build()
+            .ok()?
+            .get("language")
+            .ok()
+    }
+
     /// 指定されたモードに基づいて新しい Settings インスタンスを作成します。
     ///
     /// # 引数
@@ -45,13 +943,133 @@ impl Settings {
     ///
     /// 設定ファイルの読み込みや解析に失敗した場合、`Error`を返します。
     fn new_with_mode(run_mode: &str) -> Result<Self, Error> {
+        let config_dir = env::var("CONFIG_DIR").unwrap_or_else(|_| "config".into());
+        let config_dir = Path::new(&config_dir);
+
+        // 空の環境変数は、トークンが指定されていないものとして扱います
+        let env_token = env::var("APP_CHATWORK_API_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        let files = Config::builder()
+            .add_source(File::from(config_dir.join("default")))
+            .add_source(File::from(config_dir.join(run_mode)).required(false))
+            .build()?;
         let s = Config::builder()
-            .add_source(File::with_name("config/default"))
-            .add_source(File::with_name(&format!("config/{}", run_mode)).required(false))
+            .add_source(files.clone())
             .add_source(Environment::with_prefix("APP"))
+            // `Environment`はキーを階層に分割しないため、APIトークンは個別に上書きします
+            .set_override_option("chatwork.api_token", env_token.clone())?
             .build()?;
 
-        Ok(s.try_deserialize()?)
+        let mut settings: Settings = s.try_deserialize()?;
+        // トークンの読み込み（コマンドの実行など）より前に、設定の誤りをまとめて報告します
+        let mut issues = validation::validate(&settings);
+        issues.extend(validation::check_keys(&files.try_deserialize()?));
+        let (errors, warnings): (Vec<_>, Vec<_>) =
+            issues.into_iter().partition(ConfigIssue::is_error);
+        if !errors.is_empty() {
+            let issues: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            return Err(config::ConfigError::Message(t!(
+                "settings.invalid",
+                issues = issues.join("\n  - ")
+            ))
+            .into());
+        }
+
+        if !settings.accounts.is_empty() {
+            settings.resolve_accounts()?;
+        } else if env_token.is_none() {
+            settings.chatwork.resolve_api_token()?;
+        }
+        settings.warnings = [config_dir.join("default"), config_dir.join(run_mode)]
+            .iter()
+            .flat_map(|base| {
+                CONFIG_EXTENSIONS
+                    .iter()
+                    .map(move |extension| base.with_extension(extension))
+            })
+            .filter_map(|path| token::check_config_file(&path))
+            .chain(warnings.iter().map(ToString::to_string))
+            .collect();
+        Ok(settings)
+    }
+
+    /// `accounts`の名前を検証し、アカウントごとにAPIトークンを読み込みます。
+    ///
+    /// # エラー
+    ///
+    /// 名前が空または重複している場合、`[chatwork]`にもトークンが指定されている場合、
+    /// OAuthのトークンを保存するファイルが他のアカウントと同じ場合、
+    /// いずれかのアカウントのトークンを読み込めない場合に`Error`を返します。
+    fn resolve_accounts(&mut self) -> Result<(), Error> {
+        if self.chatwork.token_sources() > 0 {
+            return Err(config::ConfigError::Message(t!("settings.chatwork_and_accounts")).into());
+        }
+        let mut names = HashSet::new();
+        let mut token_paths = HashSet::new();
+        for account in &mut self.accounts {
+            if account.name.trim().is_empty() {
+                return Err(config::ConfigError::Message(t!("settings.account_name_empty")).into());
+            }
+            if !names.insert(account.name.clone()) {
+                return Err(config::ConfigError::Message(t!(
+                    "settings.account_name_duplicated",
+                    account = account.name
+                ))
+                .into());
+            }
+            if let Some(oauth) = &account.chatwork.oauth {
+                // リフレッシュトークンは更新のたびに無効になるため、ファイルを共有できません
+                if !token_paths.insert(oauth.token_path.clone()) {
+                    return Err(config::ConfigError::Message(t!(
+                        "settings.oauth_token_path_duplicated",
+                        account = account.name,
+                        path = oauth.token_path.display()
+                    ))
+                    .into());
+                }
+            }
+            account.chatwork.resolve_api_token().map_err(|e| {
+                // 「設定エラー」の表記が重ならないよう、元のメッセージにアカウント名を付けます
+                let error = match e {
+                    Error::ConfigError(config::ConfigError::Message(message)) => message,
+                    e => e.to_string(),
+                };
+                config::ConfigError::Message(t!(
+                    "settings.account_error",
+                    account = account.name,
+                    error = error
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// アカウントごとに、そのアカウントの設定を`chatwork`に持つ設定を返します。
+    ///
+    /// `accounts`が空の場合は、`[chatwork]`の設定を名前のないアカウントとして返します。
+    /// `accounts`を指定した場合、ダイジェストはアカウント名のサブディレクトリに書き込み、
+    /// `digest_room_id`を指定したアカウントはそのルームに投稿します。
+    pub fn per_account(&self) -> Vec<(Option<String>, Settings)> {
+        if self.accounts.is_empty() {
+            return vec![(None, self.clone())];
+        }
+        self.accounts
+            .iter()
+            .map(|account| {
+                let mut settings = Settings {
+                    chatwork: account.chatwork.clone(),
+                    accounts: Vec::new(),
+                    ..self.clone()
+                };
+                settings.digest.output_dir = self.digest.output_dir.join(&account.name);
+                if account.digest_room_id.is_some() {
+                    settings.digest.post_room_id = account.digest_room_id;
+                }
+                (Some(account.name.clone()), settings)
+            })
+            .collect()
     }
 }
 
@@ -59,16 +1077,24 @@ impl Settings {
 mod tests {
     use super::*;
     use std::fs;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
+    /// 環境変数を書き換えるテストを直列化するためのロックです。
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
     /// テスト用の設定ファイルを作成するヘルパー関数です。
     fn create_test_config(dir: &TempDir, filename: &str, content: &str) {
         let config_path = dir.path().join(filename);
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent).expect("テスト用設定ディレクトリの作成に失敗しました");
+        }
         fs::write(config_path, content).expect("テスト用設定ファイルの作成に失敗しました");
     }
 
     #[test]
     fn test_settings_new_with_mode() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
 
         // デフォルト設定の作成
@@ -94,17 +1120,20 @@ mod tests {
         );
 
         // テスト用の設定ディレクトリを設定
-        env::set_var("CONFIG_DIR", temp_dir.path());
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
 
         let settings = Settings::new_with_mode("development").expect("設定の作成に失敗しました");
 
-        assert_eq!(settings.chatwork.api_token, "dev_token");
+        assert_eq!(settings.chatwork.api_token.expose(), "dev_token");
         assert_eq!(settings.chatwork.exclude_account_ids, vec!["123", "456"]);
         assert_eq!(settings.chatwork.exclude_room_ids, HashSet::from([1, 2, 3]));
+        assert!(settings.journal.enabled);
+        assert_eq!(settings.journal.path, PathBuf::from("data/journal.jsonl"));
     }
 
     #[test]
     fn test_settings_new_with_mode_production() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
 
         // デフォルト設定の作成
@@ -129,17 +1158,18 @@ mod tests {
         );
 
         // テスト用の設定ディレクトリを設定
-        env::set_var("CONFIG_DIR", temp_dir.path());
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
 
         let settings = Settings::new_with_mode("production").expect("設定の作成に失敗しました");
 
-        assert_eq!(settings.chatwork.api_token, "prod_token");
+        assert_eq!(settings.chatwork.api_token.expose(), "prod_token");
         assert_eq!(settings.chatwork.exclude_account_ids, vec!["123", "456"]);
         assert!(settings.chatwork.exclude_room_ids.is_empty());
     }
 
     #[test]
     fn test_settings_new_with_env_override() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
         let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
 
         // デフォルト設定の作成
@@ -154,18 +1184,669 @@ mod tests {
         );
 
         // テスト用の設定ディレクトリを設定
-        env::set_var("CONFIG_DIR", temp_dir.path());
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
 
         // 環境変数でオーバーライド
         env::set_var("APP_CHATWORK_API_TOKEN", "env_token");
 
         let settings = Settings::new_with_mode("development").expect("設定の作成に失敗しました");
 
-        assert_eq!(settings.chatwork.api_token, "env_token");
+        assert_eq!(settings.chatwork.api_token.expose(), "env_token");
         assert_eq!(settings.chatwork.exclude_account_ids, vec!["123", "456"]);
         assert!(settings.chatwork.exclude_room_ids.is_empty());
 
         // クリーンアップ
         env::remove_var("APP_CHATWORK_API_TOKEN");
     }
+
+    #[test]
+    fn test_settings_include_mode() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = ["123"]
+            include_room_ids = [10]
+            include_room_patterns = ["^お知らせ", "bot$"]
+            exclude_room_patterns = ["雑談"]
+        "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        let settings = Settings::new_with_mode("development").expect("設定の作成に失敗しました");
+
+        assert!(settings.chatwork.is_include_mode());
+        assert_eq!(settings.chatwork.include_room_ids, HashSet::from([10]));
+        assert_eq!(settings.chatwork.include_room_patterns.len(), 2);
+        assert!(settings.chatwork.include_room_patterns[0].is_match("お知らせ（全社）"));
+        assert!(settings.chatwork.exclude_room_patterns[0].is_match("雑談部屋"));
+    }
+
+    #[test]
+    fn test_settings_invalid_pattern() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = ["123"]
+            include_room_patterns = ["("]
+        "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        let result = Settings::new_with_mode("development");
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_settings_toall_policy() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = ["123"]
+
+            [[chatwork.room_rules]]
+            room_patterns = ["全社"]
+            toall = { action = "ignore" }
+
+            [[chatwork.room_rules]]
+            room_ids = [20]
+            toall = { action = "block_from_senders", senders = ["777"] }
+        "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        let settings = Settings::new_with_mode("development").expect("設定の作成に失敗しました");
+        let room = |room_id: i32, name: &str| Room {
+            room_id,
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        let chatwork = &settings.chatwork;
+        assert_eq!(chatwork.toall.action, ToallAction::Block);
+        assert_eq!(
+            chatwork.toall_policy_for(&room(10, "全社連絡")).action,
+            ToallAction::Ignore
+        );
+        let policy = chatwork.toall_policy_for(&room(20, "チーム"));
+        assert!(policy.blocks("777"));
+        assert!(!policy.blocks("888"));
+        assert!(chatwork.toall_policy_for(&room(30, "チーム")).blocks("888"));
+    }
+
+    #[test]
+    fn test_settings_notifiers() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = []
+
+            [[notifiers]]
+            type = "webhook"
+            url = "https://example.com/hook"
+            rules = ["mention"]
+            headers = { x-token = "secret" }
+
+            [[notifiers]]
+            type = "email"
+            smtp_host = "smtp.example.com"
+            smtp_port = 2525
+            from = "bot@example.com"
+            to = ["me@example.com"]
+
+            [[notifiers]]
+            type = "command"
+            program = "notify-send"
+            args = ["既読を止めました"]
+            "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        env::remove_var("CONFIG_DIR");
+
+        assert_eq!(settings.notifiers.len(), 3);
+        match &settings.notifiers[0].kind {
+            NotifierKind::Webhook { url, headers } => {
+                assert_eq!(url, "https://example.com/hook");
+                assert_eq!(headers.get("x-token").map(String::as_str), Some("secret"));
+            }
+            kind => panic!("Webhookとして読み込まれませんでした: {:?}", kind),
+        }
+        assert_eq!(settings.notifiers[0].rules, vec!["mention"]);
+        match &settings.notifiers[1].kind {
+            NotifierKind::Email(email) => {
+                assert_eq!(email.smtp_port, Some(2525));
+                assert_eq!(email.security, SmtpSecurity::Starttls);
+                assert_eq!(email.to, vec!["me@example.com"]);
+            }
+            kind => panic!("メールとして読み込まれませんでした: {:?}", kind),
+        }
+        assert!(matches!(
+            &settings.notifiers[2].kind,
+            NotifierKind::Command { program, .. } if program == "notify-send"
+        ));
+    }
+
+    #[test]
+    fn test_settings_logging() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = []
+
+            [logging]
+            level = "debug"
+            format = "json"
+            stderr = false
+
+            [logging.file]
+            path = "C:/Users/me/chatwork.log"
+            rotation = "size"
+            max_size_mb = 5
+
+            [logging.redaction]
+            message_bodies = "excerpt"
+            excerpt_chars = 5
+            "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        env::remove_var("CONFIG_DIR");
+
+        let logging = &settings.logging;
+        assert_eq!(logging.level, "debug");
+        assert_eq!(logging.format, LogFormat::Json);
+        assert!(!logging.stderr);
+        let file = logging
+            .file
+            .as_ref()
+            .expect("ログファイルの設定がありません");
+        assert_eq!(file.path, PathBuf::from("C:/Users/me/chatwork.log"));
+        assert_eq!(file.rotation, LogRotation::Size);
+        assert_eq!(file.max_size_mb, 5);
+        assert_eq!(file.max_files, 7);
+        assert_eq!(logging.redaction.message_bodies, BodyRedaction::Excerpt);
+        assert_eq!(logging.redaction.body("おはようございます"), "おはようご…");
+
+        let defaults = LoggingSettings::default();
+        assert_eq!(defaults.level, "info");
+        assert!(defaults.stderr);
+        assert!(defaults.file.is_none());
+        assert_eq!(
+            defaults.redaction.body("おはようございます"),
+            "（本文は省略しました: 9文字）"
+        );
+    }
+
+    #[test]
+    fn test_settings_language() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            language = "en"
+
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = []
+            "#,
+        );
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        // ヘルプの表示に使用するため、引数の解析前に言語のみを読み込める
+        let language = Settings::configured_language();
+        env::remove_var("CONFIG_DIR");
+
+        assert_eq!(settings.language, Some(Language::En));
+        assert_eq!(language, Some(Language::En));
+    }
+
+    #[test]
+    fn test_settings_accounts() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            account_mode = "concurrent"
+
+            [digest]
+            post_room_id = 100
+
+            [[accounts]]
+            name = "personal"
+            api_token = "personal_token"
+            exclude_account_ids = ["1"]
+
+            [[accounts]]
+            name = "company"
+            api_token_command = { program = "sh", args = ["-c", "echo company_token"] }
+            exclude_account_ids = ["2"]
+            exclude_room_ids = [10]
+            digest_room_id = 200
+
+            [[accounts.room_rules]]
+            room_ids = [20]
+            min_message_age_minutes = 30
+            "#,
+        );
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        assert_eq!(settings.account_mode, AccountMode::Concurrent);
+
+        let accounts = settings.per_account();
+        assert_eq!(accounts.len(), 2);
+        let (name, personal) = &accounts[0];
+        assert_eq!(name.as_deref(), Some("personal"));
+        assert_eq!(personal.chatwork.api_token.expose(), "personal_token");
+        assert_eq!(personal.chatwork.exclude_account_ids, vec!["1"]);
+        assert_eq!(personal.digest.post_room_id, Some(100));
+        assert_eq!(
+            personal.digest.output_dir,
+            PathBuf::from("data/digests/personal")
+        );
+        let (name, company) = &accounts[1];
+        assert_eq!(name.as_deref(), Some("company"));
+        assert_eq!(company.chatwork.api_token.expose(), "company_token");
+        assert!(company.chatwork.exclude_room_ids.contains(&10));
+        assert_eq!(company.chatwork.room_rules.len(), 1);
+        assert_eq!(company.digest.post_room_id, Some(200));
+
+        // アカウント名の重複
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [[accounts]]
+            name = "personal"
+            api_token = "token1"
+            exclude_account_ids = []
+
+            [[accounts]]
+            name = "personal"
+            api_token = "token2"
+            exclude_account_ids = []
+            "#,
+        );
+        assert!(Settings::new_with_mode("development").is_err());
+
+        // [chatwork]と[[accounts]]の両方にトークンを指定
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "default_token"
+            exclude_account_ids = []
+
+            [[accounts]]
+            name = "personal"
+            api_token = "token1"
+            exclude_account_ids = []
+            "#,
+        );
+        assert!(Settings::new_with_mode("development").is_err());
+
+        // トークンを指定していないアカウントは、場所を含むエラーになる
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [[accounts]]
+            name = "personal"
+            exclude_account_ids = []
+            "#,
+        );
+        let error = Settings::new_with_mode("development")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("accounts[0].api_token"), "{}", error);
+
+        // トークンを読み込めないアカウントは、名前を含むエラーになる
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [[accounts]]
+            name = "personal"
+            api_token_file = "missing_token"
+            exclude_account_ids = []
+            "#,
+        );
+        let error = Settings::new_with_mode("development")
+            .unwrap_err()
+            .to_string();
+        env::remove_var("CONFIG_DIR");
+        assert!(error.contains("personal"), "{}", error);
+    }
+
+    #[test]
+    fn test_settings_oauth() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        // OAuthを指定した場合はAPIトークンがなくてもよい
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            exclude_account_ids = []
+
+            [chatwork.oauth]
+            client_id = "client"
+            client_secret = "client_secret"
+            "#,
+        );
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        let oauth = settings.chatwork.oauth.expect("OAuthの設定がありません");
+        assert!(settings.chatwork.api_token.is_empty());
+        assert_eq!(oauth.client_id, "client");
+        assert_eq!(
+            oauth.client_secret.as_ref().unwrap().expose(),
+            "client_secret"
+        );
+        assert_eq!(oauth.redirect_uri(), "http://127.0.0.1:8765/callback");
+        assert_eq!(oauth.token_path, PathBuf::from("data/oauth_token.json"));
+        assert!(oauth.scope.contains("rooms.all:read_write"));
+
+        // APIトークンとOAuthの両方を指定
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "token"
+            exclude_account_ids = []
+            oauth = { client_id = "client" }
+            "#,
+        );
+        assert!(Settings::new_with_mode("development").is_err());
+
+        // 複数のアカウントで同じトークンのファイルを使用
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [[accounts]]
+            name = "personal"
+            exclude_account_ids = []
+            oauth = { client_id = "client1" }
+
+            [[accounts]]
+            name = "company"
+            exclude_account_ids = []
+            oauth = { client_id = "client2" }
+            "#,
+        );
+        let error = Settings::new_with_mode("development")
+            .unwrap_err()
+            .to_string();
+        env::remove_var("CONFIG_DIR");
+        assert!(error.contains("company"), "{}", error);
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        // 誤りは場所とともにまとめて報告し、トークンのコマンドは実行しない
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            exclude_account_ids = ["123", "456 "]
+            exclude_room_ids = [-1]
+            api_token_command = { program = "no-such-password-manager" }
+            "#,
+        );
+        let error = Settings::new_with_mode("development")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("chatwork.exclude_account_ids[1]: "),
+            "{}",
+            error
+        );
+        assert!(error.contains("chatwork.exclude_room_ids: "), "{}", error);
+        assert!(!error.contains("no-such-password-manager"), "{}", error);
+
+        // 不明なキーと文字列で書かれたルームIDは警告にする
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "token"
+            exclude_account_ids = []
+            exclude_room_id = [10]
+            include_room_ids = ["20"]
+            "#,
+        );
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        env::remove_var("CONFIG_DIR");
+        assert_eq!(settings.chatwork.include_room_ids, HashSet::from([20]));
+        let warnings = settings.warnings.join("\n");
+        assert!(
+            warnings.contains("chatwork.exclude_room_id: "),
+            "{}",
+            warnings
+        );
+        assert!(
+            warnings.contains("chatwork.include_room_ids[0]: "),
+            "{}",
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_settings_api_token_sources() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+
+        // コマンドの出力からトークンを読み込む
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            exclude_account_ids = []
+            api_token_command = { program = "sh", args = ["-c", "echo command_token"] }
+            "#,
+        );
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        assert_eq!(settings.chatwork.api_token.expose(), "command_token");
+
+        // 読み込み元が複数指定されている
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "plain_token"
+            exclude_account_ids = []
+            api_token_command = { program = "sh", args = ["-c", "echo command_token"] }
+            "#,
+        );
+        assert!(Settings::new_with_mode("development").is_err());
+
+        // トークンがどこにも指定されていない
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            exclude_account_ids = []
+            "#,
+        );
+        let error = Settings::new_with_mode("development").unwrap_err();
+        assert!(
+            error.to_string().contains("chatwork.api_token"),
+            "{}",
+            error
+        );
+
+        // 空の環境変数はトークンとして扱わず、設定の誤りとして場所とともに報告する
+        env::set_var("APP_CHATWORK_API_TOKEN", "");
+        let error = Settings::new_with_mode("development").unwrap_err();
+        env::remove_var("APP_CHATWORK_API_TOKEN");
+        assert!(
+            error.to_string().contains("chatwork.api_token"),
+            "{}",
+            error
+        );
+
+        // 環境変数のトークンは他の読み込み元より優先される
+        env::set_var("APP_CHATWORK_API_TOKEN", "env_token");
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        env::remove_var("APP_CHATWORK_API_TOKEN");
+        env::remove_var("CONFIG_DIR");
+        assert_eq!(settings.chatwork.api_token.expose(), "env_token");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_settings_api_token_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let token_path = temp_dir.path().join("token");
+        fs::write(&token_path, "file_token\n").unwrap();
+        fs::set_permissions(&token_path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            &format!(
+                r#"
+                [chatwork]
+                api_token_file = "{}"
+                exclude_account_ids = []
+                "#,
+                token_path.display()
+            ),
+        );
+        let config_path = temp_dir.path().join("config/default.toml");
+        fs::set_permissions(&config_path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        env::set_var("CONFIG_DIR", temp_dir.path().join("config"));
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        assert_eq!(settings.chatwork.api_token.expose(), "file_token");
+        // トークン自体は設定ファイルに書かれていないため、警告しない
+        assert!(settings.warnings.is_empty());
+
+        // 他のユーザーも読めるトークンのファイルは拒否する
+        fs::set_permissions(&token_path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(Settings::new_with_mode("development").is_err());
+
+        // 全てのユーザーが読める設定ファイルにトークンが書かれている場合は警告する
+        create_test_config(
+            &temp_dir,
+            "config/default.toml",
+            r#"
+            [chatwork]
+            api_token = "plain_token"
+            exclude_account_ids = []
+            "#,
+        );
+        fs::set_permissions(&config_path, fs::Permissions::from_mode(0o644)).unwrap();
+        let settings =
+            Settings::new_with_mode("development").expect("設定の読み込みに失敗しました");
+        env::remove_var("CONFIG_DIR");
+        assert_eq!(settings.warnings.len(), 1);
+        assert!(settings.warnings[0].contains("default.toml"));
+    }
+
+    #[test]
+    fn test_settings_debug_hides_secrets() {
+        let settings = Settings {
+            chatwork: ChatworkSettings {
+                api_token: "secret_token".into(),
+                exclude_account_ids: vec!["123".to_string()],
+                ..Default::default()
+            },
+            webhook_receiver: Some(WebhookReceiverSettings {
+                listen: default_webhook_listen(),
+                path: default_webhook_path(),
+                token: "webhook_secret".into(),
+                debounce_ms: default_webhook_debounce_ms(),
+            }),
+            ..Default::default()
+        };
+
+        let debug = format!("{:?}", settings);
+        assert!(!debug.contains("secret_token"));
+        assert!(!debug.contains("webhook_secret"));
+        assert!(debug.contains("api_token: ***"));
+        assert!(debug.contains("123"));
+    }
+
+    #[test]
+    fn test_attachment_settings_protects() {
+        let settings = AttachmentSettings {
+            protect: true,
+            name_patterns: vec![Pattern::new(r"(?i)\.(pdf|docx)$").unwrap()],
+            min_size_bytes: 1024,
+        };
+
+        assert!(settings.protects("契約書.PDF", 2048));
+        assert!(!settings.protects("契約書.pdf", 100));
+        assert!(!settings.protects("screenshot.png", 2048));
+        assert!(!AttachmentSettings::default().protects("契約書.pdf", 2048));
+    }
 }
@@ -0,0 +1,123 @@
+//! OpenTelemetryモジュール
+//!
+//! このモジュールは、`otlp`フィーチャーを有効にした場合に、
+//! 実行・ルーム・APIリクエストのスパンをOTLP/HTTPでコレクターへ送信する機能を提供します。
+
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::error::Error;
+use crate::t;
+
+/// トレースに記録するサービス名です。
+const SERVICE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// 終了時に未送信のスパンを送信するために保持するプロバイダーです。
+static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// スパンをOTLP/HTTPで送信するプロバイダーを作成します。
+///
+/// # 引数
+///
+/// * `endpoint` - 送信先のURL（例: `http://localhost:4318/v1/traces`）。
+///   `None`の場合は環境変数 `OTEL_EXPORTER_OTLP_ENDPOINT` などの設定に従います。
+///
+/// # エラー
+///
+/// エクスポーターの作成に失敗した場合、`Error`を返します。
+pub fn tracer_provider(endpoint: Option<&str>) -> Result<SdkTracerProvider, Error> {
+    let mut builder = SpanExporter::builder().with_http();
+    if let Some(endpoint) = endpoint {
+        builder = builder.with_endpoint(endpoint);
+    }
+    let exporter = builder
+        .build()
+        .with_context(|| t!("telemetry.exporter_failed"))?;
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build())
+}
+
+/// プロバイダーにスパンを渡す`tracing`のレイヤーを作成します。
+pub fn layer<S>(provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+}
+
+/// 環境変数 `OTEL_EXPORTER_OTLP_ENDPOINT` が設定されている場合に、スパンを送信するレイヤーを作成します。
+///
+/// ロギングの設定前に呼び出されるため、エクスポーターの作成に失敗した場合は標準エラー出力に表示します。
+pub fn layer_from_env<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT")?;
+    match tracer_provider(None) {
+        Ok(provider) => {
+            let layer = layer(&provider);
+            let _ = PROVIDER.set(provider);
+            Some(layer)
+        }
+        Err(e) => {
+            eprintln!("{}", t!("telemetry.setup_failed", error = e));
+            None
+        }
+    }
+}
+
+/// 未送信のスパンを送信し、プロバイダーを停止します。
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("{}", t!("telemetry.shutdown_failed", error = e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_exports_spans_to_collector() {
+        // コレクターの代わりにOTLP/HTTPのリクエストを受け付けるサーバーを起動する
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(MockServer::start());
+        runtime.block_on(
+            Mock::given(method("POST"))
+                .and(path("/v1/traces"))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&server),
+        );
+
+        let provider = tracer_provider(Some(&format!("{}/v1/traces", server.uri()))).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer(&provider));
+        tracing::subscriber::with_default(subscriber, || {
+            let run = tracing::info_span!("run", run_id = "test");
+            let _run = run.enter();
+            let room = tracing::info_span!("room", room_id = 1);
+            let _room = room.enter();
+            tracing::info!("ルームを処理しました");
+        });
+        provider.force_flush().unwrap();
+
+        let requests = runtime.block_on(server.received_requests()).unwrap();
+        assert!(!requests.is_empty());
+        assert!(!requests[0].body.is_empty());
+        provider.shutdown().unwrap();
+    }
+}
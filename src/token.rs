@@ -0,0 +1,263 @@
+//! APIトークンの読み込みモジュール
+//!
+//! このモジュールは、APIトークンを設定ファイルに平文で書く代わりに、
+//! 所有者のみが読めるファイル、パスワードマネージャーなどのコマンドの出力、
+//! OSのキーリングから読み込む機能を提供します。
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::error::Error;
+use crate::secret::Secret;
+use crate::settings::{KeyringEntry, TokenCommand};
+use crate::t;
+
+/// トークンの読み込みに関するエラーを設定エラーとして作成します。
+fn token_error(message: String) -> Error {
+    Error::ConfigError(config::ConfigError::Message(message))
+}
+
+/// 前後の空白を取り除いたトークンを返します。空の場合は`empty`のメッセージでエラーにします。
+fn non_empty(token: &str, empty: impl FnOnce() -> String) -> Result<Secret, Error> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(token_error(empty()));
+    }
+    Ok(token.into())
+}
+
+/// ファイルからAPIトークンを読み込みます。
+///
+/// Unixでは、所有者以外が読み書きできるファイル（パーミッションが`0600`や`0400`でないもの）は拒否します。
+///
+/// # エラー
+///
+/// ファイルを読み込めない場合、パーミッションが緩すぎる場合、ファイルが空の場合に`Error`を返します。
+pub fn read_file(path: &Path) -> Result<Secret, Error> {
+    let read_failed = |e: std::io::Error| {
+        token_error(t!(
+            "token.file_read_failed",
+            path = path.display(),
+            error = e
+        ))
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .map_err(read_failed)?
+            .permissions()
+            .mode()
+            & 0o777;
+        if mode & 0o077 != 0 {
+            return Err(token_error(t!(
+                "token.file_permissions",
+                path = path.display(),
+                mode = format!("{:o}", mode)
+            )));
+        }
+    }
+
+    let token = fs::read_to_string(path).map_err(read_failed)?;
+    non_empty(&token, || t!("token.file_empty", path = path.display()))
+}
+
+/// コマンドを実行し、標準出力からAPIトークンを読み込みます。
+///
+/// パスワードマネージャーが入力を求められるよう、標準入力と標準エラー出力はそのまま引き継ぎます。
+///
+/// # エラー
+///
+/// コマンドを実行できない場合、コマンドが異常終了した場合、出力が空の場合に`Error`を返します。
+pub fn run_command(command: &TokenCommand) -> Result<Secret, Error> {
+    let output = Command::new(&command.program)
+        .args(&command.args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            token_error(t!(
+                "token.command_start_failed",
+                program = command.program,
+                error = e
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(token_error(t!(
+            "token.command_failed",
+            program = command.program,
+            status = output.status
+        )));
+    }
+    non_empty(&String::from_utf8_lossy(&output.stdout), || {
+        t!("token.command_empty", program = command.program)
+    })
+}
+
+/// OSのキーリングからAPIトークンを読み込みます。
+///
+/// 非同期ランタイムとのデッドロックを避けるため、キーリングへのアクセスは専用のスレッドで行います。
+///
+/// # エラー
+///
+/// キーリングにトークンが保存されていない場合や、キーリングにアクセスできない場合に`Error`を返します。
+#[cfg(feature = "keyring")]
+pub fn read_keyring(entry: &KeyringEntry) -> Result<Secret, Error> {
+    let (service, user) = (entry.service.clone(), entry.user.clone());
+    let token = std::thread::spawn(move || keyring::Entry::new(&service, &user)?.get_password())
+        .join()
+        .map_err(|_| token_error(t!("token.keyring_panicked")))?
+        .map_err(|e| {
+            token_error(t!(
+                "token.keyring_failed",
+                service = entry.service,
+                user = entry.user,
+                error = e
+            ))
+        })?;
+    non_empty(&token, || {
+        t!(
+            "token.keyring_empty",
+            service = entry.service,
+            user = entry.user
+        )
+    })
+}
+
+/// OSのキーリングからAPIトークンを読み込みます。
+///
+/// `keyring`フィーチャーを有効にせずにビルドした場合は、常にエラーになります。
+#[cfg(not(feature = "keyring"))]
+pub fn read_keyring(_entry: &KeyringEntry) -> Result<Secret, Error> {
+    Err(token_error(t!("token.keyring_disabled")))
+}
+
+/// OSのキーリングにAPIトークンを保存します。
+///
+/// # エラー
+///
+/// キーリングにアクセスできない場合や、`keyring`フィーチャーを有効にせずにビルドした場合に`Error`を返します。
+pub fn store_keyring(entry: &KeyringEntry, token: &str) -> Result<(), Error> {
+    let token = non_empty(token, || t!("token.input_empty"))?;
+    #[cfg(feature = "keyring")]
+    {
+        let (service, user) = (entry.service.clone(), entry.user.clone());
+        std::thread::spawn(move || {
+            keyring::Entry::new(&service, &user)?.set_password(token.expose())
+        })
+        .join()
+        .map_err(|_| token_error(t!("token.keyring_panicked")))?
+        .map_err(|e| {
+            token_error(t!(
+                "token.keyring_failed",
+                service = entry.service,
+                user = entry.user,
+                error = e
+            ))
+        })
+    }
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = (entry, token);
+        Err(token_error(t!("token.keyring_disabled")))
+    }
+}
+
+/// 設定ファイルにAPIトークンが書かれていて、全てのユーザーが読める場合に警告を返します。
+///
+/// パーミッションを確認できるのはUnixのみです。それ以外の環境では常に`None`を返します。
+pub fn check_config_file(path: &Path) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path).ok()?.permissions().mode();
+        if mode & 0o004 == 0 {
+            return None;
+        }
+        // コメントアウトされていない`api_token`のキー（`api_token_file`などは除く）を探します
+        let pattern =
+            regex::Regex::new(r#"(?m)^[^#\n]*\bapi_token\b"?\s*[=:]"#).expect("正規表現が不正です");
+        let content = fs::read_to_string(path).ok()?;
+        pattern.is_match(&content).then(|| {
+            t!(
+                "token.config_world_readable",
+                path = path.display(),
+                mode = format!("{:o}", mode & 0o777)
+            )
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_with_mode(path: &Path, content: &str, mode: u32) {
+        fs::write(path, content).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_read_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+
+        write_with_mode(&path, "secret_token\n", 0o600);
+        assert_eq!(read_file(&path).unwrap().expose(), "secret_token");
+
+        write_with_mode(&path, "secret_token\n", 0o644);
+        let error = read_file(&path).unwrap_err().to_string();
+        assert!(error.contains("644"), "{}", error);
+
+        write_with_mode(&path, "\n", 0o400);
+        assert!(read_file(&path).is_err());
+        assert!(read_file(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_run_command() {
+        let command = |script: &str| TokenCommand {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+        };
+
+        assert_eq!(
+            run_command(&command("echo secret_token")).unwrap().expose(),
+            "secret_token"
+        );
+        assert!(run_command(&command("exit 1")).is_err());
+        assert!(run_command(&command("true")).is_err());
+        assert!(run_command(&TokenCommand {
+            program: "no-such-password-manager".to_string(),
+            args: Vec::new(),
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("default.toml");
+
+        write_with_mode(&path, "[chatwork]\napi_token = \"secret\"\n", 0o644);
+        assert!(check_config_file(&path).is_some());
+
+        write_with_mode(&path, "[chatwork]\napi_token = \"secret\"\n", 0o640);
+        assert!(check_config_file(&path).is_none());
+
+        write_with_mode(
+            &path,
+            "[chatwork]\n# api_token = \"secret\"\napi_token_file = \"token\"\n",
+            0o644,
+        );
+        assert!(check_config_file(&path).is_none());
+    }
+}
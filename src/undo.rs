@@ -0,0 +1,163 @@
+//! 既読の取り消し（undo）モジュール
+//!
+//! このモジュールは、ジャーナルに記録された既読位置の移動を元に、
+//! メッセージを未読に戻す処理を提供します。
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use log::{info, warn};
+
+use crate::client::ChatworkClientTrait;
+use crate::error::Error;
+use crate::journal::{new_run_id, Journal, JournalAction, JournalEntry};
+use crate::t;
+
+/// 1つのルームの取り消し結果を表す構造体です。
+#[derive(Debug)]
+pub struct UndoOutcome {
+    /// ルームID
+    pub room_id: i32,
+    /// 未読に戻した最初のメッセージのID（戻せなかった場合は`None`）
+    pub message_id: Option<String>,
+    /// 取り消し後の未読メッセージ数、または失敗した理由
+    pub result: Result<i32, Error>,
+}
+
+/// ジャーナルの記録に従ってメッセージを未読に戻します。
+///
+/// 同じルームに複数の記録がある場合は、最も古い記録の`previous_boundary`まで戻します。
+/// 未読に戻したルームは、`unread`の記録としてジャーナルに追記されます。
+///
+/// # 引数
+///
+/// * `client` - Chatwork APIクライアント
+/// * `journal` - 取り消しの記録を追記するジャーナル
+/// * `entries` - 取り消す既読の記録
+///
+/// # 戻り値
+///
+/// ルームごとの取り消し結果を返します。
+pub async fn undo_entries<T: ChatworkClientTrait>(
+    client: &T,
+    journal: &Journal,
+    entries: &[JournalEntry],
+) -> Vec<UndoOutcome> {
+    // ルームごとに最も古い記録を選ぶ（entriesは古い順に並んでいる）
+    let mut oldest: BTreeMap<i32, &JournalEntry> = BTreeMap::new();
+    for entry in entries {
+        oldest.entry(entry.room_id).or_insert(entry);
+    }
+
+    let run_id = new_run_id();
+    let mut outcomes = Vec::new();
+    for (room_id, entry) in oldest {
+        let Some(previous_boundary) = entry.previous_boundary.clone() else {
+            warn!("{}", t!("undo.no_previous_boundary", room_id = room_id));
+            outcomes.push(UndoOutcome {
+                room_id,
+                message_id: None,
+                result: Err(Error::Other(anyhow::anyhow!(t!(
+                    "undo.no_previous_boundary_error"
+                )))),
+            });
+            continue;
+        };
+
+        info!(
+            "{}",
+            t!(
+                "undo.marking_unread",
+                room_id = room_id,
+                message_id = previous_boundary
+            )
+        );
+        let result = client
+            .mark_message_as_unread(room_id, &previous_boundary)
+            .await;
+
+        if let Ok(status) = &result {
+            let record = JournalEntry {
+                run_id: run_id.clone(),
+                timestamp: Utc::now(),
+                action: JournalAction::Unread,
+                room_id,
+                previous_boundary: Some(entry.new_boundary.clone()),
+                new_boundary: previous_boundary.clone(),
+                consumed: entry.consumed,
+                rules: Vec::new(),
+                read_status: Some(status.clone()),
+                account: entry.account.clone(),
+            };
+            if let Err(e) = journal.append(&record) {
+                warn!("{}", t!("journal.write_failed", error = e));
+            }
+        }
+
+        outcomes.push(UndoOutcome {
+            room_id,
+            message_id: Some(previous_boundary),
+            result: result.map(|status| status.unread_num),
+        });
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MockChatworkClientTrait;
+    use crate::models::ReadStatus;
+    use mockall::predicate::*;
+    use tempfile::TempDir;
+
+    fn entry(room_id: i32, previous: Option<&str>, new: &str) -> JournalEntry {
+        JournalEntry {
+            run_id: "run".to_string(),
+            timestamp: Utc::now(),
+            action: JournalAction::Read,
+            room_id,
+            previous_boundary: previous.map(str::to_string),
+            new_boundary: new.to_string(),
+            consumed: 1,
+            rules: Vec::new(),
+            read_status: None,
+            account: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_undo_entries() {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗しました");
+        let journal = Journal::new(temp_dir.path().join("journal.jsonl"));
+        let mut mock_client = MockChatworkClientTrait::new();
+
+        // 同じルームの記録は最も古い境界まで戻す
+        mock_client
+            .expect_mark_message_as_unread()
+            .with(eq(1), eq("10"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(ReadStatus {
+                    unread_num: 5,
+                    mention_num: 0,
+                })
+            });
+
+        let entries = vec![
+            entry(1, Some("10"), "12"),
+            entry(1, Some("13"), "15"),
+            entry(2, None, "20"),
+        ];
+        let outcomes = undo_entries(&mock_client, &journal, &entries).await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].result.as_ref().unwrap(), &5);
+        assert!(outcomes[1].result.is_err());
+
+        let recorded = journal.entries().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].action, JournalAction::Unread);
+        assert_eq!(recorded[0].new_boundary, "10");
+    }
+}
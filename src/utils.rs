@@ -1,15 +1,43 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use rolling_file::{RollingCondition, RollingFileAppender};
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+use crate::settings::{LogFileSettings, LogFormat, LogRotation, LoggingSettings};
+use crate::t;
+
+/// 出力先ごとのレイヤーを重ねる対象のサブスクライバーです。
+type FilteredRegistry = tracing_subscriber::layer::Layered<EnvFilter, Registry>;
+
 /// ロギングシステムを設定します。
 ///
-/// この関数は環境変数 `RUST_LOG` に基づいてログレベルを設定します。
-/// `RUST_LOG` が設定されていない場合、デフォルトで "info" レベルを使用します。
+/// この関数は設定の`level`に基づいてログレベルを設定し、
+/// 設定に従って標準エラー出力とログファイルの一方または両方にログを出力します。
+/// 環境変数 `RUST_LOG` が設定されている場合は、そのログレベルが優先されます。
+/// 環境変数 `LOG_FORMAT` が "json" または "text" の場合は、設定の`format`より優先されます。
+/// JSONには実行・ルーム・APIリクエストのスパンの情報（`run_id`、`room_id`など）が含まれます。
+///
+/// ログファイルを開けない場合は、その旨を標準エラー出力に表示し、ファイルへの出力なしで続行します。
+///
+/// `otlp` フィーチャーを有効にしてビルドし、環境変数 `OTEL_EXPORTER_OTLP_ENDPOINT` を設定した場合は、
+/// スパンをOpenTelemetry（OTLP/HTTP）で送信します。
 ///
 /// # 使用例
 ///
-/// ```
-/// use your_crate_name::setup_logging;
+/// ```no_run
+/// use chatwork_auto_read::settings::LoggingSettings;
+/// use chatwork_auto_read::utils::setup_logging;
 ///
 /// fn main() {
-///     setup_logging();
+///     setup_logging(&LoggingSettings::default());
 ///     // これ以降のログ出力は設定されたレベルに従います
 /// }
 /// ```
@@ -18,7 +46,246 @@
 ///
 /// この関数は通常、アプリケーションの起動時に一度だけ呼び出されるべきです。
 /// 複数回呼び出すと、予期せぬ動作を引き起こす可能性があります。
-pub fn setup_logging() {
-    let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
+pub fn setup_logging(settings: &LoggingSettings) {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&settings.level))
+        .unwrap_or_else(|e| {
+            eprintln!("{}", t!("logging.invalid_level", error = e));
+            EnvFilter::new("info")
+        });
+    let format = match std::env::var("LOG_FORMAT") {
+        Ok(format) if format.eq_ignore_ascii_case("json") => LogFormat::Json,
+        Ok(format) if format.eq_ignore_ascii_case("text") => LogFormat::Text,
+        _ => settings.format,
+    };
+
+    let mut layers: Vec<Box<dyn Layer<FilteredRegistry> + Send + Sync>> = Vec::new();
+    if settings.stderr {
+        layers.push(format_layer(format, std::io::stderr, true));
+    }
+    if let Some(file) = &settings.file {
+        match open_log_file(file) {
+            Ok(appender) => layers.push(format_layer(format, Mutex::new(appender), false)),
+            Err(e) => eprintln!(
+                "{}",
+                t!(
+                    "logging.open_file_failed",
+                    path = file.path.display(),
+                    error = e
+                )
+            ),
+        }
+    }
+    #[cfg(feature = "otlp")]
+    if let Some(layer) = crate::telemetry::layer_from_env() {
+        layers.push(layer.boxed());
+    }
+
+    // `log`クレートのマクロによる出力も、スパンの情報とともに同じ出力先に記録されます
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .init();
+}
+
+/// 指定された形式でログを書き込むレイヤーを作成します。
+fn format_layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+        LogFormat::Text => layer.boxed(),
+    }
+}
+
+/// 設定に従ってローテーションするログファイルを開きます。
+///
+/// ログファイルのディレクトリが存在しない場合は作成します。
+///
+/// # エラー
+///
+/// ディレクトリの作成やファイルのオープンに失敗した場合、`std::io::Error`を返します。
+pub fn open_log_file(
+    settings: &LogFileSettings,
+) -> std::io::Result<RollingFileAppender<LogRotationCondition>> {
+    if let Some(dir) = settings.path.parent() {
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(dir)?;
+        }
+    }
+    let condition = LogRotationCondition::new(
+        settings.rotation,
+        settings.max_size_mb.saturating_mul(1024 * 1024),
+        &settings.path,
+    );
+    // 書き込んだログがすぐにファイルに反映されるよう、バッファリングしません
+    RollingFileAppender::new_with_buffer_capacity(&settings.path, condition, settings.max_files, 0)
+}
+
+/// ログファイルをローテーションする条件です。
+#[derive(Debug, Clone)]
+pub struct LogRotationCondition {
+    /// ローテーションの方法
+    rotation: LogRotation,
+    /// `LogRotation::Size`の場合にローテーションするファイルの大きさ（バイト）
+    max_size: u64,
+    /// 最後にログを書き込んだ日時
+    last_write: Option<DateTime<Local>>,
+}
+
+impl LogRotationCondition {
+    /// 新しい`LogRotationCondition`インスタンスを作成します。
+    ///
+    /// 1回の実行ごとに起動する場合でも日付でローテーションできるよう、
+    /// 既存のログファイルの更新日時を最後に書き込んだ日時とみなします。
+    pub fn new(rotation: LogRotation, max_size: u64, path: &Path) -> Self {
+        let last_write = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Local>::from);
+        Self {
+            rotation,
+            max_size,
+            last_write,
+        }
+    }
+}
+
+impl RollingCondition for LogRotationCondition {
+    fn should_rollover(&mut self, now: &DateTime<Local>, current_filesize: u64) -> bool {
+        let rollover = match self.rotation {
+            LogRotation::Daily => self
+                .last_write
+                .is_some_and(|last| last.date_naive() != now.date_naive()),
+            LogRotation::Size => current_filesize > 0 && current_filesize >= self.max_size,
+            LogRotation::Never => false,
+        };
+        self.last_write = Some(*now);
+        rollover
+    }
+}
+
+/// メッセージ本文の最初の空でない行を、指定した文字数までに切り詰めて返します。
+///
+/// 切り詰めた場合は末尾に"…"を付けます。
+///
+/// # 使用例
+///
+/// ```
+/// use chatwork_auto_read::utils::excerpt;
+///
+/// assert_eq!(excerpt("\nおはようございます\n本日の予定", 5), "おはようご…");
+/// assert_eq!(excerpt("了解です", 10), "了解です");
+/// ```
+pub fn excerpt(body: &str, max_chars: usize) -> String {
+    let line = body
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() > max_chars {
+        let mut truncated: String = line.chars().take(max_chars).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        line.to_string()
+    }
+}
+
+/// HTMLの本文や属性値に埋め込めるよう、特殊文字をエスケープします。
+///
+/// # 使用例
+///
+/// ```
+/// use chatwork_auto_read::utils::escape_html;
+///
+/// assert_eq!(escape_html("<a href=\"x\">A&B</a>"), "&lt;a href=&quot;x&quot;&gt;A&amp;B&lt;/a&gt;");
+/// ```
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    fn file_settings(path: PathBuf, rotation: LogRotation) -> LogFileSettings {
+        LogFileSettings {
+            path,
+            rotation,
+            max_size_mb: 1,
+            max_files: 2,
+        }
+    }
+
+    #[test]
+    fn test_log_file_rotates_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("app.log");
+        let mut appender = open_log_file(&file_settings(path.clone(), LogRotation::Size)).unwrap();
+
+        let line = vec![b'a'; 1024 * 1024];
+        let now = Local::now();
+        for _ in 0..4 {
+            appender.write_with_datetime(&line, &now).unwrap();
+        }
+
+        // 古いファイルは`max_files`個まで残り、それより古いものは削除される
+        assert_eq!(fs::metadata(&path).unwrap().len(), 1024 * 1024);
+        assert!(path.with_extension("log.1").exists());
+        assert!(path.with_extension("log.2").exists());
+        assert!(!path.with_extension("log.3").exists());
+    }
+
+    #[test]
+    fn test_log_file_rotates_daily() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let mut appender = open_log_file(&file_settings(path.clone(), LogRotation::Daily)).unwrap();
+
+        let morning = Local.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let evening = Local.with_ymd_and_hms(2024, 5, 1, 21, 0, 0).unwrap();
+        let next_day = Local.with_ymd_and_hms(2024, 5, 2, 9, 0, 0).unwrap();
+        appender.write_with_datetime(b"1\n", &morning).unwrap();
+        appender.write_with_datetime(b"2\n", &evening).unwrap();
+        appender.write_with_datetime(b"3\n", &next_day).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(path.with_extension("log.1")).unwrap(),
+            "1\n2\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3\n");
+    }
+
+    #[test]
+    fn test_daily_rotation_uses_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "前回の実行\n").unwrap();
+
+        // 既存のファイルは今日更新されたため、今日のうちはローテーションしない
+        let mut condition = LogRotationCondition::new(LogRotation::Daily, 0, &path);
+        assert!(!condition.should_rollover(&Local::now(), 1));
+        let tomorrow = Local::now() + chrono::Duration::days(1);
+        assert!(condition.should_rollover(&tomorrow, 1));
+
+        let mut never = LogRotationCondition::new(LogRotation::Never, 0, &path);
+        assert!(!never.should_rollover(&tomorrow, u64::MAX));
+    }
 }
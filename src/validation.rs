@@ -0,0 +1,776 @@
+//! 設定の検証モジュール
+//!
+//! このモジュールは、読み込んだ設定の意味的な誤り（空白を含むアカウントID、正でないルームIDなど）や
+//! 設定ファイルの不明なキーを、誤りのある場所（例: `accounts[1].exclude_account_ids[0]`）とともに検出する機能、
+//! Chatwork APIで参照先のルームやアカウントが存在するかを確認する機能、
+//! エディタの補完に使用するJSON Schemaを生成する機能を提供します。
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::net::SocketAddr;
+
+use schemars::schema::RootSchema;
+use serde_json::Value;
+
+use crate::client::ChatworkClientTrait;
+use crate::models::Account;
+use crate::settings::{ChatworkSettings, NotifierKind, Settings, ToallAction, ToallPolicy};
+use crate::t;
+
+/// 問題の重大度です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// 設定の読み込みを中止する誤り
+    Error,
+    /// 動作はするものの、意図と異なる可能性がある設定
+    Warning,
+}
+
+/// 設定の検証で見つかった問題です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// 問題の重大度
+    pub severity: Severity,
+    /// 問題のある設定の場所（例: `chatwork.exclude_account_ids[0]`）
+    pub location: String,
+    /// 問題の内容
+    pub message: String,
+}
+
+impl ConfigIssue {
+    /// 誤りを作成します。
+    pub fn error(location: impl Into<String>, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            location: location.into(),
+            message,
+        }
+    }
+
+    /// 警告を作成します。
+    pub fn warning(location: impl Into<String>, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location: location.into(),
+            message,
+        }
+    }
+
+    /// 誤り（設定の読み込みを中止するもの）かどうかを返します。
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// 設定ファイルのJSON Schemaを返します。
+///
+/// 説明には各項目のドキュメントコメントを使用します。
+pub fn schema() -> RootSchema {
+    schemars::schema_for!(Settings)
+}
+
+/// 設定ファイルのJSON Schemaを整形したJSONとして返します。
+///
+/// ```
+/// use chatwork_auto_read::validation::schema_json;
+///
+/// let schema: serde_json::Value = serde_json::from_str(&schema_json()).unwrap();
+/// assert!(schema["properties"]["chatwork"].is_object());
+/// ```
+pub fn schema_json() -> String {
+    let mut json = serde_json::to_string_pretty(&schema()).expect("JSON Schemaを出力できません");
+    json.push('\n');
+    json
+}
+
+/// 設定の意味的な誤りを検証します。
+///
+/// トークンの読み込みより前に、設定ファイルと環境変数から読み込んだ値に対して実行します。
+pub fn validate(settings: &Settings) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    if settings.accounts.is_empty() {
+        check_chatwork(&settings.chatwork, "chatwork", &mut issues);
+    }
+    for (index, account) in settings.accounts.iter().enumerate() {
+        let location = format!("accounts[{}]", index);
+        check_chatwork(&account.chatwork, &location, &mut issues);
+        if let Some(room_id) = account.digest_room_id {
+            check_room_id(
+                room_id,
+                &format!("{}.digest_room_id", location),
+                &mut issues,
+            );
+        }
+    }
+
+    let digest = &settings.digest;
+    if let Some(room_id) = digest.post_room_id {
+        check_room_id(room_id, "digest.post_room_id", &mut issues);
+    }
+    if digest.enabled && !digest.write_file && !digest.post_to_chatwork {
+        issues.push(ConfigIssue::warning(
+            "digest",
+            t!("validation.digest_no_output"),
+        ));
+    }
+
+    if settings.daemon.interval_secs == 0 {
+        issues.push(ConfigIssue::warning(
+            "daemon.interval_secs",
+            t!("validation.interval_zero"),
+        ));
+    }
+    if let Some(listen) = &settings.daemon.listen {
+        check_listen(listen, "daemon.listen", &mut issues);
+    }
+    if let Some(receiver) = &settings.webhook_receiver {
+        check_listen(&receiver.listen, "webhook_receiver.listen", &mut issues);
+        if !receiver.path.starts_with('/') {
+            issues.push(ConfigIssue::error(
+                "webhook_receiver.path",
+                t!("validation.path_not_absolute", path = receiver.path),
+            ));
+        }
+    }
+    if let Some(webhook) = &settings.event_webhook {
+        check_url(&webhook.url, "event_webhook.url", &mut issues);
+    }
+    for (index, notifier) in settings.notifiers.iter().enumerate() {
+        let location = format!("notifiers[{}]", index);
+        match &notifier.kind {
+            NotifierKind::Webhook { url, .. } => {
+                check_url(url, &format!("{}.url", location), &mut issues)
+            }
+            NotifierKind::Email(email) => {
+                check_mailbox(&email.from, &format!("{}.from", location), &mut issues);
+                if email.to.is_empty() {
+                    issues.push(ConfigIssue::error(
+                        format!("{}.to", location),
+                        t!("validation.empty_list"),
+                    ));
+                }
+                for (index, to) in email.to.iter().enumerate() {
+                    check_mailbox(to, &format!("{}.to[{}]", location, index), &mut issues);
+                }
+            }
+            NotifierKind::Command { program, .. } => {
+                check_not_blank(program, &format!("{}.program", location), &mut issues)
+            }
+        }
+    }
+    issues
+}
+
+/// `[chatwork]`または`[[accounts]]`の1つのアカウントの設定を検証します。
+fn check_chatwork(chatwork: &ChatworkSettings, location: &str, issues: &mut Vec<ConfigIssue>) {
+    let token = chatwork.api_token.expose();
+    if chatwork.token_sources() == 0 {
+        issues.push(ConfigIssue::error(
+            format!("{}.api_token", location),
+            t!("token.missing"),
+        ));
+    } else if !token.is_empty() && token.trim() != token {
+        issues.push(ConfigIssue::error(
+            format!("{}.api_token", location),
+            t!("validation.token_whitespace"),
+        ));
+    }
+    if let Some(command) = &chatwork.api_token_command {
+        check_not_blank(
+            &command.program,
+            &format!("{}.api_token_command.program", location),
+            issues,
+        );
+    }
+    if let Some(oauth) = &chatwork.oauth {
+        check_not_blank(
+            &oauth.client_id,
+            &format!("{}.oauth.client_id", location),
+            issues,
+        );
+        if oauth.redirect_port == 0 {
+            issues.push(ConfigIssue::error(
+                format!("{}.oauth.redirect_port", location),
+                t!("validation.port_zero"),
+            ));
+        }
+    }
+
+    for (index, account_id) in chatwork.exclude_account_ids.iter().enumerate() {
+        check_account_id(
+            account_id,
+            &format!("{}.exclude_account_ids[{}]", location, index),
+            issues,
+        );
+    }
+    for (index, account_id) in chatwork.protect_senders.iter().enumerate() {
+        check_account_id(
+            account_id,
+            &format!("{}.protect_senders[{}]", location, index),
+            issues,
+        );
+    }
+    check_toall(&chatwork.toall, &format!("{}.toall", location), issues);
+
+    for (name, room_ids) in [
+        ("exclude_room_ids", &chatwork.exclude_room_ids),
+        ("include_room_ids", &chatwork.include_room_ids),
+    ] {
+        for &room_id in sorted(room_ids) {
+            check_room_id(room_id, &format!("{}.{}", location, name), issues);
+        }
+    }
+    for &room_id in sorted(&chatwork.include_room_ids) {
+        if chatwork.exclude_room_ids.contains(&room_id) {
+            issues.push(ConfigIssue::warning(
+                format!("{}.include_room_ids", location),
+                t!("validation.room_included_and_excluded", room_id = room_id),
+            ));
+        }
+    }
+
+    for (index, rule) in chatwork.room_rules.iter().enumerate() {
+        let location = format!("{}.room_rules[{}]", location, index);
+        if rule.room_ids.is_empty() && rule.room_patterns.is_empty() {
+            issues.push(ConfigIssue::warning(
+                location.clone(),
+                t!("validation.rule_matches_nothing"),
+            ));
+        }
+        for &room_id in sorted(&rule.room_ids) {
+            check_room_id(room_id, &format!("{}.room_ids", location), issues);
+        }
+        if let Some(toall) = &rule.toall {
+            check_toall(toall, &format!("{}.toall", location), issues);
+        }
+    }
+}
+
+/// `[toall]`ポリシーの送信者のアカウントIDを検証します。
+fn check_toall(toall: &ToallPolicy, location: &str, issues: &mut Vec<ConfigIssue>) {
+    if toall.action == ToallAction::BlockFromSenders && toall.senders.is_empty() {
+        issues.push(ConfigIssue::warning(
+            format!("{}.senders", location),
+            t!("validation.senders_empty"),
+        ));
+    }
+    for (index, account_id) in toall.senders.iter().enumerate() {
+        check_account_id(
+            account_id,
+            &format!("{}.senders[{}]", location, index),
+            issues,
+        );
+    }
+}
+
+/// アカウントIDが数字のみで構成されているかを検証します。
+///
+/// APIが返すアカウントIDと文字列として比較するため、空白などが含まれていると一致しません。
+fn check_account_id(account_id: &str, location: &str, issues: &mut Vec<ConfigIssue>) {
+    let message = if account_id.is_empty() {
+        t!("validation.account_id_empty")
+    } else if account_id.chars().any(char::is_whitespace) {
+        t!("validation.account_id_whitespace", account_id = account_id)
+    } else if !account_id.chars().all(|c| c.is_ascii_digit()) {
+        t!("validation.account_id_not_numeric", account_id = account_id)
+    } else {
+        return;
+    };
+    issues.push(ConfigIssue::error(location, message));
+}
+
+/// ルームIDが正の数かを検証します。
+fn check_room_id(room_id: i32, location: &str, issues: &mut Vec<ConfigIssue>) {
+    if room_id <= 0 {
+        issues.push(ConfigIssue::error(
+            location,
+            t!("validation.room_id_not_positive", room_id = room_id),
+        ));
+    }
+}
+
+/// 待ち受けるアドレスが`IPアドレス:ポート`の形式かを検証します。
+fn check_listen(listen: &str, location: &str, issues: &mut Vec<ConfigIssue>) {
+    if listen.parse::<SocketAddr>().is_err() {
+        issues.push(ConfigIssue::error(
+            location,
+            t!("validation.invalid_listen", listen = listen),
+        ));
+    }
+}
+
+/// URLがHTTPまたはHTTPSのURLかを検証します。
+fn check_url(url: &str, location: &str, issues: &mut Vec<ConfigIssue>) {
+    let valid = reqwest::Url::parse(url)
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false);
+    if !valid {
+        issues.push(ConfigIssue::error(
+            location,
+            t!("validation.invalid_url", url = url),
+        ));
+    }
+}
+
+/// メールアドレスとして解釈できるかを検証します。
+fn check_mailbox(address: &str, location: &str, issues: &mut Vec<ConfigIssue>) {
+    if address.parse::<lettre::message::Mailbox>().is_err() {
+        issues.push(ConfigIssue::error(
+            location,
+            t!("validation.invalid_mailbox", address = address),
+        ));
+    }
+}
+
+/// 空または空白のみでないかを検証します。
+fn check_not_blank(value: &str, location: &str, issues: &mut Vec<ConfigIssue>) {
+    if value.trim().is_empty() {
+        issues.push(ConfigIssue::error(location, t!("validation.blank")));
+    }
+}
+
+/// 問題の報告順が実行ごとに変わらないよう、ルームIDを昇順に並べます。
+fn sorted(room_ids: &HashSet<i32>) -> Vec<&i32> {
+    let mut room_ids: Vec<_> = room_ids.iter().collect();
+    room_ids.sort();
+    room_ids
+}
+
+/// 設定ファイルの内容をJSON Schemaと照らし合わせ、不明なキーと文字列で書かれた数値を検出します。
+///
+/// 不明なキーは読み込み時に無視されるため、キー名の誤りに気付けるよう警告にします。
+///
+/// # 引数
+///
+/// * `raw` - 設定ファイルから読み込んだ値（環境変数による上書きを含まないもの）
+pub fn check_keys(raw: &Value) -> Vec<ConfigIssue> {
+    let schema = serde_json::to_value(schema()).expect("JSON Schemaを変換できません");
+    let mut issues = Vec::new();
+    check_value(raw, &[&schema], &schema, "", &mut issues);
+    issues
+}
+
+/// 値を、候補となるスキーマのいずれかと照らし合わせます。
+fn check_value(
+    value: &Value,
+    schemas: &[&Value],
+    root: &Value,
+    location: &str,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    let alternatives = alternatives(schemas, root);
+    match value {
+        Value::Object(map) => {
+            // 任意のキーを指定できる表（`headers`など）は、キーを検証しません
+            let additional: Vec<&Value> = alternatives
+                .iter()
+                .filter_map(|schema| schema.get("additionalProperties"))
+                .filter(|schema| schema.is_object())
+                .collect();
+            let mut properties: BTreeMap<&str, Vec<&Value>> = BTreeMap::new();
+            for schema in &alternatives {
+                if let Some(Value::Object(props)) = schema.get("properties") {
+                    for (key, schema) in props {
+                        properties.entry(key).or_default().push(schema);
+                    }
+                }
+            }
+            if properties.is_empty() && additional.is_empty() {
+                return;
+            }
+
+            for (key, value) in map {
+                let location = if location.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", location, key)
+                };
+                match properties.get(key.as_str()) {
+                    Some(schemas) => check_value(value, schemas, root, &location, issues),
+                    None if !additional.is_empty() => {
+                        check_value(value, &additional, root, &location, issues)
+                    }
+                    None => {
+                        let message = match similar_key(key, properties.keys().copied()) {
+                            Some(similar) => {
+                                t!("validation.unknown_key_similar", similar = similar)
+                            }
+                            None => t!("validation.unknown_key"),
+                        };
+                        issues.push(ConfigIssue::warning(location, message));
+                    }
+                }
+            }
+        }
+        Value::Array(values) => {
+            let items: Vec<&Value> = alternatives
+                .iter()
+                .filter_map(|schema| schema.get("items"))
+                .collect();
+            if items.is_empty() {
+                return;
+            }
+            for (index, value) in values.iter().enumerate() {
+                let location = format!("{}[{}]", location, index);
+                check_value(value, &items, root, &location, issues);
+            }
+        }
+        Value::String(text) => {
+            let types: Vec<&str> = alternatives
+                .iter()
+                .flat_map(|schema| match schema.get("type") {
+                    Some(Value::String(kind)) => vec![kind.as_str()],
+                    Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                })
+                .collect();
+            let numeric = types
+                .iter()
+                .any(|kind| matches!(*kind, "integer" | "number"));
+            if numeric && !types.contains(&"string") {
+                issues.push(ConfigIssue::warning(
+                    location,
+                    t!("validation.number_as_string", value = text),
+                ));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `$ref`を解決し、`allOf`・`anyOf`・`oneOf`の候補を展開したスキーマの一覧を返します。
+fn alternatives<'a>(schemas: &[&'a Value], root: &'a Value) -> Vec<&'a Value> {
+    let mut result = Vec::new();
+    let mut pending: Vec<&Value> = schemas.to_vec();
+    while let Some(schema) = pending.pop() {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(target) = reference
+                .strip_prefix('#')
+                .and_then(|pointer| root.pointer(pointer))
+            {
+                pending.push(target);
+            }
+        }
+        for key in ["allOf", "anyOf", "oneOf"] {
+            if let Some(Value::Array(subschemas)) = schema.get(key) {
+                pending.extend(subschemas);
+            }
+        }
+        result.push(schema);
+    }
+    result
+}
+
+/// 不明なキーに似た（タイプミスと思われる）既知のキーを返します。
+fn similar_key<'a>(key: &str, known: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    known
+        .map(|candidate| (strsim::jaro_winkler(key, candidate), candidate))
+        .filter(|(similarity, _)| *similarity > 0.85)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Chatwork APIで、トークンが有効か、設定で参照しているルームとアカウントが存在するかを確認します。
+///
+/// 対象のルーム（`include_room_ids`）とダイジェストの投稿先のルームが参加しているルームにない場合は誤り、
+/// 除外やルームごとの個別設定で参照しているルームやアカウントが見つからない場合は警告にします。
+/// アカウントは自分とコンタクトの中から探すため、コンタクトでないルームのメンバーは見つからない場合があります。
+///
+/// # 引数
+///
+/// * `client` - 確認するアカウントのクライアント
+/// * `chatwork` - 確認するアカウントの設定
+/// * `location` - 問題の場所に使用する設定の場所（`chatwork`または`accounts[0]`など）
+/// * `digest_room` - ダイジェストの投稿先のルームIDと、その設定の場所
+///
+/// # 戻り値
+///
+/// 見つかった問題と、トークンが有効な場合は認証されたアカウントの情報を返します。
+pub async fn check_api(
+    client: &impl ChatworkClientTrait,
+    chatwork: &ChatworkSettings,
+    location: &str,
+    digest_room: Option<(i32, String)>,
+) -> (Vec<ConfigIssue>, Option<Account>) {
+    let mut issues = Vec::new();
+    let me = match client.fetch_me().await {
+        Ok(me) => me,
+        Err(e) => {
+            issues.push(ConfigIssue::error(
+                location,
+                t!("validation.token_rejected", error = e),
+            ));
+            return (issues, None);
+        }
+    };
+
+    match client.fetch_rooms().await {
+        Ok(rooms) => {
+            let joined: HashSet<i32> = rooms.iter().map(|room| room.room_id).collect();
+            let mut check = |room_id: i32, location: String, required: bool| {
+                if !joined.contains(&room_id) {
+                    let message = t!("validation.room_not_found", room_id = room_id);
+                    issues.push(if required {
+                        ConfigIssue::error(location, message)
+                    } else {
+                        ConfigIssue::warning(location, message)
+                    });
+                }
+            };
+            for &room_id in sorted(&chatwork.include_room_ids) {
+                check(room_id, format!("{}.include_room_ids", location), true);
+            }
+            for &room_id in sorted(&chatwork.exclude_room_ids) {
+                check(room_id, format!("{}.exclude_room_ids", location), false);
+            }
+            for (index, rule) in chatwork.room_rules.iter().enumerate() {
+                for &room_id in sorted(&rule.room_ids) {
+                    let location = format!("{}.room_rules[{}].room_ids", location, index);
+                    check(room_id, location, false);
+                }
+            }
+            if let Some((room_id, location)) = digest_room {
+                check(room_id, location, true);
+            }
+        }
+        Err(e) => issues.push(ConfigIssue::error(
+            location,
+            t!("validation.rooms_failed", error = e),
+        )),
+    }
+
+    let mut account_ids: Vec<(String, &String)> = chatwork
+        .exclude_account_ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (format!("{}.exclude_account_ids[{}]", location, index), id))
+        .chain(
+            chatwork
+                .protect_senders
+                .iter()
+                .enumerate()
+                .map(|(index, id)| (format!("{}.protect_senders[{}]", location, index), id)),
+        )
+        .collect();
+    let policies = std::iter::once((format!("{}.toall", location), &chatwork.toall)).chain(
+        chatwork
+            .room_rules
+            .iter()
+            .enumerate()
+            .filter_map(|(index, rule)| {
+                let location = format!("{}.room_rules[{}].toall", location, index);
+                rule.toall.as_ref().map(|toall| (location, toall))
+            }),
+    );
+    for (policy_location, toall) in policies {
+        for (index, id) in toall.senders.iter().enumerate() {
+            account_ids.push((format!("{}.senders[{}]", policy_location, index), id));
+        }
+    }
+    if !account_ids.is_empty() {
+        match client.fetch_contacts().await {
+            Ok(contacts) => {
+                let known: HashSet<String> = contacts
+                    .iter()
+                    .chain(std::iter::once(&me))
+                    .map(|account| account.account_id.to_string())
+                    .collect();
+                for (location, id) in account_ids {
+                    if !known.contains(id) {
+                        issues.push(ConfigIssue::warning(
+                            location,
+                            t!("validation.account_not_found", account_id = id),
+                        ));
+                    }
+                }
+            }
+            Err(e) => issues.push(ConfigIssue::warning(
+                location,
+                t!("validation.contacts_failed", error = e),
+            )),
+        }
+    }
+    (issues, Some(me))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MockChatworkClientTrait;
+    use crate::models::Room;
+    use crate::settings::RoomRule;
+
+    fn locations(issues: &[ConfigIssue]) -> Vec<(&str, Severity)> {
+        issues
+            .iter()
+            .map(|issue| (issue.location.as_str(), issue.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut settings = Settings::default();
+        settings.daemon.interval_secs = 60;
+        settings.chatwork.api_token = "token".into();
+        settings.chatwork.exclude_account_ids =
+            vec!["123".to_string(), "456 ".to_string(), "abc".to_string()];
+        settings.chatwork.protect_senders = vec!["".to_string()];
+        settings.chatwork.exclude_room_ids = HashSet::from([10, 0]);
+        settings.chatwork.include_room_ids = HashSet::from([10]);
+        settings.chatwork.room_rules = vec![RoomRule::default()];
+        settings.daemon.listen = Some("localhost".to_string());
+
+        let issues = validate(&settings);
+        assert_eq!(
+            locations(&issues),
+            vec![
+                ("chatwork.exclude_account_ids[1]", Severity::Error),
+                ("chatwork.exclude_account_ids[2]", Severity::Error),
+                ("chatwork.protect_senders[0]", Severity::Error),
+                ("chatwork.exclude_room_ids", Severity::Error),
+                ("chatwork.include_room_ids", Severity::Warning),
+                ("chatwork.room_rules[0]", Severity::Warning),
+                ("daemon.listen", Severity::Error),
+            ]
+        );
+        assert!(issues[0].to_string().contains("空白"), "{}", issues[0]);
+    }
+
+    #[test]
+    fn test_validate_api_token() {
+        // トークンの読み込み元がない場合は、トークンの場所とともに報告する
+        let mut settings = Settings::default();
+        settings.daemon.interval_secs = 60;
+        let issues = validate(&settings);
+        assert_eq!(
+            locations(&issues),
+            vec![("chatwork.api_token", Severity::Error)]
+        );
+
+        settings.chatwork.api_token_file = Some("token".into());
+        assert!(validate(&settings).is_empty());
+
+        settings.chatwork.api_token_file = None;
+        settings.accounts = vec![crate::settings::AccountSettings {
+            name: "company".to_string(),
+            digest_room_id: None,
+            chatwork: ChatworkSettings::default(),
+        }];
+        let issues = validate(&settings);
+        assert_eq!(
+            locations(&issues),
+            vec![("accounts[0].api_token", Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn test_check_keys() {
+        let raw = serde_json::json!({
+            "chatwork": {
+                "exclude_account_ids": ["123"],
+                "exclude_room_id": [10],
+                "include_room_ids": ["20"],
+                "room_rules": [{ "room_ids": [30], "toall": { "action": "ignore", "sender": [] } }],
+            },
+            "accounts": [{ "name": "company", "api_token_file": "token", "unknown": 1 }],
+            "notifiers": [{ "type": "webhook", "url": "https://example.com", "headers": { "X-Custom": "1" } }],
+            "digest": { "enabled": true },
+        });
+
+        let issues = check_keys(&raw);
+        assert_eq!(
+            locations(&issues),
+            vec![
+                ("accounts[0].unknown", Severity::Warning),
+                ("chatwork.exclude_room_id", Severity::Warning),
+                ("chatwork.include_room_ids[0]", Severity::Warning),
+                ("chatwork.room_rules[0].toall.sender", Severity::Warning),
+            ]
+        );
+        // タイプミスと思われるキーには、正しいキーを提示する
+        assert!(
+            issues[1].message.contains("exclude_room_ids"),
+            "{}",
+            issues[1]
+        );
+    }
+
+    #[test]
+    fn test_schema_file_is_up_to_date() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("config.schema.json");
+        assert!(
+            std::fs::read_to_string(path).unwrap() == schema_json(),
+            "config.schema.jsonが古いため、`cargo run -- config-schema > config.schema.json`で再生成してください"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_api() {
+        let mut chatwork = ChatworkSettings {
+            exclude_account_ids: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            include_room_ids: HashSet::from([10, 99]),
+            exclude_room_ids: HashSet::from([98]),
+            protect_senders: vec!["5".to_string()],
+            ..Default::default()
+        };
+        chatwork.toall.senders = vec!["4".to_string()];
+
+        let mut client = MockChatworkClientTrait::new();
+        client.expect_fetch_me().returning(|| {
+            Ok(Account {
+                account_id: 1,
+                name: "自分".to_string(),
+            })
+        });
+        client.expect_fetch_rooms().returning(|| {
+            Ok(vec![Room {
+                room_id: 10,
+                ..Default::default()
+            }])
+        });
+        client.expect_fetch_contacts().returning(|| {
+            Ok(vec![Account {
+                account_id: 2,
+                name: "同僚".to_string(),
+            }])
+        });
+
+        let (issues, me) = check_api(
+            &client,
+            &chatwork,
+            "chatwork",
+            Some((97, "digest.post_room_id".to_string())),
+        )
+        .await;
+        assert_eq!(me.unwrap().name, "自分");
+        assert_eq!(
+            locations(&issues),
+            vec![
+                ("chatwork.include_room_ids", Severity::Error),
+                ("chatwork.exclude_room_ids", Severity::Warning),
+                ("digest.post_room_id", Severity::Error),
+                ("chatwork.exclude_account_ids[2]", Severity::Warning),
+                ("chatwork.protect_senders[0]", Severity::Warning),
+                ("chatwork.toall.senders[0]", Severity::Warning),
+            ]
+        );
+
+        // トークンが無効な場合は、それ以上確認しない
+        let mut client = MockChatworkClientTrait::new();
+        client.expect_fetch_me().returning(|| {
+            Err(crate::Error::ApiError(
+                reqwest::StatusCode::UNAUTHORIZED,
+                "Invalid API token".to_string(),
+            ))
+        });
+        let (issues, me) = check_api(&client, &chatwork, "accounts[0]", None).await;
+        assert!(me.is_none());
+        assert_eq!(locations(&issues), vec![("accounts[0]", Severity::Error)]);
+    }
+}
@@ -0,0 +1,344 @@
+//! Webhook受信モジュール
+//!
+//! このモジュールは、ChatworkのWebhook（`message_created` / `mention_to_me`）を受信し、
+//! 署名を検証したうえで、メッセージを受信したルームを処理待ちとして記録する機能を提供します。
+//! ルーム一覧をポーリングせずに、メッセージが届いたルームだけを処理するために使用します。
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Context;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use sha2::Sha256;
+use tokio::sync::Notify;
+
+use crate::error::Error;
+use crate::t;
+
+/// Chatworkが本文の署名を設定するヘッダーの名前です。
+pub const SIGNATURE_HEADER: &str = "X-ChatWorkWebhookSignature";
+
+/// ChatworkのWebhookリクエストの本文です。
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookRequest {
+    /// イベントの種類（`message_created` / `message_updated` / `mention_to_me`）
+    pub webhook_event_type: String,
+    /// イベントの発生日時（UNIX時間）
+    #[serde(default)]
+    pub webhook_event_time: i64,
+    /// イベントの内容
+    pub webhook_event: WebhookEvent,
+}
+
+/// Webhookのイベントの内容です。
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookEvent {
+    /// メッセージが投稿されたルームのID
+    pub room_id: i32,
+    /// メッセージのID
+    #[serde(default)]
+    pub message_id: Option<String>,
+}
+
+/// Webhookから把握したルームごとの状態です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomState {
+    /// 受信したWebhookの数
+    pub received: u64,
+    /// 受信した自分宛てのメンションの数
+    pub mentions: u64,
+    /// 最後に受信したメッセージのID
+    pub last_message_id: Option<String>,
+    /// 最後に受信したイベントの発生日時（UNIX時間）
+    pub last_event_time: i64,
+    /// 処理待ちかどうか
+    pub pending: bool,
+}
+
+/// ChatworkのWebhookを受信し、処理待ちのルームを管理する構造体です。
+pub struct WebhookReceiver {
+    /// 署名の検証に使用する鍵（Base64デコードしたトークン）
+    key: Vec<u8>,
+    /// ルームごとの状態
+    rooms: Mutex<HashMap<i32, RoomState>>,
+    /// 処理待ちのルームが増えたことを知らせる通知
+    notify: Notify,
+}
+
+impl WebhookReceiver {
+    /// 新しい`WebhookReceiver`インスタンスを作成します。
+    ///
+    /// # 引数
+    ///
+    /// * `token` - ChatworkのWebhook設定で発行されたトークン（Base64）
+    ///
+    /// # エラー
+    ///
+    /// トークンがBase64としてデコードできない場合、`Error`を返します。
+    pub fn new(token: &str) -> Result<Self, Error> {
+        let key = STANDARD
+            .decode(token.trim())
+            .with_context(|| t!("webhook.invalid_token"))?;
+        Ok(Self {
+            key,
+            rooms: Mutex::new(HashMap::new()),
+            notify: Notify::new(),
+        })
+    }
+
+    /// Webhookを受け付けるルーターを作成します。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - Webhookを受け付けるパス
+    pub fn router(self: Arc<Self>, path: &str) -> Router {
+        Router::new()
+            .route(path, post(handle_webhook))
+            .with_state(self)
+    }
+
+    /// 本文の署名を検証します。
+    ///
+    /// 署名は、Base64デコードしたトークンを鍵とする本文のHMAC-SHA256をBase64エンコードしたものです。
+    pub fn verify(&self, body: &[u8], signature: &str) -> bool {
+        let Ok(signature) = STANDARD.decode(signature.trim()) else {
+            return false;
+        };
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMACは任意の長さの鍵を受け付けます");
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// Webhookリクエストを受け付け、ルームの状態を更新します。
+    ///
+    /// # 戻り値
+    ///
+    /// レスポンスのステータスコードを返します。
+    /// 署名が正しくない場合は`401`、本文を解析できない場合は`400`になります。
+    pub fn receive(&self, headers: &HeaderMap, body: &[u8]) -> StatusCode {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if !signature.is_some_and(|signature| self.verify(body, signature)) {
+            warn!("{}", t!("webhook.invalid_signature"));
+            return StatusCode::UNAUTHORIZED;
+        }
+
+        let request: WebhookRequest = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("{}", t!("webhook.invalid_body", error = e));
+                return StatusCode::BAD_REQUEST;
+            }
+        };
+        debug!(
+            "{}",
+            t!(
+                "webhook.received",
+                event_type = request.webhook_event_type,
+                room_id = request.webhook_event.room_id
+            )
+        );
+
+        match request.webhook_event_type.as_str() {
+            "message_created" | "mention_to_me" => {
+                self.record(&request);
+                self.notify.notify_one();
+            }
+            other => debug!("{}", t!("webhook.ignored", event_type = other)),
+        }
+        StatusCode::OK
+    }
+
+    /// 受信したイベントをルームの状態に反映し、ルームを処理待ちにします。
+    fn record(&self, request: &WebhookRequest) {
+        let event = &request.webhook_event;
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let state = rooms.entry(event.room_id).or_default();
+        state.received += 1;
+        if request.webhook_event_type == "mention_to_me" {
+            state.mentions += 1;
+        }
+        if event.message_id.is_some() {
+            state.last_message_id = event.message_id.clone();
+        }
+        state.last_event_time = state.last_event_time.max(request.webhook_event_time);
+        state.pending = true;
+    }
+
+    /// 指定されたルームの状態を返します。
+    pub fn room_state(&self, room_id: i32) -> Option<RoomState> {
+        self.rooms
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&room_id)
+            .cloned()
+    }
+
+    /// 処理待ちのルームのIDを取り出し、処理待ちの状態を解除します。
+    pub fn take_pending(&self) -> Vec<i32> {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let pending: BTreeSet<i32> = rooms
+            .iter_mut()
+            .filter(|(_, state)| state.pending)
+            .map(|(room_id, state)| {
+                state.pending = false;
+                *room_id
+            })
+            .collect();
+        pending.into_iter().collect()
+    }
+
+    /// 処理待ちのルームができるまで待ち、さらに`debounce`だけ待ってから処理待ちのルームを取り出します。
+    ///
+    /// 待っている間に同じルームで受信したWebhookは、まとめて1回の処理になります。
+    pub async fn wait_pending(&self, debounce: Duration) -> Vec<i32> {
+        loop {
+            self.notify.notified().await;
+            tokio::time::sleep(debounce).await;
+            let room_ids = self.take_pending();
+            if !room_ids.is_empty() {
+                info!(
+                    "{}",
+                    t!("webhook.processing", room_ids = format!("{:?}", room_ids))
+                );
+                return room_ids;
+            }
+        }
+    }
+}
+
+/// Webhookリクエストを処理するハンドラーです。
+async fn handle_webhook(
+    State(receiver): State<Arc<WebhookReceiver>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    receiver.receive(&headers, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    /// テスト用のトークン（`secret`をBase64エンコードしたもの）です。
+    const TOKEN: &str = "c2VjcmV0";
+
+    const BODY: &str = r#"{"webhook_setting_id":"1","webhook_event_type":"message_created","webhook_event_time":1700000000,"webhook_event":{"message_id":"100","room_id":10,"account_id":1,"body":"hello","send_time":1700000000,"update_time":0}}"#;
+
+    fn sign(body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(body.as_bytes());
+        STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    fn request(body: &str, signature: Option<&str>) -> Request<Body> {
+        let mut builder = Request::post("/webhook").header("content-type", "application/json");
+        if let Some(signature) = signature {
+            builder = builder.header(SIGNATURE_HEADER, signature);
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        let receiver = WebhookReceiver::new(TOKEN).unwrap();
+        // printf '{}' | openssl dgst -sha256 -hmac secret -binary | base64
+        assert!(receiver.verify(b"{}", "dzJZAsrKgS3CWXM6rNBGtzgXNyx3e42VtAJkdHRRbhM="));
+        assert!(!receiver.verify(b"{ }", "dzJZAsrKgS3CWXM6rNBGtzgXNyx3e42VtAJkdHRRbhM="));
+        assert!(!receiver.verify(b"{}", "not base64"));
+
+        assert!(WebhookReceiver::new("not base64!").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_router_accepts_signed_webhook() {
+        let receiver = Arc::new(WebhookReceiver::new(TOKEN).unwrap());
+        let router = receiver.clone().router("/webhook");
+
+        let response = router
+            .clone()
+            .oneshot(request(BODY, Some(&sign(BODY))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mention = BODY.replace("message_created", "mention_to_me");
+        let response = router
+            .oneshot(request(&mention, Some(&sign(&mention))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            receiver.room_state(10),
+            Some(RoomState {
+                received: 2,
+                mentions: 1,
+                last_message_id: Some("100".to_string()),
+                last_event_time: 1700000000,
+                pending: true,
+            })
+        );
+        assert_eq!(receiver.take_pending(), vec![10]);
+        assert!(receiver.take_pending().is_empty());
+        assert!(!receiver.room_state(10).unwrap().pending);
+    }
+
+    #[tokio::test]
+    async fn test_router_rejects_invalid_webhook() {
+        let receiver = Arc::new(WebhookReceiver::new(TOKEN).unwrap());
+        let router = receiver.clone().router("/webhook");
+
+        let response = router.clone().oneshot(request(BODY, None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router
+            .clone()
+            .oneshot(request(BODY, Some(&sign("{}"))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router
+            .oneshot(request("not json", Some(&sign("not json"))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        assert!(receiver.room_state(10).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_pending_coalesces_rooms() {
+        let receiver = WebhookReceiver::new(TOKEN).unwrap();
+        let mut headers = HeaderMap::new();
+        for body in [
+            BODY.to_string(),
+            BODY.replace("\"room_id\":10", "\"room_id\":20"),
+        ] {
+            headers.insert(SIGNATURE_HEADER, sign(&body).parse().unwrap());
+            assert_eq!(receiver.receive(&headers, body.as_bytes()), StatusCode::OK);
+        }
+        headers.insert(SIGNATURE_HEADER, sign(BODY).parse().unwrap());
+        receiver.receive(&headers, BODY.as_bytes());
+
+        let room_ids = receiver.wait_pending(Duration::from_millis(1)).await;
+        assert_eq!(room_ids, vec![10, 20]);
+        assert_eq!(receiver.room_state(10).unwrap().received, 2);
+    }
+}